@@ -63,10 +63,19 @@ pub fn impl_(input: DeriveInput, list: AttributeList) -> Result<TokenStream> {
 
         impl #ident {
             #[allow(clippy::too_many_arguments)]
-            pub fn builder( #named_fields ) -> crate::pagination::PaginationBuilder {
+            pub fn kind(
+                #named_fields
+            ) -> (crate::pagination::PaginationKind, crate::pagination::Pages) {
                 let pages = crate::pagination::Pages::new(#per_page, #entries);
                 let kind = crate::pagination::PaginationKind:: #variant (Box::new(Self { #token_fields }));
 
+                (kind, pages)
+            }
+
+            #[allow(clippy::too_many_arguments, unused)]
+            pub fn builder( #named_fields ) -> crate::pagination::PaginationBuilder {
+                let (kind, pages) = Self::kind( #token_fields );
+
                 crate::pagination::PaginationBuilder::new(kind, pages)
             }
         }
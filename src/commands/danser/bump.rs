@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use command_macros::SlashCommand;
+use eyre::Result;
+use time::OffsetDateTime;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::embed::EmbedField;
+
+use crate::{
+    core::{
+        locale::Msg,
+        replay_queue::{BumpError, QueueEntry},
+        Context,
+    },
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        Authored, InteractionCommandExt,
+    },
+};
+
+#[derive(CreateCommand, CommandModel, SlashCommand)]
+#[command(
+    name = "bump",
+    help = "Moves a waiting replay of this server to the front of the queue, \
+    right behind the one that's currently rendering. \
+    Specify the replay either by its job id or by its position as shown in `/queue`."
+)]
+#[flags(AUTHORITY, SKIP_DEFER)]
+/// Move a waiting replay of this server to the front of the queue
+pub struct Bump {
+    /// Id of the job to move to the front
+    id: Option<u32>,
+    #[command(min_value = 2)]
+    /// Position in the queue of the job to move to the front
+    position: Option<u32>,
+}
+
+async fn slash_bump(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
+    let Bump { id, position } = Bump::from_interaction(command.input_data())?;
+
+    let guild = match command.guild_id {
+        Some(guild) => guild,
+        None => {
+            let content = "Replays can only be bumped within servers";
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let entry = match (id, position) {
+        (Some(id), None) => QueueEntry::Id(id),
+        (None, Some(position)) => QueueEntry::Position(position as usize),
+        _ => {
+            let content = "Specify either the id or the position of the job";
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let (id, previous) = match ctx.replay_queue.bump(entry, guild).await {
+        Ok(bumped) => bumped,
+        Err(err) => {
+            let locale = ctx.guild_locale(Some(guild));
+
+            let content = match err {
+                BumpError::NotFound => locale.get(Msg::JobNotFound),
+                BumpError::InProgress => "That job is already being processed",
+                BumpError::OtherGuild => "Only replays of this server can be bumped",
+            };
+
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    info!(
+        "User {} bumped job {id} from #{previous} to the front of the queue",
+        command.user_id()?
+    );
+
+    let mut fields = vec![EmbedField {
+        inline: true,
+        name: "Position".to_owned(),
+        value: format!("#{previous} → #2"),
+    }];
+
+    if let Some(wait) = ctx.stats.estimated_wait(1) {
+        let start = OffsetDateTime::now_utc() + wait;
+
+        fields.push(EmbedField {
+            inline: true,
+            name: "Estimated start".to_owned(),
+            value: format!("<t:{}:R>", start.unix_timestamp()),
+        });
+    }
+
+    let embed = EmbedBuilder::new()
+        .title(format!("Bumped job {id}"))
+        .description("The job will be processed right after the current one")
+        .fields(fields);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use std::{fmt::Write, path::PathBuf};
+
+use bytes::Bytes;
+use eyre::{Context as _, Result};
+use osu_db::{Mode, Replay};
+use tokio::fs;
+use twilight_http::response::ResponseFuture;
+use twilight_model::{
+    channel::{Attachment, Message},
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    core::{
+        commands::checks::check_render_cooldown,
+        locale::{Locale, Msg},
+        replay_queue::{parse_replay, unrenderable_mods},
+        BotConfig, Context, ParseWarning, ReplayData, TimePoints,
+    },
+    custom_client::{attachment_error, is_expired_attachment},
+    util::{disk, interaction::InteractionCommand, mods_string, ChannelExt, InteractionCommandExt},
+};
+
+/// Where a render request came from, errors are sent there
+#[derive(Copy, Clone)]
+pub enum Requester<'a> {
+    /// A command that was deferred already
+    Command(&'a InteractionCommand),
+    /// A message in an input channel with auto-render enabled
+    Message(&'a Message),
+}
+
+impl Requester<'_> {
+    fn error(self, ctx: &Context, content: impl Into<String>) -> ResponseFuture<Message> {
+        match self {
+            Self::Command(command) => command.error(ctx, content),
+            Self::Message(msg) => msg.error(ctx, content),
+        }
+    }
+}
+
+/// Returns the reason why the user can't submit a render right now, if any
+pub async fn render_rejection(
+    ctx: &Context,
+    user: Id<UserMarker>,
+    channel: Id<ChannelMarker>,
+    guild: Option<Id<GuildMarker>>,
+) -> Option<String> {
+    let locale = ctx.guild_locale(guild);
+
+    if disk::low_disk_space().is_some() {
+        return Some(locale.get(Msg::LowDiskSpace).to_owned());
+    }
+
+    if let Some(remaining) = check_render_cooldown(ctx, user, channel, guild).await {
+        let cooldown = locale.get(Msg::RenderCooldown);
+
+        return Some(format!("{cooldown} {}s", remaining.as_secs() + 1));
+    }
+
+    if let Some(content) = ctx.replay_queue.capacity_check(&ctx.stats, locale, 1).await {
+        return Some(content);
+    }
+
+    let guild_limit = ctx.guild_queue_limit(guild);
+
+    ctx.replay_queue
+        .guild_capacity_check(guild, guild_limit, locale)
+        .await
+}
+
+/// Downloads the replay and verifies that it's a valid osu!standard play.
+///
+/// If the replay is invalid, an error is sent as response and `None` is returned.
+pub async fn fetch_replay(
+    ctx: &Context,
+    requester: Requester<'_>,
+    attachment: &Attachment,
+) -> Result<Option<(Bytes, Replay)>> {
+    // Discord occasionally serves truncated files so verify the download
+    // and retry once before giving up
+    let mut retried = false;
+
+    let (bytes, replay) = loop {
+        let bytes = match ctx.client().get_discord_attachment(attachment).await {
+            Ok(bytes) => bytes,
+            // Retrying won't bring back an expired url
+            Err(err) if !retried && !is_expired_attachment(&err) => {
+                warn!("{:?}", err.wrap_err("failed to download replay, retrying"));
+                retried = true;
+
+                continue;
+            }
+            Err(err) => {
+                let content = attachment_error(&err, "Failed to download attachment");
+                requester.error(ctx, content).await?;
+
+                return Err(err);
+            }
+        };
+
+        let res = if bytes.len() as u64 != attachment.size {
+            Err(eyre!(
+                "expected {} bytes for replay, got {}",
+                attachment.size,
+                bytes.len()
+            ))
+        } else {
+            parse_replay(&bytes).context("failed to parse .osr file")
+        };
+
+        match res {
+            Ok(replay) => break (bytes, replay),
+            Err(err) if !retried => {
+                warn!("{:?}", err.wrap_err("corrupt replay download, retrying"));
+                retried = true;
+            }
+            Err(err) => {
+                let content = "The replay file appears to be corrupt.\n\
+                    Make sure you upload a valid .osr file and try again.";
+                requester.error(ctx, content).await?;
+
+                return Err(err);
+            }
+        }
+    };
+
+    if replay.mode != Mode::Standard {
+        let content = "danser only accepts osu!standard plays, sorry :(";
+        requester.error(ctx, content).await?;
+
+        return Ok(None);
+    }
+
+    Ok(Some((bytes, replay)))
+}
+
+/// Store the replay in the downloads folder since attachment urls expire.
+///
+/// If it can't be stored, an error is sent as response.
+pub async fn store_replay(
+    ctx: &Context,
+    requester: Requester<'_>,
+    filename: &str,
+    bytes: &[u8],
+) -> Result<PathBuf> {
+    let mut path = BotConfig::get().paths.downloads();
+    path.push(filename);
+
+    if let Err(err) = fs::write(&path, bytes).await {
+        requester.error(ctx, "Failed to store replay file").await?;
+
+        return Err(err).with_context(|| format!("failed writing to file `{path:?}`"));
+    }
+
+    Ok(path)
+}
+
+/// Warnings about the replay that are appended to the response,
+/// each on its own line
+pub fn replay_warnings(
+    ctx: &Context,
+    guild: Option<Id<GuildMarker>>,
+    bytes: &[u8],
+    parse_warnings: Vec<ParseWarning>,
+) -> String {
+    let locale = ctx.guild_locale(guild);
+    let mut warnings = String::new();
+
+    // Lazer replays may contain mods that danser doesn't know about
+    let lazer_mods = unrenderable_mods(bytes);
+
+    if !lazer_mods.is_empty() {
+        let _ = write!(
+            warnings,
+            "\n:warning: {}: `{}`",
+            locale.get(Msg::LazerMods),
+            lazer_mods.join("`, `")
+        );
+    }
+
+    if ctx.parse_warnings(guild) {
+        for warning in parse_warnings {
+            let _ = write!(warnings, "\n:warning: {warning}");
+        }
+    }
+
+    warnings
+}
+
+/// Push the replay to the queue as one job per clip and start the user's cooldown.
+///
+/// Returns the response content that lists the job ids.
+pub async fn enqueue(
+    ctx: &Context,
+    replay_data: ReplayData,
+    segments: Vec<TimePoints>,
+    locale: Locale,
+    warnings: &str,
+) -> String {
+    let mods = mods_string(replay_data.replay.mods);
+    ctx.render_cooldowns.start(replay_data.user);
+
+    let clip_count = segments.len();
+    let mut job_ids = String::new();
+
+    // Each highlight clip is rendered as its own job
+    for (i, time_points) in segments.into_iter().enumerate() {
+        let mut clip = replay_data.clone();
+        clip.time_points = time_points;
+
+        if i > 0 {
+            clip.id = ctx.replay_queue.next_id();
+            job_ids.push_str(", ");
+        }
+
+        if clip_count > 1 {
+            let label = format!("clip {}/{clip_count}", i + 1);
+
+            clip.title = Some(match clip.title {
+                Some(title) => format!("{title} ({label})"),
+                None => label,
+            });
+        }
+
+        let _ = write!(job_ids, "`{}`", clip.id);
+        ctx.replay_queue.push(clip).await;
+    }
+
+    format!(
+        "{pushed} `+{mods}`\n{job_id}: {job_ids}{warnings}",
+        pushed = locale.get(Msg::ReplayPushed),
+        job_id = locale.get(Msg::JobId),
+    )
+}
@@ -1,11 +1,18 @@
 pub use self::{
-    queue::*, render::*, render_from_bathbot_embed::*, settings::*, setup::*, skin::*, skin_list::*
+    bump::*, enqueue::*, queue::*, recent::*, render::*, render_from_bathbot_embed::*, reupload::*,
+    settings::*, setup::*, skin::*, skin_list::*, skin_preview::*, trim::*,
 };
 
-mod render_from_bathbot_embed;
+mod bump;
+mod enqueue;
 mod queue;
+mod recent;
 mod render;
+mod render_from_bathbot_embed;
+mod reupload;
 mod settings;
 mod setup;
 mod skin;
 mod skin_list;
+mod skin_preview;
+mod trim;
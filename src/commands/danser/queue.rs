@@ -10,11 +10,11 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::channel::embed::EmbedField;
 
 use crate::{
-    core::{BotConfig, Context, ReplayStatus},
+    core::{locale::Msg, BotConfig, Context, ReplayStatus},
     util::{
         builder::{EmbedBuilder, MessageBuilder},
         interaction::InteractionCommand,
-        InteractionCommandExt,
+        mods_string, Authored, InteractionCommandExt,
     },
 };
 
@@ -22,50 +22,87 @@ use crate::{
 #[command(name = "queue")]
 #[flags(SKIP_DEFER)]
 /// Displays the current replay queue
-pub struct Queue;
+pub struct Queue {
+    /// Only show the status of the job with this id
+    id: Option<u32>,
+}
+
+async fn slash_queue(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
+    let Queue { id } = Queue::from_interaction(command.input_data())?;
+
+    if let Some(id) = id {
+        return job_status(&ctx, &command, id).await;
+    }
 
-async fn slash_queue(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let locale = ctx.guild_locale(command.guild_id);
+    let compact = ctx.compact_embeds(command.user_id()?, command.guild_id);
     let queue_guard = ctx.replay_queue.queue.lock().await;
     let status = *ctx.replay_queue.status.lock().await;
 
     let mut embed = EmbedBuilder::new()
-        .title("Current queue")
-        .timestamp(OffsetDateTime::now_utc());
+        .title(locale.get(Msg::QueueTitle))
+        .timestamp(OffsetDateTime::now_utc())
+        .compact(compact);
 
     let mut iter = queue_guard.iter();
 
     if let Some(data) = iter.next() {
-        let name = "Progress".to_owned();
-
-        let value = format!(
-            "<@{user}>: {name}\n\
-            • Downloading: {downloading}\n\
-            • Rendering: {rendering}\n\
-            • Encoding: {encoding}\n\
-            • Uploading: {uploading}",
-            user = data.user,
-            name = data.replay_name(),
-            downloading = if let ReplayStatus::Downloading = status {
-                ProcessStatus::Running(None)
-            } else {
-                ProcessStatus::Done
-            },
-            rendering = match status {
-                ReplayStatus::Downloading => ProcessStatus::Waiting,
-                ReplayStatus::Rendering(progress) => ProcessStatus::Running(Some(progress)),
-                _ => ProcessStatus::Done,
-            },
-            encoding = match status {
-                ReplayStatus::Encoding(progress) => ProcessStatus::Running(Some(progress)),
-                ReplayStatus::Uploading => ProcessStatus::Done,
-                _ => ProcessStatus::Waiting,
-            },
-            uploading = if let ReplayStatus::Uploading = status {
-                ProcessStatus::Running(None)
-            } else {
-                ProcessStatus::Waiting
-            },
-        );
+        let name = locale.get(Msg::QueueProgress).to_owned();
+
+        let compact_value = || {
+            let step = match status {
+                ReplayStatus::Downloading => locale.get(Msg::StatusDownloading).to_owned(),
+                ReplayStatus::Rendering(progress) => {
+                    format!("{} ({progress}%)", locale.get(Msg::StatusRendering))
+                }
+                ReplayStatus::Encoding(progress) => {
+                    format!("{} ({progress}%)", locale.get(Msg::StatusEncoding))
+                }
+                ReplayStatus::Uploading => locale.get(Msg::StatusUploading).to_owned(),
+                ReplayStatus::Waiting | ReplayStatus::Queued(_) => status.to_string(),
+            };
+
+            format!("<@{}>: {step}", data.user)
+        };
+
+        let full_value = || {
+            format!(
+                "<@{user}>: {name} +{mods}\n\
+                • {downloading_label}: {downloading}\n\
+                • {rendering_label}: {rendering}\n\
+                • {encoding_label}: {encoding}\n\
+                • {uploading_label}: {uploading}",
+                user = data.user,
+                name = data.display_title(),
+                mods = mods_string(data.replay.mods),
+                downloading_label = locale.get(Msg::StatusDownloading),
+                rendering_label = locale.get(Msg::StatusRendering),
+                encoding_label = locale.get(Msg::StatusEncoding),
+                uploading_label = locale.get(Msg::StatusUploading),
+                downloading = if let ReplayStatus::Downloading = status {
+                    ProcessStatus::Running(None)
+                } else {
+                    ProcessStatus::Done
+                },
+                rendering = match status {
+                    ReplayStatus::Downloading => ProcessStatus::Waiting,
+                    ReplayStatus::Rendering(progress) => ProcessStatus::Running(Some(progress)),
+                    _ => ProcessStatus::Done,
+                },
+                encoding = match status {
+                    ReplayStatus::Encoding(progress) => ProcessStatus::Running(Some(progress)),
+                    ReplayStatus::Uploading => ProcessStatus::Done,
+                    _ => ProcessStatus::Waiting,
+                },
+                uploading = if let ReplayStatus::Uploading = status {
+                    ProcessStatus::Running(None)
+                } else {
+                    ProcessStatus::Waiting
+                },
+            )
+        };
+
+        let value = embed.detail(full_value, compact_value);
 
         let mut fields = vec![EmbedField {
             inline: false,
@@ -74,13 +111,23 @@ async fn slash_queue(ctx: Arc<Context>, command: InteractionCommand) -> Result<(
         }];
 
         if let Some(data) = iter.next() {
-            let name = "Upcoming".to_owned();
+            let name = locale.get(Msg::QueueUpcoming).to_owned();
             let mut value = String::with_capacity(128);
 
-            let _ = writeln!(value, "`2.` <@{}>: {}", data.user, data.replay_name());
-
-            for (data, idx) in iter.zip(3..) {
-                let _ = writeln!(value, "`{idx}.` <@{}>: {}", data.user, data.replay_name());
+            for (data, idx) in std::iter::once(data).chain(iter).zip(2..) {
+                let entry = embed.detail(
+                    || {
+                        format!(
+                            "<@{}>: {} +{}",
+                            data.user,
+                            data.display_title(),
+                            mods_string(data.replay.mods),
+                        )
+                    },
+                    || format!("<@{}>", data.user),
+                );
+
+                let _ = writeln!(value, "`{idx}.` {entry}");
             }
 
             fields.push(EmbedField {
@@ -92,7 +139,7 @@ async fn slash_queue(ctx: Arc<Context>, command: InteractionCommand) -> Result<(
 
         embed = embed.fields(fields);
     } else {
-        embed = embed.description("The queue is empty");
+        embed = embed.description(locale.get(Msg::QueueEmpty));
     }
 
     let builder = MessageBuilder::new().embed(embed);
@@ -101,6 +148,56 @@ async fn slash_queue(ctx: Arc<Context>, command: InteractionCommand) -> Result<(
     Ok(())
 }
 
+async fn job_status(ctx: &Context, command: &InteractionCommand, id: u32) -> Result<()> {
+    let locale = ctx.guild_locale(command.guild_id);
+
+    let status = match ctx.replay_queue.entry_status(id).await {
+        Some(status) => status,
+        None => {
+            let content = locale.get(Msg::JobNotFound);
+            command.error_callback(ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let position = match status {
+        ReplayStatus::Queued(position) => position,
+        _ => 1,
+    };
+
+    let mut fields = vec![EmbedField {
+        inline: true,
+        name: locale.get(Msg::JobPosition).to_owned(),
+        value: format!("#{position}"),
+    }];
+
+    // Only entries that are still waiting have a start to estimate
+    if let Some(wait) = ctx
+        .stats
+        .estimated_wait(position - 1)
+        .filter(|_| position > 1)
+    {
+        let start = OffsetDateTime::now_utc() + wait;
+
+        fields.push(EmbedField {
+            inline: true,
+            name: locale.get(Msg::JobStart).to_owned(),
+            value: format!("<t:{}:R>", start.unix_timestamp()),
+        });
+    }
+
+    let embed = EmbedBuilder::new()
+        .title(format!("{}: {id}", locale.get(Msg::JobId)))
+        .description(status.to_string())
+        .fields(fields);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(ctx, builder, true).await?;
+
+    Ok(())
+}
+
 enum ProcessStatus {
     Done,
     Running(Option<u8>),
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use command_macros::SlashCommand;
+use eyre::{ContextCompat, Result};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::Context,
+    pagination::{PaginationOptions, RecentRendersPagination},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+#[derive(CreateCommand, CommandModel, SlashCommand)]
+#[command(name = "recent", dm_permission = false)]
+#[flags(SKIP_DEFER)]
+/// Browse the most recently rendered replays of this server
+pub struct Recent;
+
+async fn slash_recent(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let renders = ctx.render_history.recent(guild_id);
+
+    if renders.is_empty() {
+        let builder = MessageBuilder::new().embed("No replays have been rendered here recently");
+        command.callback(&ctx, builder, false).await?;
+
+        return Ok(());
+    }
+
+    let (kind, pages) = RecentRendersPagination::kind(renders);
+
+    ctx.paginate(command, kind, pages, PaginationOptions::default())
+        .await
+}
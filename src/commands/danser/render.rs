@@ -1,19 +1,45 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use command_macros::SlashCommand;
-use eyre::{Context as _, Result};
-use osu_db::{Mode, Replay};
-use tokio::{fs::File, io::AsyncWriteExt};
-use twilight_interactions::command::{CommandModel, CreateCommand};
-use twilight_model::channel::Attachment;
+use eyre::{Report, Result};
+use osu_db::Replay;
+use tokio::time::{interval, Duration};
+use tracing::Span;
+use twilight_interactions::command::{CommandModel, CreateCommand, ResolvedUser};
+use twilight_model::{
+    channel::{embed::EmbedField, Attachment},
+    id::{marker::ChannelMarker, Id},
+};
 
 use crate::{
-    core::{BotConfig, Context, ReplayData, TimePoints},
+    core::{
+        commands::checks::check_authority,
+        locale::Msg,
+        replay_queue::{
+            skin_ini::{self, SkinIniOverrides},
+            InvalidMapset,
+        },
+        settings::DanserSettings,
+        ComparedReplay, Context, HighlightMode, LocalMap, PitchMode, PlaybackSpeed,
+        PreparedSettings, QualityPreset, RenderFor, RenderOptions, ReplayData, ReplayQueue,
+        ReplaySlim, ReplayStatus, Resolution, TimePoints, VisualMods,
+    },
+    custom_client::{attachment_error, DownloadFailure},
     util::{
-        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        mods_string, Authored, InteractionCommandExt,
     },
 };
 
+use super::{
+    enqueue::{enqueue, fetch_replay, render_rejection, replay_warnings, store_replay, Requester},
+    trim::start_trim,
+};
+
+/// Placeholder for paths of the host that don't belong in a discord message
+const REDACTED: &str = "<redacted>";
+
 #[derive(CreateCommand, CommandModel, SlashCommand)]
 #[command(name = "render")]
 #[flags(SKIP_DEFER)]
@@ -22,28 +48,193 @@ pub struct Render {
     #[command(name = "replay")]
     /// Specify the replay through a .osr file
     attachment: Attachment,
+    /// Specify the map through a .osz file if it's not submitted
+    beatmap: Option<Attachment>,
+    #[command(min_value = 1)]
+    /// Render on this map id instead of the replay's map, e.g. another difficulty
+    map_id: Option<u32>,
     #[command(min_value = 0, max_value = 65_535)]
     /// Specify a start timestamp in minutes and seconds
     start: Option<String>,
     #[command(min_value = 0, max_value = 65_535)]
     /// Specify an end timestamp in minutes and seconds
     end: Option<String>,
+    /// Only render short clips around the play's misses
+    highlight: Option<HighlightMode>,
+    #[command(min_value = 0, max_value = 100)]
+    /// Percent background dim for this render only
+    dim: Option<u8>,
+    #[command(min_value = 0.1, max_value = 2.0)]
+    /// Scale the size of the cursor for this render only
+    cursor_scale: Option<f64>,
+    #[command(min_value = 0, max_value = 100)]
+    /// Percent master volume for this render only
+    master_volume: Option<u8>,
+    #[command(min_value = 0, max_value = 100)]
+    /// Percent music volume for this render only
+    music_volume: Option<u8>,
+    #[command(min_value = 0, max_value = 100)]
+    /// Percent hitsound volume for this render only
+    hitsound_volume: Option<u8>,
+    /// Render without any audio
+    no_audio: Option<bool>,
+    /// Higher quality means larger files and longer renders, defaults to balanced
+    quality: Option<QualityPreset>,
+    /// Resolution for this render only, e.g. 1080p, vertical, or 1080x1920
+    resolution: Option<String>,
+    /// Force the storyboard on or off for this render only
+    storyboard: Option<bool>,
+    #[command(min_value = 0.5, max_value = 2.0)]
+    /// Playback speed on top of the replay's mods, e.g. 1.25
+    speed: Option<f64>,
+    /// How the music's pitch reacts to the speed, defaults to corrected
+    pitch: Option<PitchMode>,
+    /// Show these visual mods instead of the replay's, e.g. HD or NM to hide them
+    visual_mods: Option<String>,
+    /// A skin.ini with keys that override those of the skin for this render only
+    skin_ini: Option<Attachment>,
+    /// A second replay of the same map to render alongside for comparison
+    compare: Option<Attachment>,
+    /// A short note to tell your renders apart, e.g. "my new PB"
+    title: Option<String>,
+    /// Render on behalf of this user, e.g. someone you coach
+    for_user: Option<ResolvedUser>,
+    /// Adjust the start and end with buttons before the render is queued
+    trim: Option<bool>,
+    /// Only validate the render and show what would happen without queueing it
+    dry_run: Option<bool>,
 }
 
 pub async fn slash_render(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
     let Render {
         attachment,
+        beatmap,
+        map_id,
         start,
         end,
+        highlight,
+        dim,
+        cursor_scale,
+        master_volume,
+        music_volume,
+        hitsound_volume,
+        no_audio,
+        quality,
+        resolution,
+        storyboard,
+        speed,
+        pitch,
+        visual_mods,
+        skin_ini,
+        compare,
+        title,
+        for_user,
+        trim,
+        dry_run,
     } = Render::from_interaction(command.input_data())?;
 
-    if !matches!(attachment.filename.split('.').last(), Some("osr")) {
+    Span::current().record("replay", &attachment.filename.as_str());
+
+    let is_replay_file = |file: &Attachment| matches!(file.filename.split('.').last(), Some("osr"));
+
+    if !is_replay_file(&attachment) || !compare.as_ref().map_or(true, is_replay_file) {
         let content = "The attachment must be a .osr file!";
         command.error_callback(&ctx, content, true).await?;
 
         return Ok(());
     }
 
+    // Only a full mapset comes with the map's audio and background
+    let is_mapset = |beatmap: &Attachment| beatmap.filename.ends_with(".osz");
+
+    if !beatmap.as_ref().map_or(true, is_mapset) {
+        let content = "The beatmap must be a .osz file!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if let Some(ref file) = skin_ini {
+        if !file.filename.ends_with(".ini") {
+            let content = "The skin.ini overrides must be a .ini file!";
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        } else if file.size > skin_ini::MAX_SIZE {
+            let content = format!(
+                "The skin.ini overrides must not be larger than {}KB",
+                skin_ini::MAX_SIZE / 1024
+            );
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    }
+
+    if beatmap.is_some() && map_id.is_some() {
+        let content = "Specify either a beatmap file or a map id, not both";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if compare.is_some() && map_id.is_some() {
+        let content = "Comparisons can only be rendered on the replays' own map";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if pitch.is_some() && speed.is_none() {
+        let content = "The pitch can only be chosen alongside a speed";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if visual_mods.is_some() && compare.is_some() {
+        let content = "Visual mods can't be overridden for comparisons";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if highlight.is_some() && (start.is_some() || end.is_some() || compare.is_some()) {
+        let content = "Highlights can't be combined with a start, end, or comparison";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let trim = trim.unwrap_or(false);
+
+    if trim && highlight.is_some() {
+        let content = "Highlights can't be trimmed";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if map_id == Some(0) {
+        let content = "The map id must be positive";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if for_user.as_ref().map_or(false, |user| user.resolved.bot) {
+        let content = "Renders can't be made for bots";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    // Rendering for yourself is the same as not specifying anyone
+    let submitter = command.user_id()?;
+    let render_for = for_user
+        .filter(|user| user.resolved.id != submitter)
+        .map(|user| RenderFor::new(&user.resolved));
+
     let start_in_seconds = if let Some(start) = start {
         match TimePoints::parse_single(&start) {
             Ok(start) => start,
@@ -68,107 +259,834 @@ pub async fn slash_render(ctx: Arc<Context>, mut command: InteractionCommand) ->
         0
     };
 
-    let output_channel = match command.guild_id {
-        Some(guild) => {
-            // Returns the output channel if:
-            // - Settings of the server are stored
-            // - The server's input channels include the current channel
-            // - The server's output channel has been configured
-            let check = ctx.guild_settings(guild, |server| {
-                server
-                    .input_channels
-                    .contains(&command.channel_id)
-                    .then_some(server.output_channel)
-                    .ok_or(())
-            });
+    let visual_mods = match visual_mods.as_deref().map(VisualMods::parse) {
+        Some(Ok(mods)) => Some(mods),
+        Some(Err(err)) => {
+            command.error_callback(&ctx, err, true).await?;
 
-            match check {
-                Some(Ok(Some(output_channel))) => output_channel,
-                Some(Err(_)) => {
-                    let content = "This channel is not setup as input channel.\n\
-                        Check out `/setup` for more info.";
-                    command.error_callback(&ctx, content, true).await?;
+            return Ok(());
+        }
+        None => None,
+    };
 
-                    return Ok(());
-                }
-                Some(Ok(None)) | None => {
-                    let content =
-                        "Looks like this server has not setup their output channel yet.\n\
-                        Be sure to use `/setup` first.";
-                    command.error_callback(&ctx, content, false).await?;
+    let resolution = match resolution.as_deref().map(Resolution::parse) {
+        Some(Ok(resolution)) => Some(resolution),
+        Some(Err(err)) => {
+            command.error_callback(&ctx, err, true).await?;
 
-                    return Ok(());
-                }
-            }
+            return Ok(());
         }
-        None => command.channel_id,
+        None => None,
+    };
+
+    let options = RenderOptions {
+        background_dim: dim,
+        cursor_scale,
+        skin: None,
+        master_volume,
+        music_volume,
+        hitsound_volume,
+        no_audio: no_audio.unwrap_or(false),
+        quality: Some(quality.unwrap_or_default()),
+        resolution,
+        storyboard,
+        speed: speed.map(|rate| PlaybackSpeed {
+            rate,
+            pitch: pitch.unwrap_or_default(),
+        }),
+        visual_mods,
     };
 
+    if let Err(err) = options.validate() {
+        command.error_callback(&ctx, err, true).await?;
+
+        return Ok(());
+    }
+
+    let output_channel = match render_output_channel(&ctx, &command).await? {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+
+    let rejection = render_rejection(&ctx, submitter, command.channel_id, command.guild_id);
+
+    if let Some(content) = rejection.await {
+        command.error_callback(&ctx, content, false).await?;
+
+        return Ok(());
+    }
+
+    let locale = ctx.guild_locale(command.guild_id);
+    let requester = Requester::Command(&command);
+
     command.defer(&ctx, false).await?;
 
-    let bytes = match ctx.client().get_discord_attachment(&attachment).await {
-        Ok(bytes) => bytes,
-        Err(err) => {
-            command.error(&ctx, "Failed to download attachment").await?;
+    let (bytes, replay) = match fetch_replay(&ctx, requester, &attachment).await? {
+        Some(tuple) => tuple,
+        None => return Ok(()),
+    };
 
-            return Err(err);
+    // Without the map's hash the map has to be given explicitly
+    if replay.beatmap_hash.is_none() {
+        let content = if compare.is_some() {
+            Some("Comparisons require replays that contain the hash of their map")
+        } else if beatmap.is_none() && map_id.is_none() {
+            Some(
+                "The replay does not contain the hash of its map so it's unknown \
+                which map to render on.\n\
+                Attach the map's .osz file as `beatmap` or specify its `map_id`.",
+            )
+        } else {
+            None
+        };
+
+        if let Some(content) = content {
+            command.error(&ctx, content).await?;
+
+            return Ok(());
         }
+    }
+
+    let compared = match compare {
+        Some(ref file) => match fetch_replay(&ctx, requester, file).await? {
+            Some((bytes, compared)) if compared.beatmap_hash == replay.beatmap_hash => {
+                Some((bytes, compared))
+            }
+            Some(_) => {
+                let content = "Both replays must be set on the same map";
+                command.error(&ctx, content).await?;
+
+                return Ok(());
+            }
+            None => return Ok(()),
+        },
+        None => None,
     };
 
-    let replay = match Replay::from_bytes(&bytes) {
-        Ok(replay) => replay,
-        Err(err) => {
-            let content = "Failed to parse the .osr file. Did you give a valid replay file?";
+    let skin_ini = match skin_ini {
+        Some(ref file) => match fetch_skin_ini(&ctx, &command, file).await? {
+            Some(overrides) => Some(overrides),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
+    let local_map = match (beatmap, map_id) {
+        (Some(beatmap), _) => match verify_local_map(&ctx, &command, &beatmap, &replay).await? {
+            Some(local_map) => Some(local_map),
+            None => return Ok(()),
+        },
+        (None, Some(map_id)) => match fetch_map_override(&ctx, &command, map_id).await? {
+            Some(local_map) => Some(local_map),
+            None => return Ok(()),
+        },
+        (None, None) => None,
+    };
+
+    // Overriding the map is allowed to mismatch but the user should know
+    let hash_mismatch = map_id.is_some()
+        && local_map
+            .as_ref()
+            .zip(replay.beatmap_hash.as_deref())
+            .map_or(false, |(local_map, hash)| !local_map.matches_hash(hash));
+
+    let time_points = TimePoints {
+        start: start_in_seconds,
+        end: end_in_seconds,
+    };
+
+    let segments = match highlight {
+        Some(mode) => match highlight_segments(&ctx, &command, mode, &replay).await? {
+            Some(segments) => segments,
+            None => return Ok(()),
+        },
+        None => vec![time_points],
+    };
+
+    // Prechecks only made room for a single clip
+    if segments.len() > 1 {
+        let capacity_check = ctx
+            .replay_queue
+            .capacity_check(&ctx.stats, locale, segments.len())
+            .await;
+
+        if let Some(content) = capacity_check {
             command.error(&ctx, content).await?;
 
-            return Err(err).context("failed to parse .osr file");
+            return Ok(());
         }
-    };
+    }
 
-    if replay.mode != Mode::Standard {
-        let content = "danser only accepts osu!standard plays, sorry :(";
-        command.error(&ctx, content).await?;
+    if let Some(guild) = command.guild_id.filter(|_| segments.len() > 1) {
+        let guild_limit = ctx.guild_queue_limit(Some(guild));
+        let count = ctx.replay_queue.guild_count(guild).await;
 
-        return Ok(());
+        if count + segments.len() > guild_limit {
+            let content = ReplayQueue::guild_queue_full(count, guild_limit, locale);
+            command.error(&ctx, content).await?;
+
+            return Ok(());
+        }
+    }
+
+    if dry_run.unwrap_or(false) {
+        let replay = ReplaySlim::from(replay);
+
+        return dry_run_summary(
+            &ctx,
+            &command,
+            &replay,
+            local_map.as_ref(),
+            &segments,
+            &options,
+            skin_ini.is_some(),
+            output_channel,
+        )
+        .await;
     }
 
-    let config = BotConfig::get();
-    let mut replay_file = config.paths.downloads();
-    replay_file.push(attachment.filename);
+    // The editor shows the map's length which is looked up before the map is stored
+    let trim_map = if trim {
+        let hash = replay.beatmap_hash.as_deref();
 
-    let mut file = match File::create(&replay_file).await {
-        Ok(file) => file,
+        match map_summary(&ctx, &command, hash, local_map.as_ref()).await? {
+            Some(summary) => Some(summary),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+
+    let local_map = match local_map.map(LocalMap::store).transpose() {
+        Ok(path) => path,
         Err(err) => {
-            command.error(&ctx, "Failed to store replay file").await?;
+            command.error(&ctx, "Failed to store beatmap file").await?;
 
-            return Err(err).with_context(|| format!("failed to create file `{replay_file:?}`"));
+            return Err(err);
         }
     };
 
-    if let Err(err) = file.write_all(&bytes).await {
-        command.error(&ctx, "Failed to store replay file").await?;
+    let replay_file = store_replay(&ctx, requester, &attachment.filename, &bytes).await?;
+    let id = ctx.replay_queue.next_id();
 
-        return Err(err).with_context(|| format!("failed writing to file `{replay_file:?}`"));
+    let comparison = match compared {
+        Some((bytes, compared)) => {
+            let filename = format!("compare-{id}.osr");
+            let path = store_replay(&ctx, requester, &filename, &bytes).await?;
+
+            Some(ComparedReplay {
+                path,
+                replay: compared.into(),
+            })
+        }
+        None => None,
     };
 
+    let (replay, parse_warnings) = ReplaySlim::with_warnings(replay);
+
     let replay_data = ReplayData {
+        id,
+        comparison,
+        guild: command.guild_id,
         input_channel: command.channel_id,
+        interaction_token: Some(command.token.clone()),
+        local_map,
         output_channel,
+        options,
         path: replay_file,
-        replay: replay.into(),
-        time_points: TimePoints {
-            start: start_in_seconds,
-            end: end_in_seconds,
-        },
-        user: command.user_id()?,
+        queued_at: Instant::now(),
+        render_for,
+        replay,
+        skin_ini,
+        time_points,
+        title: title.as_deref().and_then(ReplayData::sanitize_title),
+        user: submitter,
+    };
+
+    let mut warnings = String::new();
+
+    if hash_mismatch {
+        warnings.push_str("\n:warning: ");
+        warnings.push_str(locale.get(Msg::MapHashMismatch));
+    }
+
+    warnings.push_str(&replay_warnings(
+        &ctx,
+        command.guild_id,
+        &bytes,
+        parse_warnings,
+    ));
+
+    if let Some((map_title, map_seconds)) = trim_map {
+        return start_trim(ctx, command, replay_data, map_title, map_seconds, warnings).await;
+    }
+
+    let content = enqueue(&ctx, replay_data, segments, locale, &warnings).await;
+
+    tokio::spawn(track_queue_status(ctx, command, id, content));
+
+    Ok(())
+}
+
+/// Downloads the uploaded map and verifies that it matches the replay.
+///
+/// If the map is invalid, an error is sent as response and `None` is returned.
+async fn verify_local_map(
+    ctx: &Context,
+    command: &InteractionCommand,
+    beatmap: &Attachment,
+    replay: &Replay,
+) -> Result<Option<LocalMap>> {
+    let bytes = match ctx.client().get_discord_attachment(beatmap).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let content = attachment_error(&err, "Failed to download beatmap");
+            command.error(ctx, content).await?;
+
+            return Err(err);
+        }
+    };
+
+    let hash = replay.beatmap_hash.as_deref();
+
+    match LocalMap::from_upload(bytes.to_vec(), hash) {
+        Ok(local_map) => Ok(Some(local_map)),
+        Err(err) if err.is::<InvalidMapset>() => {
+            command.error(ctx, err.to_string()).await?;
+
+            Ok(None)
+        }
+        Err(err) => {
+            command
+                .error(ctx, "Failed to read the uploaded beatmap")
+                .await?;
+
+            Err(err.wrap_err("failed to read uploaded beatmap"))
+        }
+    }
+}
+
+/// Downloads the skin.ini overrides and validates them.
+///
+/// If they're invalid, an error is sent as response and `None` is returned.
+async fn fetch_skin_ini(
+    ctx: &Context,
+    command: &InteractionCommand,
+    attachment: &Attachment,
+) -> Result<Option<SkinIniOverrides>> {
+    let bytes = match ctx.client().get_discord_attachment(attachment).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let content = attachment_error(&err, "Failed to download skin.ini overrides");
+            command.error(ctx, content).await?;
+
+            return Err(err);
+        }
+    };
+
+    let content = match std::str::from_utf8(&bytes) {
+        Ok(content) => content,
+        Err(_) => {
+            let content = "The skin.ini overrides must be valid UTF-8";
+            command.error(ctx, content).await?;
+
+            return Ok(None);
+        }
     };
 
-    ctx.replay_queue.push(replay_data).await;
+    match SkinIniOverrides::parse(content) {
+        Ok(overrides) => Ok(Some(overrides)),
+        Err(reason) => {
+            let content = format!("Invalid skin.ini overrides: {reason}");
+            command.error(ctx, content).await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Downloads the mapset of the map id that overrides the replay's map
+/// so that it's rendered with its audio and background.
+///
+/// If the map or its mapset can't be retrieved, an error is
+/// sent as response and `None` is returned.
+async fn fetch_map_override(
+    ctx: &Context,
+    command: &InteractionCommand,
+    map_id: u32,
+) -> Result<Option<LocalMap>> {
+    let bytes = match ctx.client().get_map_file(map_id).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let content = match DownloadFailure::from_report(&err) {
+                DownloadFailure::NotFound => {
+                    let content =
+                        format!("Map {map_id} could not be found, it might be private or deleted");
+                    command.error(ctx, content).await?;
+
+                    return Ok(None);
+                }
+                DownloadFailure::Ratelimited => {
+                    "osu! is receiving too many requests right now, try again in a minute"
+                }
+                DownloadFailure::ErrorPage => {
+                    "osu! kept responding with an error page instead of the map, \
+                    it's likely busy so try again later"
+                }
+                DownloadFailure::Other => "Failed to download beatmap",
+            };
+
+            command.error(ctx, content).await?;
+
+            return Err(err.wrap_err(format!("failed to download map {map_id}")));
+        }
+    };
+
+    if bytes.is_empty() {
+        let content = format!("There is no map with id {map_id}");
+        command.error(ctx, content).await?;
+
+        return Ok(None);
+    }
+
+    // Mirrors only serve whole mapsets so the hash picks the difficulty
+    let hash = format!("{:x}", md5::compute(&bytes));
+
+    let mapset_id = match ctx.osu().beatmap().map_id(map_id).await {
+        Ok(map) => map.mapset_id,
+        Err(err) => {
+            command.error(ctx, "Failed to retrieve the map").await?;
+
+            let err = Report::from(err).wrap_err(format!("failed to request map {map_id}"));
+
+            return Err(err);
+        }
+    };
+
+    let kitsu = ctx
+        .client()
+        .download_kitsu_mapset(mapset_id)
+        .await
+        .and_then(|bytes| LocalMap::from_upload(bytes.to_vec(), Some(&hash)));
+
+    let res = match kitsu {
+        Ok(local_map) => Ok(local_map),
+        Err(err) => {
+            let err = err.wrap_err(format!("failed to get mapset {mapset_id} from kitsu"));
+            warn!("{err:?}");
+
+            ctx.client()
+                .download_chimu_mapset(mapset_id)
+                .await
+                .and_then(|bytes| LocalMap::from_upload(bytes.to_vec(), Some(&hash)))
+        }
+    };
+
+    match res {
+        Ok(local_map) => Ok(Some(local_map)),
+        Err(err) => {
+            let content = "Failed to download the map's mapset. \
+                Mirrors are likely down or outdated, try again later.";
+            command.error(ctx, content).await?;
+
+            Err(err.wrap_err(format!("failed to get mapset {mapset_id} from chimu")))
+        }
+    }
+}
+
+/// Time windows of the clips around the replay's misses.
+///
+/// If there are none, an error is sent as response and `None` is returned.
+async fn highlight_segments(
+    ctx: &Context,
+    command: &InteractionCommand,
+    mode: HighlightMode,
+    replay: &Replay,
+) -> Result<Option<Vec<TimePoints>>> {
+    if replay.count_miss == 0 {
+        command
+            .error(ctx, "The play has no misses to highlight")
+            .await?;
+
+        return Ok(None);
+    }
+
+    let segments = replay
+        .life_graph
+        .as_deref()
+        .map(|life_graph| mode.windows(life_graph, replay.count_miss as usize))
+        .unwrap_or_default();
+
+    if segments.is_empty() {
+        let content = "Could not locate the misses, the replay is missing its life bar graph";
+        command.error(ctx, content).await?;
+
+        return Ok(None);
+    }
+
+    Ok(Some(segments))
+}
+
+/// The title and length in seconds of the given map or the one the replay was set on.
+///
+/// If the map can't be retrieved, an error is sent as response and `None` is returned.
+async fn map_summary(
+    ctx: &Context,
+    command: &InteractionCommand,
+    hash: Option<&str>,
+    local_map: Option<&LocalMap>,
+) -> Result<Option<(String, u32)>> {
+    if let Some(local_map) = local_map {
+        return Ok(Some((local_map.title(), local_map.seconds_total())));
+    }
+
+    let hash = match hash {
+        Some(hash) => hash,
+        None => {
+            let content = "Missing the beatmap hash in the replay file";
+            command.error(ctx, content).await?;
+
+            return Ok(None);
+        }
+    };
+
+    let map = match ctx.osu().beatmap().checksum(hash).await {
+        Ok(map) => map,
+        Err(err) => {
+            let content = "Failed to retrieve map. Maybe it's not submitted?";
+            command.error(ctx, content).await?;
+
+            let err =
+                Report::from(err).wrap_err(format!("failed to request map with hash `{hash}`"));
+
+            return Err(err);
+        }
+    };
+
+    let map_title = match map.mapset {
+        Some(ref mapset) => format!("{} - {} [{}]", mapset.artist, mapset.title, map.version),
+        None => format!("[{}]", map.version),
+    };
+
+    Ok(Some((map_title, map.seconds_total)))
+}
+
+/// Responds with a summary of what rendering the replay would look like
+/// without queueing it or touching any files.
+///
+/// Authorities also get the resolved danser settings, e.g. to debug why a render looks off.
+#[allow(clippy::too_many_arguments)]
+async fn dry_run_summary(
+    ctx: &Context,
+    command: &InteractionCommand,
+    replay: &ReplaySlim,
+    local_map: Option<&LocalMap>,
+    segments: &[TimePoints],
+    options: &RenderOptions,
+    skin_ini: bool,
+    output_channel: Id<ChannelMarker>,
+) -> Result<()> {
+    let hash = replay.beatmap_hash.as_deref();
+
+    let (map_title, seconds_total) = match map_summary(ctx, command, hash, local_map).await? {
+        Some(summary) => summary,
+        None => return Ok(()),
+    };
+
+    let user = command.user_id()?;
+
+    // The skin.ini overrides are left out since their copy of the skin would
+    // be removed right away, the job id is irrelevant without them
+    let prepared = ctx.prepare_render_settings(user, command.guild_id, options, None, 0);
+
+    let PreparedSettings {
+        mut settings,
+        broken_skin,
+        ..
+    } = match prepared {
+        Ok(prepared) => prepared,
+        Err(err) => {
+            command.error(ctx, "Failed to read settings file").await?;
+
+            return Err(err);
+        }
+    };
+
+    let mut skin = match broken_skin {
+        Some((broken, reason)) => format!(
+            "{} (`{broken}` can't be used because {reason})",
+            settings.skin_name()
+        ),
+        None => settings.skin_name().into_owned(),
+    };
+
+    if skin_ini {
+        skin.push_str(" with skin.ini overrides");
+    }
+
+    let storyboard = settings.playfield.background.load_storyboards;
+
+    let speed = options.speed.map_or(1.0, |speed| speed.rate);
+    let clock_rate = replay.clock_rate() * speed;
 
-    let content = "Replay has been pushed to the queue!";
-    let builder = MessageBuilder::new().embed(content);
+    let seconds: u32 = segments
+        .iter()
+        .map(|time_points| time_points.playback_duration(seconds_total, clock_rate))
+        .sum();
 
-    command.update(&ctx, &builder).await?;
+    let position = ctx.replay_queue.queue.lock().await.len() + 1;
+
+    let mut fields = vec![
+        EmbedField {
+            inline: false,
+            name: "Map".to_owned(),
+            value: map_title,
+        },
+        EmbedField {
+            inline: true,
+            name: "Mods".to_owned(),
+            value: mods_string(replay.mods),
+        },
+        EmbedField {
+            inline: true,
+            name: "Skin".to_owned(),
+            value: skin,
+        },
+        EmbedField {
+            inline: true,
+            name: "Quality".to_owned(),
+            value: options.quality.unwrap_or_default().name().to_owned(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Storyboard".to_owned(),
+            value: if storyboard { "On" } else { "Off" }.to_owned(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Estimated length".to_owned(),
+            value: format!("{}:{:02}", seconds / 60, seconds % 60),
+        },
+        EmbedField {
+            inline: true,
+            name: "Output channel".to_owned(),
+            value: format!("<#{output_channel}>"),
+        },
+        EmbedField {
+            inline: true,
+            name: "Queue position".to_owned(),
+            value: format!("#{position}"),
+        },
+    ];
+
+    if let Some(speed) = options.speed {
+        let field = EmbedField {
+            inline: true,
+            name: "Speed".to_owned(),
+            value: speed.label(),
+        };
+
+        fields.insert(2, field);
+    }
+
+    if let Some(resolution) = options.resolution {
+        let field = EmbedField {
+            inline: true,
+            name: "Resolution".to_owned(),
+            value: resolution.label(),
+        };
+
+        fields.insert(2, field);
+    }
+
+    if let Some(mods) = options.visual_mods {
+        let field = EmbedField {
+            inline: true,
+            name: "Visual mods".to_owned(),
+            value: format!("{} (overridden)", mods.label()),
+        };
+
+        fields.insert(2, field);
+    }
+
+    if segments.len() > 1 {
+        let clips = segments
+            .iter()
+            .map(|time_points| {
+                let TimePoints { start, end } = time_points;
+
+                format!(
+                    "{}:{:02}-{}:{:02}",
+                    start / 60,
+                    start % 60,
+                    end / 60,
+                    end % 60
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let field = EmbedField {
+            inline: false,
+            name: format!("Clips ({})", segments.len()),
+            value: clips,
+        };
+
+        fields.push(field);
+    }
+
+    let embed = EmbedBuilder::new()
+        .title("Dry run: the replay would be rendered as follows")
+        .fields(fields);
+
+    let mut builder = MessageBuilder::new().embed(embed);
+
+    let authority = check_authority(ctx, user, command.channel_id, command.guild_id).await;
+
+    if authority.is_none() {
+        redact(&mut settings);
+        let json = serde_json::to_vec_pretty(&settings)?;
+        builder = builder.attachment("settings.json", json);
+    }
+
+    command.update(ctx, &builder).await?;
 
     Ok(())
 }
+
+/// Replace paths of the host with a placeholder and the skin path with its name
+fn redact(settings: &mut DanserSettings) {
+    settings.skin.current_skin = settings.skin_name().into_owned();
+
+    let general = &mut settings.general;
+
+    let paths = [
+        &mut general.osu_songs_dir,
+        &mut general.osu_skins_dir,
+        &mut general.osu_replays_dir,
+        &mut settings.recording.output_dir,
+        &mut settings.gameplay.underlay.path,
+    ];
+
+    for path in paths {
+        if !path.is_empty() {
+            *path = REDACTED.to_owned();
+        }
+    }
+}
+
+/// Keeps the command's response updated with the queue status of the entry
+/// until the entry is being processed or the interaction token expires.
+pub(super) async fn track_queue_status(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    id: u32,
+    content: String,
+) {
+    // Interaction tokens are valid for 15 minutes
+    const TOKEN_LIFETIME: Duration = Duration::from_secs(14 * 60);
+    const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+    let start = Instant::now();
+    let mut interval = interval(UPDATE_INTERVAL);
+    let mut prev_status = None;
+
+    while start.elapsed() < TOKEN_LIFETIME {
+        interval.tick().await;
+
+        let status = match ctx.replay_queue.entry_status(id).await {
+            Some(status) => status,
+            None => return,
+        };
+
+        if prev_status != Some(status) {
+            let content = format!("{content}\n{status}");
+            let builder = MessageBuilder::new().embed(content);
+
+            if let Err(err) = command.update(&ctx, &builder).await {
+                let err = Report::from(err).wrap_err("failed to update queue status");
+                warn!("{err:?}");
+
+                return;
+            }
+
+            prev_status = Some(status);
+        }
+
+        if !matches!(status, ReplayStatus::Queued(_)) {
+            return;
+        }
+    }
+}
+
+/// Returns the channel in which the rendered video should be posted.
+///
+/// If the command was used in a server that is not properly setup,
+/// an error is sent as callback and `None` is returned.
+pub(super) async fn render_output_channel(
+    ctx: &Context,
+    command: &InteractionCommand,
+) -> Result<Option<Id<ChannelMarker>>> {
+    let output_channel = match command.guild_id {
+        Some(guild) => {
+            // Returns the output channel if:
+            // - Settings of the server are stored
+            // - The server's input channels include the current channel
+            // - The server's output channel has been configured
+            let check = ctx.guild_settings(guild, |server| {
+                server
+                    .input_channels
+                    .contains(&command.channel_id)
+                    .then_some(server.output_channel)
+                    .ok_or(())
+            });
+
+            let locale = ctx.guild_locale(Some(guild));
+
+            match check {
+                Some(Ok(Some(output_channel))) => output_channel,
+                Some(Err(_)) => {
+                    let content = locale.get(Msg::NotInputChannel);
+                    command.error_callback(ctx, content, true).await?;
+
+                    return Ok(None);
+                }
+                Some(Ok(None)) | None => {
+                    let content = locale.get(Msg::MissingOutputChannel);
+                    command.error_callback(ctx, content, false).await?;
+
+                    return Ok(None);
+                }
+            }
+        }
+        None => command.channel_id,
+    };
+
+    Ok(Some(output_channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_host_paths() {
+        let mut settings = DanserSettings::default();
+        settings.general.osu_songs_dir = "/home/bot/danser/songs".to_owned();
+        settings.general.osu_skins_dir = "/home/bot/danser/skins".to_owned();
+        settings.skin.current_skin = "/home/bot/danser/skins/Rafis".to_owned();
+        settings.recording.output_dir = "/home/bot/danser/videos".to_owned();
+
+        redact(&mut settings);
+
+        let json = serde_json::to_string(&settings).unwrap();
+
+        assert!(!json.contains("/home/bot"));
+        assert_eq!(settings.skin.current_skin, "Rafis");
+        assert_eq!(settings.general.osu_songs_dir, REDACTED);
+
+        // Paths that aren't set stay empty
+        assert!(settings.general.osu_replays_dir.is_empty());
+        assert!(settings.gameplay.underlay.path.is_empty());
+    }
+}
@@ -1,22 +1,36 @@
-use std::{fs, sync::Arc};
+use std::{sync::Arc, time::Instant};
 
 use command_macros::msg_command;
 use eyre::{Context as _, ContextCompat, Report};
-use osu_db::Replay;
 use rosu_v2::prelude::Score;
 use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 use twilight_interactions::command::CommandInputData;
 use twilight_model::{channel::embed::Embed, util::Timestamp};
 
 use crate::{
-    core::{replay_queue::ReplaySlim, BotConfig, Context, ReplayData, TimePoints},
-    util::{
-        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    core::{
+        replay_queue::{parse_replay, ReplaySlim},
+        Context, RenderOptions, ReplayData, TimePoints,
     },
+    util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
+};
+
+use super::{
+    enqueue::{enqueue, render_rejection, replay_warnings, store_replay, Requester},
+    track_queue_status,
 };
 
 #[msg_command(name = "Render score", dm_permission = false)]
 async fn render_from_msg(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
+    let user = command.user_id()?;
+    let rejection = render_rejection(&ctx, user, command.channel_id, command.guild_id);
+
+    if let Some(content) = rejection.await {
+        command.error(&ctx, content).await?;
+
+        return Ok(());
+    }
+
     let input_data = command.input_data();
 
     let (osu_user_id, timestamp) = match parse_embed(&input_data) {
@@ -108,13 +122,8 @@ async fn render_from_msg(ctx: Arc<Context>, mut command: InteractionCommand) ->
         None => "<unknown map>".into(),
     };
 
-    let mut path = BotConfig::get().paths.downloads();
-    path.push(format!("{osu_user} - {map_title}.osr"));
-
-    fs::write(&path, &replay_bytes).context("failed to write into replay file")?;
-
-    let replay = match Replay::from_bytes(&replay_bytes) {
-        Ok(replay) => ReplaySlim::from(replay),
+    let replay = match parse_replay(&replay_bytes) {
+        Ok(replay) => replay,
         Err(err) => {
             let content = "Failed to parse replay";
             let _ = command.error(&ctx, content).await;
@@ -123,8 +132,13 @@ async fn render_from_msg(ctx: Arc<Context>, mut command: InteractionCommand) ->
         }
     };
 
+    let requester = Requester::Command(&command);
+    let filename = format!("{osu_user} - {map_title}.osr");
+    let path = store_replay(&ctx, requester, &filename, &replay_bytes).await?;
+
+    let (replay, parse_warnings) = ReplaySlim::with_warnings(replay);
+
     let input_channel = command.channel_id;
-    let user = command.user_id()?;
 
     let guild_id = command.guild_id().context("expected guild id")?;
     let output_channel = ctx
@@ -132,19 +146,34 @@ async fn render_from_msg(ctx: Arc<Context>, mut command: InteractionCommand) ->
         .flatten()
         .unwrap_or(input_channel);
 
+    // The whole replay is rendered
+    let time_points = TimePoints { start: 0, end: 0 };
+
     let replay_data = ReplayData {
+        id: ctx.replay_queue.next_id(),
+        comparison: None,
+        guild: command.guild_id,
         input_channel,
+        interaction_token: Some(command.token.clone()),
+        local_map: None,
         output_channel,
+        options: RenderOptions::default(),
         path,
+        queued_at: Instant::now(),
+        render_for: None,
         replay,
+        skin_ini: None,
         user,
-        time_points: TimePoints { start: 0, end: 0 },
+        time_points,
+        title: None,
     };
 
-    ctx.replay_queue.push(replay_data).await;
+    let id = replay_data.id;
+    let locale = ctx.guild_locale(command.guild_id);
+    let warnings = replay_warnings(&ctx, command.guild_id, &replay_bytes, parse_warnings);
+    let content = enqueue(&ctx, replay_data, vec![time_points], locale, &warnings).await;
 
-    let builder = MessageBuilder::new().embed("Replay has been pushed to the queue!");
-    command.update(&ctx, &builder).await?;
+    tokio::spawn(track_queue_status(ctx, command, id, content));
 
     Ok(())
 }
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use command_macros::SlashCommand;
+use eyre::Result;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::{locale::Msg, BotConfig, Context},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+#[derive(CreateCommand, CommandModel, SlashCommand)]
+#[command(name = "reupload")]
+/// Upload the video of a recently completed render again
+pub struct Reupload {
+    #[command(min_value = 1)]
+    /// The job id that was shown when the replay was queued
+    id: u32,
+}
+
+async fn slash_reupload(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
+    let Reupload { id } = Reupload::from_interaction(command.input_data())?;
+
+    let locale = ctx.guild_locale(command.guild_id);
+    let user = command.user_id()?;
+
+    let output = match ctx.render_outputs.get(id) {
+        Some(output) => output,
+        None => {
+            command
+                .error(&ctx, locale.get(Msg::ReuploadNotFound))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if !output.is_owner(user) && !BotConfig::get().owners.contains(&user) {
+        command.error(&ctx, locale.get(Msg::ReuploadNotOwn)).await?;
+
+        return Ok(());
+    }
+
+    if !output.path.exists() {
+        command
+            .error(&ctx, locale.get(Msg::ReuploadCleanedUp))
+            .await?;
+
+        return Ok(());
+    }
+
+    let upload_fut = ctx
+        .client()
+        .upload_video(&output.title, output.user, &output.path);
+
+    let link = match upload_fut.await {
+        Ok(res) if res.error == 1 => {
+            command.error(&ctx, "Failed to upload the video").await?;
+
+            bail!("failed to re-upload: `{}`", res.text);
+        }
+        Ok(res) => res.text,
+        Err(err) => {
+            command.error(&ctx, "Failed to upload the video").await?;
+
+            return Err(err.wrap_err("failed to re-upload file"));
+        }
+    };
+
+    let content = format!("{} {link}", locale.get(Msg::ReuploadDone));
+    let builder = MessageBuilder::new().content(content);
+    command.update(&ctx, &builder).await?;
+
+    Ok(())
+}
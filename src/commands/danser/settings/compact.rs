@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use eyre::Result;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SettingsCompact;
+
+pub async fn compact(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SettingsCompact,
+) -> Result<()> {
+    let author = command.user_id()?;
+    let SettingsCompact { enabled } = args;
+
+    let upsert_res = ctx.upsert_user_settings(author, |s| s.compact_embeds = enabled);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update your settings";
+        let _ = command.error_callback(&ctx, content, true).await;
+
+        return Err(err);
+    }
+
+    let content = match enabled {
+        Some(true) => "Embeds for you will now show fewer details for better readability on mobile",
+        Some(false) => "Embeds for you will now show all details",
+        None => "Embeds for you will now follow the server's setting",
+    };
+
+    let builder = MessageBuilder::new().embed(content.to_owned());
+    command.callback(&ctx, builder, true).await?;
+
+    Ok(())
+}
@@ -11,6 +11,7 @@ use twilight_model::application::command::CommandOptionChoice;
 
 use crate::{
     core::{settings::DanserSettings, BotConfig, Context},
+    custom_client::attachment_error,
     util::{
         builder::MessageBuilder, interaction::InteractionCommand, levenshtein_distance, Authored,
         CowUtils, InteractionCommandExt,
@@ -38,6 +39,7 @@ pub async fn edit(
 
             let mut skins: Vec<_> = ctx
                 .skin_list()
+                .await
                 .get()?
                 .iter()
                 .map(|skin| {
@@ -95,7 +97,7 @@ pub async fn edit(
                 }
             },
             Err(err) => {
-                let content = "Failed to download the provided attachment";
+                let content = attachment_error(&err, "Failed to download the provided attachment");
                 let _ = command.error_callback(&ctx, content, false).await;
 
                 return Err(err.wrap_err("failed to download settings attachment"));
@@ -229,7 +231,7 @@ fn modify_settings(
     let mut changed = false;
 
     if let Some(skin) = skin {
-        let skin_name_res = ctx.skin_list().get().map(|skins| {
+        let skin_name_res = ctx.skin_list().await.get().map(|skins| {
             let skin = skin.cow_replace('_', " ");
 
             skins.iter().find_map(|skin_| {
@@ -24,8 +24,9 @@ use crate::{
     util::{builder::EmbedBuilder, interaction::InteractionCommand, InteractionCommandExt},
 };
 
-use self::{copy::*, default::*, edit::*, view::*};
+use self::{compact::*, copy::*, default::*, edit::*, view::*};
 
+mod compact;
 mod copy;
 mod default;
 mod edit;
@@ -37,6 +38,8 @@ mod view;
 #[allow(unused)]
 /// Adjust your danser settings
 pub enum Settings {
+    #[command(name = "compact")]
+    Compact(SettingsCompact),
     #[command(name = "copy")]
     Copy(SettingsCopy),
     #[command(name = "default")]
@@ -49,6 +52,8 @@ pub enum Settings {
 
 #[derive(CommandModel)]
 pub enum SettingsParsable {
+    #[command(name = "compact")]
+    Compact(SettingsCompact),
     #[command(name = "copy")]
     Copy(SettingsCopy),
     #[command(name = "default")]
@@ -59,6 +64,14 @@ pub enum SettingsParsable {
     View(SettingsView),
 }
 
+#[derive(CreateCommand, CommandModel)]
+#[command(name = "compact")]
+/// Choose whether embeds for you show fewer details
+pub struct SettingsCompact {
+    /// Whether embeds should be compact, leave empty to follow the server's setting
+    enabled: Option<bool>,
+}
+
 #[derive(CreateCommand, CommandModel)]
 #[command(name = "copy")]
 /// Copy over the settings from another user
@@ -241,6 +254,7 @@ pub struct SettingsView {
 
 pub async fn slash_settings(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
     match SettingsParsable::from_interaction(command.input_data())? {
+        SettingsParsable::Compact(args) => compact(ctx, command, args).await,
         SettingsParsable::Copy(args) => copy(ctx, command, args).await,
         SettingsParsable::Default(args) => default(ctx, command, args).await,
         SettingsParsable::Edit(args) => edit(ctx, command, args).await,
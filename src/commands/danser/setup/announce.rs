@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupAnnounce;
+
+pub async fn announce(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupAnnounce,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let SetupAnnounce { enabled } = args;
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.announce_renders = enabled);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = if enabled {
+        "The output channel will now be notified whenever a render starts"
+    } else {
+        "The start of renders will no longer be announced"
+    };
+
+    let builder = MessageBuilder::new().embed(content.to_owned());
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupAutoRender;
+
+pub async fn auto_render(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupAutoRender,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let SetupAutoRender { enabled } = args;
+
+    let ephemeral_results = ctx
+        .guild_settings(guild_id, |s| s.ephemeral_results)
+        .unwrap_or(false);
+
+    if enabled && ephemeral_results {
+        let content = "Auto-renders can't be enabled while results are ephemeral \
+            because they're not requested through a command.\n\
+            Use `/setup results ephemeral:False` first.";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.auto_render = enabled);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = if enabled {
+        "Replays that are posted in input channels will now be rendered automatically"
+    } else {
+        "Replays that are posted in input channels will no longer be rendered automatically"
+    };
+
+    let builder = MessageBuilder::new().embed(content.to_owned());
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
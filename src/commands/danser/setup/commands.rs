@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{commands::slash::Commands, Context},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::{SetupCommands, ToggleAction};
+
+/// Commands that can't be disabled so that servers can't lock themselves out
+pub(super) const ALWAYS_ENABLED: &[&str] = &["setup"];
+
+pub async fn commands(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupCommands,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let SetupCommands { action, name } = args;
+    let name = name.trim_start_matches('/').to_owned();
+
+    if Commands::get().command(&name).is_none() {
+        let content = format!("There is no command called `{name}`");
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    match action {
+        ToggleAction::Disable => {
+            if ALWAYS_ENABLED.contains(&name.as_str()) {
+                let content = format!("The `{name}` command can not be disabled");
+                command.error_callback(&ctx, content, true).await?;
+
+                return Ok(());
+            }
+
+            let upsert_res =
+                ctx.upsert_guild_settings(guild_id, |s| s.disabled_commands.insert(name.clone()));
+
+            if let Err(err) = upsert_res {
+                let content = "Failed to update server settings";
+                let _ = command.error_callback(&ctx, content, false).await;
+
+                return Err(err);
+            }
+
+            let content = format!("The `{name}` command is now disabled in this server");
+            let builder = MessageBuilder::new().embed(content);
+            command.callback(&ctx, builder, false).await?;
+        }
+        ToggleAction::Enable => {
+            let upsert_res =
+                ctx.upsert_guild_settings(guild_id, |s| s.disabled_commands.remove(&name));
+
+            match upsert_res {
+                Ok(true) => {
+                    let content = format!("The `{name}` command is now enabled again");
+                    let builder = MessageBuilder::new().embed(content);
+                    command.callback(&ctx, builder, false).await?;
+                }
+                Ok(false) => {
+                    let content = format!("The `{name}` command is not disabled");
+                    command.error_callback(&ctx, content, false).await?;
+                }
+                Err(err) => {
+                    let content = "Failed to update server settings";
+                    let _ = command.error_callback(&ctx, content, false).await;
+
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
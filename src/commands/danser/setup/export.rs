@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use eyre::{Context as _, ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{
+        settings::{Server, ServerExport},
+        Context,
+    },
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+pub async fn export(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+
+    let export = ctx
+        .guild_settings(guild_id, ServerExport::from)
+        .unwrap_or_else(|| ServerExport::from(&Server::default()));
+
+    let json =
+        serde_json::to_string_pretty(&export).context("failed to serialize server settings")?;
+
+    let content = format!(
+        "Attach this as a .json file to `/setup import` to apply these settings to a server:\n\
+        ```json\n{json}\n```"
+    );
+
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, true).await?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{
+        commands::slash::Commands, replay_queue::watermark, settings::ServerExport, BotConfig,
+        Context,
+    },
+    custom_client::attachment_error,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::{commands::ALWAYS_ENABLED, SetupImport};
+
+pub async fn import(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupImport,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let SetupImport { file } = args;
+
+    if !matches!(file.filename.rsplit('.').next(), Some("json")) {
+        let content = "The attached file must be of type .json";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let bytes = match ctx.client().get_discord_attachment(&file).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let content = attachment_error(&err, "Failed to download the provided attachment");
+            let _ = command.error_callback(&ctx, content, false).await;
+
+            return Err(err.wrap_err("failed to download server settings attachment"));
+        }
+    };
+
+    let export = match serde_json::from_slice::<ServerExport>(&bytes) {
+        Ok(export) => export,
+        Err(err) => {
+            let content = format!(
+                "Failed to deserialize the attached .json data into server settings: {err}\n\
+                Be sure you provide settings that were exported through `/setup export`."
+            );
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let commands = Commands::get();
+
+    let invalid = export
+        .disabled_commands
+        .iter()
+        .find(|name| commands.command(name).is_none() || ALWAYS_ENABLED.contains(&name.as_str()));
+
+    if let Some(name) = invalid {
+        let content = format!("`{name}` is not a command that can be disabled");
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if let Some(Err(content)) = export.watermark.as_deref().map(watermark::validate) {
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    if export.ephemeral_results && export.auto_render {
+        let content = "Results can't be ephemeral while auto-render is enabled";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let max = BotConfig::get().guild_queue_max;
+
+    if let Some(limit) = export
+        .queue_limit
+        .filter(|&limit| limit == 0 || limit > max)
+    {
+        let content = format!("The queue limit must be between 1 and {max}, got {limit}");
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |server| export.apply(server));
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = "Successfully imported the server settings".to_owned();
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
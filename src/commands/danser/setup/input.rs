@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
-use eyre::Result;
+use eyre::{ContextCompat, Result};
 use twilight_model::guild::Permissions;
 
 use crate::{
     core::Context,
-    util::{builder::MessageBuilder, interaction::InteractionCommand, InteractionCommandExt},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
 };
 
 use super::{InputAction, SetupInput};
@@ -15,7 +17,7 @@ pub async fn input(ctx: Arc<Context>, command: InteractionCommand, args: SetupIn
     let permissions = member.permissions.unwrap_or_else(Permissions::empty);
 
     if permissions.contains(Permissions::ADMINISTRATOR) {
-        let guild_id = command.guild_id.unwrap();
+        let guild_id = command.guild_id().context("expected guild id")?;
         let SetupInput { action, channel } = args;
 
         match action {
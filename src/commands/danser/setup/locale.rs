@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{locale::Msg, Context},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupLocale;
+
+pub async fn locale(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupLocale,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        let guild_id = command.guild_id().context("expected guild id")?;
+        let SetupLocale { language } = args;
+
+        let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.locale = language);
+
+        if let Err(err) = upsert_res {
+            let content = "Failed to update server settings";
+            let _ = command.error_callback(&ctx, content, false).await;
+
+            return Err(err);
+        }
+
+        let builder = MessageBuilder::new().embed(language.get(Msg::LocaleUpdated));
+        command.callback(&ctx, builder, false).await?;
+    } else {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+    }
+
+    Ok(())
+}
@@ -3,19 +3,44 @@ use std::sync::Arc;
 use command_macros::SlashCommand;
 use eyre::Result;
 use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
-use twilight_model::id::{marker::ChannelMarker, Id};
+use twilight_model::{
+    channel::Attachment,
+    id::{marker::ChannelMarker, Id},
+};
 
 use crate::{
     commands::server_administrator,
+    core::locale::Locale,
     util::{interaction::InteractionCommand, InteractionCommandExt},
     Context,
 };
 
-use self::{input::*, output::*, view::*};
+pub use self::wizard::*;
+
+use self::{
+    announce::*, auto_render::*, commands::*, compact::*, export::*, import::*, input::*,
+    locale::*, output::*, parse_warnings::*, queue_limit::*, results::*, showcase::*, skin::*,
+    view::*, watermark::*, webhook::*,
+};
 
+mod announce;
+mod auto_render;
+mod commands;
+mod compact;
+mod export;
+mod import;
 mod input;
+mod locale;
 mod output;
+mod parse_warnings;
+mod queue_limit;
+mod results;
+mod showcase;
+mod skin;
 mod view;
+mod watermark;
+mod webhook;
+mod wizard;
 
 #[derive(CommandModel, CreateCommand, SlashCommand)]
 #[command(name = "setup", dm_permission = false)]
@@ -28,6 +53,34 @@ pub enum Setup {
     Input(SetupInput),
     #[command(name = "output")]
     Output(SetupOutput),
+    #[command(name = "showcase")]
+    Showcase(SetupShowcase),
+    #[command(name = "locale")]
+    Locale(SetupLocale),
+    #[command(name = "commands")]
+    Commands(SetupCommands),
+    #[command(name = "watermark")]
+    Watermark(SetupWatermark),
+    #[command(name = "autorender")]
+    AutoRender(SetupAutoRender),
+    #[command(name = "results")]
+    Results(SetupResults),
+    #[command(name = "announce")]
+    Announce(SetupAnnounce),
+    #[command(name = "compact")]
+    Compact(SetupCompact),
+    #[command(name = "parsewarnings")]
+    ParseWarnings(SetupParseWarnings),
+    #[command(name = "webhook")]
+    Webhook(SetupWebhook),
+    #[command(name = "queuelimit")]
+    QueueLimit(SetupQueueLimit),
+    #[command(name = "skin")]
+    Skin(SetupSkin),
+    #[command(name = "export")]
+    Export(SetupExport),
+    #[command(name = "import")]
+    Import(SetupImport),
 }
 
 #[derive(CommandModel, CreateCommand)]
@@ -61,10 +114,148 @@ pub struct SetupOutput {
     channel: Id<ChannelMarker>,
 }
 
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "showcase", default_permissions = "server_administrator")]
+/// Configure additional channels in which rendered replays will be sent
+pub struct SetupShowcase {
+    /// Add or remove a channel
+    action: InputAction,
+    /// The channel you want to add/remove
+    channel: Id<ChannelMarker>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "locale", default_permissions = "server_administrator")]
+/// Configure the language of the bot's messages
+pub struct SetupLocale {
+    /// The language you want the bot to use
+    language: Locale,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "commands", default_permissions = "server_administrator")]
+/// Disable or enable commands in this server
+pub struct SetupCommands {
+    /// Disable or enable the command
+    action: ToggleAction,
+    #[command(name = "command")]
+    /// Name of the command
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "watermark", default_permissions = "server_administrator")]
+/// Configure a text that is overlaid on rendered videos
+pub struct SetupWatermark {
+    /// The watermark text, leave empty to remove the watermark
+    text: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "autorender", default_permissions = "server_administrator")]
+/// Render replays that are posted in input channels without using a command
+pub struct SetupAutoRender {
+    /// Whether posted replays should be rendered automatically
+    enabled: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "results", default_permissions = "server_administrator")]
+/// Configure whether rendered replays are posted publicly or only shown to the requester
+pub struct SetupResults {
+    /// Whether results should only be visible to the requester
+    ephemeral: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "announce", default_permissions = "server_administrator")]
+/// Post a message in the output channel whenever a render of the server starts
+pub struct SetupAnnounce {
+    /// Whether the start of renders should be announced
+    enabled: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "compact", default_permissions = "server_administrator")]
+/// Show fewer details in embeds for better readability on mobile
+pub struct SetupCompact {
+    /// Whether embeds should be compact
+    enabled: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "parsewarnings", default_permissions = "server_administrator")]
+/// Show minor issues of replays such as unusual mods when they're queued
+pub struct SetupParseWarnings {
+    /// Whether the issues should be shown
+    enabled: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "webhook", default_permissions = "server_administrator")]
+/// Post render results through a webhook instead of the output channel
+pub struct SetupWebhook {
+    /// The webhook's url, leave empty to post in the output channel again
+    url: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "queuelimit", default_permissions = "server_administrator")]
+/// Configure how many of the server's replays can be in the queue at once
+pub struct SetupQueueLimit {
+    #[command(min_value = 1)]
+    /// Maximum amount of queued replays, leave empty to use the bot's maximum
+    limit: Option<i64>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "skin", default_permissions = "server_administrator")]
+/// Configure the skin of users that don't have their own settings
+pub struct SetupSkin {
+    #[command(min_value = 1, max_value = 65_535)]
+    /// Index of the skin in `/skinlist`, leave empty to use the default skin
+    index: Option<i64>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "export", default_permissions = "server_administrator")]
+/// Export the server's settings except for channels as JSON
+pub struct SetupExport;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "import", default_permissions = "server_administrator")]
+/// Apply settings that were exported through `/setup export`
+pub struct SetupImport {
+    /// A .json file containing the exported settings
+    file: Attachment,
+}
+
+#[derive(CommandOption, CreateOption)]
+pub enum ToggleAction {
+    #[option(name = "disable", value = "disable")]
+    Disable,
+    #[option(name = "enable", value = "enable")]
+    Enable,
+}
+
 async fn slash_setup(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
     match Setup::from_interaction(command.input_data())? {
         Setup::Input(args) => input(ctx, command, args).await,
         Setup::Output(args) => output(ctx, command, args).await,
+        Setup::Showcase(args) => showcase(ctx, command, args).await,
+        Setup::Locale(args) => locale(ctx, command, args).await,
+        Setup::Commands(args) => commands(ctx, command, args).await,
+        Setup::AutoRender(args) => auto_render(ctx, command, args).await,
+        Setup::Results(args) => results(ctx, command, args).await,
+        Setup::Announce(args) => announce(ctx, command, args).await,
+        Setup::Compact(args) => compact(ctx, command, args).await,
+        Setup::ParseWarnings(args) => parse_warnings(ctx, command, args).await,
+        Setup::Webhook(args) => webhook(ctx, command, args).await,
+        Setup::QueueLimit(args) => queue_limit(ctx, command, args).await,
+        Setup::Skin(args) => skin(ctx, command, args).await,
+        Setup::Watermark(args) => watermark(ctx, command, args).await,
+        Setup::Export(_) => export(ctx, command).await,
+        Setup::Import(args) => import(ctx, command, args).await,
         Setup::View(_) => view(ctx, command).await,
     }
 }
@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
-use eyre::Result;
+use eyre::{ContextCompat, Result};
 use twilight_model::guild::Permissions;
 
 use crate::{
     core::Context,
-    util::{builder::MessageBuilder, interaction::InteractionCommand, InteractionCommandExt},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
 };
 
 use super::SetupOutput;
@@ -19,7 +21,7 @@ pub async fn output(
     let permissions = member.permissions.unwrap_or_else(Permissions::empty);
 
     if permissions.contains(Permissions::ADMINISTRATOR) {
-        let guild_id = command.guild_id.unwrap();
+        let guild_id = command.guild_id().context("expected guild id")?;
         let SetupOutput { channel } = args;
 
         let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.output_channel = Some(channel));
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{BotConfig, Context},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupQueueLimit;
+
+pub async fn queue_limit(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupQueueLimit,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let max = BotConfig::get().guild_queue_max;
+    let limit = args.limit.map(|limit| limit as usize);
+
+    if let Some(limit) = limit.filter(|&limit| limit > max) {
+        let content = format!("The limit can be at most {max}, got {limit}");
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.queue_limit = limit);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = format!(
+        "At most {} replays of this server can now be in the queue at once",
+        limit.unwrap_or(max)
+    );
+
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupResults;
+
+pub async fn results(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupResults,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let SetupResults { ephemeral } = args;
+
+    // Ephemeral messages require an interaction which auto-renders don't have
+    let auto_render = ctx
+        .guild_settings(guild_id, |s| s.auto_render)
+        .unwrap_or(false);
+
+    if ephemeral && auto_render {
+        let content = "Results can only be ephemeral if renders are requested through commands.\n\
+            Disable `/setup autorender` first.";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.ephemeral_results = ephemeral);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = if ephemeral {
+        "Rendered replays will now only be shown to the requester"
+    } else {
+        "Rendered replays will now be posted publicly in the output channel"
+    };
+
+    let builder = MessageBuilder::new().embed(content.to_owned());
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
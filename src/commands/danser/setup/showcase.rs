@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::{InputAction, SetupShowcase};
+
+pub async fn showcase(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupShowcase,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        let guild_id = command.guild_id().context("expected guild id")?;
+        let SetupShowcase { action, channel } = args;
+
+        match action {
+            InputAction::Add => {
+                let upsert_res =
+                    ctx.upsert_guild_settings(guild_id, |s| s.showcase_channels.insert(channel));
+
+                if let Err(err) = upsert_res {
+                    let content = "Failed to update server settings";
+                    let _ = command.error_callback(&ctx, content, false).await;
+
+                    return Err(err);
+                }
+
+                let content = format!("Rendered replays will now also be sent to <#{channel}>");
+                let builder = MessageBuilder::new().embed(content);
+                command.callback(&ctx, builder, false).await?;
+            }
+            InputAction::Remove => {
+                let upsert_res =
+                    ctx.upsert_guild_settings(guild_id, |s| s.showcase_channels.remove(&channel));
+
+                match upsert_res {
+                    Ok(true) => {
+                        let content = format!("Successfully removed showcase channel <#{channel}>");
+                        let builder = MessageBuilder::new().embed(content);
+                        command.callback(&ctx, builder, false).await?;
+                    }
+                    Ok(false) => {
+                        let content = "That channel is not a showcase channel";
+                        command.error_callback(&ctx, content, false).await?;
+                    }
+                    Err(err) => {
+                        let content = "Failed to update server settings";
+                        let _ = command.error_callback(&ctx, content, false).await;
+
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    } else {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupSkin;
+
+pub async fn skin(ctx: Arc<Context>, command: InteractionCommand, args: SetupSkin) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+
+    let skin = match args.index {
+        Some(index) => {
+            let skin_res = ctx
+                .skin_list()
+                .await
+                .get()
+                .map(|skins| skins.get(index as usize - 1).cloned());
+
+            match skin_res {
+                Ok(Some(skin)) => Some(skin.to_string_lossy().into_owned()),
+                Ok(None) => {
+                    let content = format!(
+                        "There is no skin at index {index}.\n\
+                        Check `/skinlist` to see available skins."
+                    );
+                    command.error_callback(&ctx, content, true).await?;
+
+                    return Ok(());
+                }
+                Err(err) => {
+                    let content = "Failed to read the skinlist";
+                    let _ = command.error_callback(&ctx, content, false).await;
+
+                    return Err(err);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.render_defaults.skin = skin.clone());
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let content = match skin {
+        Some(skin) => format!(
+            "Users without their own settings now render with the skin `{}`",
+            skin.replace('_', " ")
+        ),
+        None => "Users without their own settings now render with the default skin".to_owned(),
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -1,40 +1,206 @@
-use std::{fmt::Write, sync::Arc};
+use std::{collections::HashSet, fmt::Write, sync::Arc};
 
-use eyre::Result;
+use eyre::{ContextCompat, Result};
+use twilight_model::id::{marker::ChannelMarker, Id};
 
 use crate::{
     core::Context,
-    util::{builder::MessageBuilder, interaction::InteractionCommand, InteractionCommandExt},
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        Authored, InteractionCommandExt,
+    },
 };
 
 pub async fn view(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
-    let guild_id = command.guild_id.unwrap();
+    let guild_id = command.guild_id().context("expected guild id")?;
 
     let input_channels = ctx
-        .guild_settings(guild_id, |server| {
-            let mut iter = server.input_channels.iter();
+        .guild_settings(guild_id, |server| channel_list(&server.input_channels))
+        .flatten()
+        .map_or(Setting::Unset("None"), Setting::Set);
 
-            iter.next().map(|channel| {
-                let mut text = format!("<#{channel}>");
+    let output_channel = ctx
+        .guild_settings(guild_id, |s| s.output_channel)
+        .flatten()
+        .map_or(Setting::Unset("None"), |channel| {
+            Setting::Set(format!("<#{channel}>"))
+        });
 
-                for channel in iter {
-                    let _ = write!(text, ", <#{channel}>");
-                }
+    let showcase_channels = ctx
+        .guild_settings(guild_id, |server| channel_list(&server.showcase_channels))
+        .flatten()
+        .map_or(Setting::Unset("None"), Setting::Set);
 
-                text
-            })
-        })
+    let disabled_commands = ctx
+        .guild_settings(guild_id, |server| command_list(&server.disabled_commands))
         .flatten()
-        .unwrap_or_else(|| "None".to_owned());
+        .map_or(Setting::Unset("None"), Setting::Set);
 
-    let output_channel = ctx
-        .guild_settings(guild_id, |s| s.output_channel)
+    let watermark = ctx
+        .guild_settings(guild_id, |server| server.watermark.clone())
         .flatten()
-        .map_or_else(|| "None".to_owned(), |channel| format!("<#{channel}>"));
+        .map_or(Setting::Unset("None"), |text| {
+            Setting::Set(format!("`{text}`"))
+        });
+
+    let auto_render = ctx
+        .guild_settings(guild_id, |server| server.auto_render)
+        .unwrap_or(false);
+
+    let announce_renders = ctx
+        .guild_settings(guild_id, |server| server.announce_renders)
+        .unwrap_or(false);
+
+    let ephemeral_results = ctx
+        .guild_settings(guild_id, |server| server.ephemeral_results)
+        .unwrap_or(false);
+
+    let results = if ephemeral_results {
+        "Only shown to the requester"
+    } else {
+        "Public"
+    };
+
+    let queue_limit = ctx.guild_queue_limit(Some(guild_id));
+
+    let default_skin = ctx
+        .guild_settings(guild_id, |server| server.render_defaults.skin.clone())
+        .flatten();
+
+    let default_skin = match default_skin {
+        Some(skin) => Setting::Set(skin_label(&ctx, &skin).await),
+        None => Setting::Unset("Default"),
+    };
+
+    // Only whether there is one since the url contains the webhook's token
+    let webhook = ctx
+        .guild_settings(guild_id, |server| server.result_webhook.is_some())
+        .unwrap_or(false);
+
+    let webhook = if webhook {
+        Setting::Set("Configured".to_owned())
+    } else {
+        Setting::Unset("None")
+    };
+
+    let compact_embeds = ctx
+        .guild_settings(guild_id, |server| server.compact_embeds)
+        .unwrap_or(false);
+
+    let locale = ctx.guild_locale(Some(guild_id));
+    let parse_warnings = ctx.parse_warnings(Some(guild_id));
+
+    let lines = [
+        ("Input channels", input_channels),
+        ("Output channel", output_channel),
+        ("Showcase channels", showcase_channels),
+        ("Disabled commands", disabled_commands),
+        ("Watermark", watermark),
+        ("Auto-render", Setting::toggle(auto_render)),
+        (
+            "Render start announcements",
+            Setting::toggle(announce_renders),
+        ),
+        ("Results", Setting::Set(results.to_owned())),
+        ("Result webhook", webhook),
+        (
+            "Queue limit",
+            Setting::Set(format!("{queue_limit} replays")),
+        ),
+        ("Default skin", default_skin),
+        ("Compact embeds", Setting::toggle(compact_embeds)),
+        ("Parse warnings", Setting::toggle(parse_warnings)),
+        ("Language", Setting::Set(locale.name().to_owned())),
+    ];
+
+    let compact = ctx.compact_embeds(command.user_id()?, Some(guild_id));
+    let embed = EmbedBuilder::new().compact(compact);
+    let mut content = String::with_capacity(512);
+
+    for (name, setting) in lines {
+        if let Some(value) = setting.display(&embed) {
+            let _ = writeln!(content, "{name}: {value}");
+        }
+    }
 
-    let content = format!("Input channels: {input_channels}\nOutput channel: {output_channel}");
-    let builder = MessageBuilder::new().embed(content);
+    let builder = MessageBuilder::new().embed(embed.description(content));
     command.callback(&ctx, builder, false).await?;
 
     Ok(())
 }
+
+/// Value of a server setting for the overview
+enum Setting {
+    Set(String),
+    /// The setting was not configured, the value describes the fallback
+    Unset(&'static str),
+}
+
+impl Setting {
+    fn toggle(enabled: bool) -> Self {
+        if enabled {
+            Self::Set("Enabled".to_owned())
+        } else {
+            Self::Unset("Disabled")
+        }
+    }
+
+    /// The value to display, compact embeds leave out what's not configured
+    fn display(self, embed: &EmbedBuilder) -> Option<String> {
+        match self {
+            Self::Set(value) => Some(value),
+            Self::Unset(value) => embed.detail(|| Some(value.to_owned()), || None),
+        }
+    }
+}
+
+/// The skin's index in the skinlist or a warning if it was removed since
+async fn skin_label(ctx: &Context, skin: &str) -> String {
+    let name = skin.replace('_', " ");
+
+    let index = match ctx.skin_list().await.get() {
+        Ok(skins) => skins.iter().position(|s| s.to_string_lossy() == skin),
+        Err(err) => {
+            warn!("{:?}", err.wrap_err("failed to get skinlist"));
+
+            return format!("`{name}`");
+        }
+    };
+
+    match index {
+        Some(idx) => format!("`{name}` (#{})", idx + 1),
+        None => format!("`{name}` ⚠️ no longer in the skinlist, the default skin is used"),
+    }
+}
+
+fn channel_list<'c>(channels: impl IntoIterator<Item = &'c Id<ChannelMarker>>) -> Option<String> {
+    let mut iter = channels.into_iter();
+
+    iter.next().map(|channel| {
+        let mut text = format!("<#{channel}>");
+
+        for channel in iter {
+            let _ = write!(text, ", <#{channel}>");
+        }
+
+        text
+    })
+}
+
+fn command_list(commands: &HashSet<String>) -> Option<String> {
+    let mut commands: Vec<_> = commands.iter().collect();
+    commands.sort_unstable();
+
+    let mut iter = commands.into_iter();
+
+    iter.next().map(|command| {
+        let mut text = format!("`{command}`");
+
+        for command in iter {
+            let _ = write!(text, ", `{command}`");
+        }
+
+        text
+    })
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::{replay_queue::watermark, Context},
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupWatermark;
+
+pub async fn watermark(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupWatermark,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let SetupWatermark { text } = args;
+
+    if let Some(Err(content)) = text.as_deref().map(watermark::validate) {
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let content = match text {
+        Some(ref text) => format!("Rendered videos will now have the watermark `{text}`"),
+        None => "Rendered videos will no longer have a watermark".to_owned(),
+    };
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.watermark = text);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::guild::Permissions;
+
+use crate::{
+    core::Context,
+    custom_client::is_webhook_url,
+    util::{
+        builder::MessageBuilder, interaction::InteractionCommand, Authored, InteractionCommandExt,
+    },
+};
+
+use super::SetupWebhook;
+
+pub async fn webhook(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SetupWebhook,
+) -> Result<()> {
+    let member = command.member.as_ref().unwrap();
+    let permissions = member.permissions.unwrap_or_else(Permissions::empty);
+
+    if !permissions.contains(Permissions::ADMINISTRATOR) {
+        let content = "You do not have the required permissions to perform this action!";
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(());
+    }
+
+    let guild_id = command.guild_id().context("expected guild id")?;
+    let url = args.url.map(|url| url.trim().to_owned());
+
+    let content = match url {
+        Some(ref url) => {
+            if !is_webhook_url(url) {
+                let content = "The url must be that of a Discord webhook, \
+                    i.e. `https://discord.com/api/webhooks/<id>/<token>`";
+                command.error_callback(&ctx, content, true).await?;
+
+                return Ok(());
+            }
+
+            let webhook = match ctx.client().get_webhook(url).await {
+                Ok(webhook) => webhook,
+                Err(err) => {
+                    let content = "Failed to reach the webhook, make sure the url is correct";
+                    command.error_callback(&ctx, content, true).await?;
+
+                    debug!("{:?}", err.wrap_err("failed to validate webhook"));
+
+                    return Ok(());
+                }
+            };
+
+            let name = webhook.name.as_deref().unwrap_or("<unnamed>");
+
+            format!(
+                "Render results will now be posted through the webhook `{name}` in <#{}>",
+                webhook.channel_id
+            )
+        }
+        None => "Render results will now be posted in the output channel".to_owned(),
+    };
+
+    let upsert_res = ctx.upsert_guild_settings(guild_id, |s| s.result_webhook = url);
+
+    if let Err(err) = upsert_res {
+        let content = "Failed to update server settings";
+        let _ = command.error_callback(&ctx, content, false).await;
+
+        return Err(err);
+    }
+
+    // The url contains the webhook's token so the confirmation is only shown to the admin
+    let builder = MessageBuilder::new().embed(content);
+    command.callback(&ctx, builder, true).await?;
+
+    Ok(())
+}
@@ -0,0 +1,403 @@
+use std::{collections::HashSet, sync::Arc};
+
+use eyre::{ContextCompat, Result};
+use time::OffsetDateTime;
+use twilight_model::{
+    application::component::{
+        button::ButtonStyle, select_menu::SelectMenuOption, ActionRow, Button, Component,
+        SelectMenu,
+    },
+    channel::{embed::EmbedField, ChannelType},
+    guild::{Guild, Permissions},
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{
+    core::{locale::Locale, BotConfig, Context},
+    util::{
+        builder::{EmbedBuilder, FooterBuilder, MessageBuilder},
+        hasher::IntBuildHasher,
+        interaction::InteractionComponent,
+        Authored, ChannelExt, ComponentExt,
+    },
+};
+
+/// Select menus can't hold more options than this
+const MAX_OPTIONS: usize = 25;
+
+/// Seconds after joining within which a guild counts as new.
+/// Otherwise its `GuildCreate` is due to a restart or an outage.
+const RECENT_JOIN: i64 = 5 * 60;
+
+/// Post the setup wizard in a guild that the bot just joined if enabled via config.
+///
+/// The wizard is only ever posted once per guild, even if it could not be posted
+/// due to missing permissions.
+pub async fn post_setup_wizard(ctx: &Context, guild: &Guild) -> Result<()> {
+    if !BotConfig::get().setup_wizard {
+        return Ok(());
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    let recently_joined = guild
+        .joined_at
+        .map_or(false, |joined_at| now - joined_at.as_secs() < RECENT_JOIN);
+
+    let prompted = ctx
+        .guild_settings(guild.id, |s| s.setup_prompted)
+        .unwrap_or(false);
+
+    if !recently_joined || prompted {
+        return Ok(());
+    }
+
+    ctx.upsert_guild_settings(guild.id, |s| s.setup_prompted = true)?;
+
+    let current_user = ctx.cache.current_user(|user| user.id)?;
+    let required = Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS;
+
+    let can_post = |channel: Id<ChannelMarker>| {
+        ctx.cache
+            .get_channel_permissions(current_user, channel, Some(guild.id))
+            .contains(required)
+    };
+
+    let channel = guild
+        .system_channel_id
+        .filter(|&c| can_post(c))
+        .or_else(|| {
+            text_channels(ctx, guild.id)
+                .into_iter()
+                .map(|(channel, _)| channel)
+                .find(|&c| can_post(c))
+        });
+
+    let channel = match channel {
+        Some(channel) => channel,
+        None => {
+            info!("No channel to post the setup wizard in guild {}", guild.id);
+
+            return Ok(());
+        }
+    };
+
+    channel
+        .create_message(ctx, &wizard_message(ctx, guild.id))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn handle_setup_wizard_input(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let guild = match authorized_guild(&ctx, &component).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let channels: HashSet<_, IntBuildHasher> = component
+        .data
+        .values
+        .iter()
+        .filter_map(|value| value.parse::<Id<ChannelMarker>>().ok())
+        .collect();
+
+    ctx.upsert_guild_settings(guild, |s| s.input_channels = channels)?;
+    component
+        .callback(&ctx, wizard_message(&ctx, guild))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn handle_setup_wizard_output(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let guild = match authorized_guild(&ctx, &component).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let channel = component
+        .data
+        .values
+        .first()
+        .and_then(|value| value.parse::<Id<ChannelMarker>>().ok());
+
+    ctx.upsert_guild_settings(guild, |s| s.output_channel = channel)?;
+    component
+        .callback(&ctx, wizard_message(&ctx, guild))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn handle_setup_wizard_locale(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let guild = match authorized_guild(&ctx, &component).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let locale = match component.data.values.first().map(String::as_str) {
+        Some("de") => Locale::German,
+        _ => Locale::English,
+    };
+
+    ctx.upsert_guild_settings(guild, |s| s.locale = locale)?;
+    component
+        .callback(&ctx, wizard_message(&ctx, guild))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn handle_setup_wizard_done(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let description = "Setup complete! Use `/setup` to adjust these settings later.";
+
+    close_wizard(ctx, component, description).await
+}
+
+pub async fn handle_setup_wizard_dismiss(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let description = "Setup dismissed. Use `/setup` to configure the bot whenever you're ready.";
+
+    close_wizard(ctx, component, description).await
+}
+
+async fn close_wizard(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+    description: &str,
+) -> Result<()> {
+    let guild = match authorized_guild(&ctx, &component).await? {
+        Some(guild) => guild,
+        None => return Ok(()),
+    };
+
+    let embed = summary_embed(&ctx, guild).description(description);
+
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+
+    component.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+/// Returns the guild if the user may use the wizard, otherwise tells them they can't
+async fn authorized_guild(
+    ctx: &Context,
+    component: &InteractionComponent,
+) -> Result<Option<Id<GuildMarker>>> {
+    let guild = component.guild_id().context("expected guild id")?;
+
+    let permissions = component
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .unwrap_or_else(Permissions::empty);
+
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        return Ok(Some(guild));
+    }
+
+    let content = "You do not have the required permissions to perform this action!";
+    component.error_callback(ctx, content).await?;
+
+    Ok(None)
+}
+
+fn wizard_message(ctx: &Context, guild: Id<GuildMarker>) -> MessageBuilder<'static> {
+    let (input_channels, output_channel, locale) = ctx
+        .guild_settings(guild, |s| {
+            (s.input_channels.clone(), s.output_channel, s.locale)
+        })
+        .unwrap_or_default();
+
+    let channels = text_channels(ctx, guild);
+    let mut components = Vec::with_capacity(4);
+
+    if !channels.is_empty() {
+        let options = |selected: &dyn Fn(Id<ChannelMarker>) -> bool| {
+            channels
+                .iter()
+                .map(|(channel, name)| SelectMenuOption {
+                    default: selected(*channel),
+                    description: None,
+                    emoji: None,
+                    label: format!("#{name}"),
+                    value: channel.to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        components.push(select_menu(
+            "setup_wizard_input",
+            "Channels in which replays can be rendered",
+            options(&|channel| input_channels.contains(&channel)),
+            0,
+            channels.len(),
+        ));
+
+        components.push(select_menu(
+            "setup_wizard_output",
+            "Channel in which rendered replays are posted",
+            options(&|channel| output_channel == Some(channel)),
+            0,
+            1,
+        ));
+    }
+
+    let locales = [Locale::English, Locale::German]
+        .into_iter()
+        .zip(["en", "de"])
+        .map(|(option, value)| SelectMenuOption {
+            default: option == locale,
+            description: None,
+            emoji: None,
+            label: option.name().to_owned(),
+            value: value.to_owned(),
+        })
+        .collect();
+
+    components.push(select_menu(
+        "setup_wizard_locale",
+        "Language of the bot's messages",
+        locales,
+        1,
+        1,
+    ));
+
+    let done = Button {
+        custom_id: Some("setup_wizard_done".to_owned()),
+        disabled: false,
+        emoji: None,
+        label: Some("Done".to_owned()),
+        style: ButtonStyle::Success,
+        url: None,
+    };
+
+    let dismiss = Button {
+        custom_id: Some("setup_wizard_dismiss".to_owned()),
+        disabled: false,
+        emoji: None,
+        label: Some("Dismiss".to_owned()),
+        style: ButtonStyle::Secondary,
+        url: None,
+    };
+
+    components.push(Component::ActionRow(ActionRow {
+        components: vec![Component::Button(done), Component::Button(dismiss)],
+    }));
+
+    let description = "Thanks for adding the bot! Pick the channels below to get started, \
+        everything can be adjusted later through `/setup`.";
+
+    let footer = if channels.len() == MAX_OPTIONS {
+        "Only the first channels are listed, use /setup for others"
+    } else {
+        "Only administrators can use this wizard"
+    };
+
+    let embed = summary_embed(ctx, guild)
+        .title("Welcome to shishabot!")
+        .description(description)
+        .footer(FooterBuilder::new(footer));
+
+    MessageBuilder::new().embed(embed).components(components)
+}
+
+fn summary_embed(ctx: &Context, guild: Id<GuildMarker>) -> EmbedBuilder {
+    let (input_channels, output_channel, locale) = ctx
+        .guild_settings(guild, |s| {
+            (s.input_channels.clone(), s.output_channel, s.locale)
+        })
+        .unwrap_or_default();
+
+    let input_channels = if input_channels.is_empty() {
+        "None".to_owned()
+    } else {
+        input_channels
+            .iter()
+            .map(|channel| format!("<#{channel}>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let output_channel = output_channel.map_or_else(|| "None".to_owned(), |c| format!("<#{c}>"));
+
+    let fields = vec![
+        field("Input channels", input_channels),
+        field("Output channel", output_channel),
+        field("Language", locale.name().to_owned()),
+    ];
+
+    EmbedBuilder::new().fields(fields)
+}
+
+fn field(name: &str, value: String) -> EmbedField {
+    EmbedField {
+        inline: true,
+        name: name.to_owned(),
+        value,
+    }
+}
+
+fn select_menu(
+    custom_id: &str,
+    placeholder: &str,
+    options: Vec<SelectMenuOption>,
+    min_values: u8,
+    max_values: usize,
+) -> Component {
+    let menu = SelectMenu {
+        custom_id: custom_id.to_owned(),
+        disabled: false,
+        max_values: Some(max_values as u8),
+        min_values: Some(min_values),
+        options,
+        placeholder: Some(placeholder.to_owned()),
+    };
+
+    Component::ActionRow(ActionRow {
+        components: vec![Component::SelectMenu(menu)],
+    })
+}
+
+/// The guild's cached text channels in their displayed order
+fn text_channels(ctx: &Context, guild: Id<GuildMarker>) -> Vec<(Id<ChannelMarker>, String)> {
+    let mut channels: Vec<_> = ctx
+        .cache
+        .guild_channels(guild, |channel| {
+            (channel.kind == ChannelType::GuildText).then(|| {
+                let name = channel.name.clone().unwrap_or_default();
+
+                (channel.position.unwrap_or(0), channel.id, name)
+            })
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+
+    channels.sort_unstable_by_key(|(position, ..)| *position);
+
+    channels
+        .into_iter()
+        .take(MAX_OPTIONS)
+        .map(|(_, channel, name)| (channel, name))
+        .collect()
+}
@@ -1,5 +1,6 @@
 use std::{
     ffi::OsString,
+    fmt::Write,
     fs,
     io::Cursor,
     path::{Path, PathBuf},
@@ -11,6 +12,7 @@ use zip::ZipArchive;
 
 use crate::{
     core::{BotConfig, Context as TwilightContext},
+    custom_client::attachment_error,
     util::{
         builder::MessageBuilder, constants::GENERAL_ISSUE, interaction::InteractionCommand,
         InteractionCommandExt,
@@ -36,13 +38,24 @@ pub async fn add(
         }
     };
 
+    // Checked before downloading to not waste bandwidth and again
+    // before extracting in case another skin was added meanwhile
+    let skin_count = ctx.skin_list().await.get()?.len();
+
+    if let Some(content) = skin_cap_reached(skin_count) {
+        command.error_callback(&ctx, content, false).await?;
+
+        return Ok(());
+    }
+
     let builder = MessageBuilder::new().embed("Downloading...");
     command.callback(&ctx, builder, false).await?;
 
     let bytes = match ctx.client().get_discord_attachment(&skin).await {
         Ok(bytes) => bytes,
         Err(err) => {
-            let _ = command.error(&ctx, "Failed to download attachment").await;
+            let content = attachment_error(&err, "Failed to download attachment");
+            let _ = command.error(&ctx, content).await;
 
             return Err(err.wrap_err("failed to download skin attachment"));
         }
@@ -69,37 +82,42 @@ pub async fn add(
 
     let mut skin_file = BotConfig::get().paths.skins();
 
-    let skin_list: Vec<_> = ctx
-        .skin_list()
-        .get()?
-        .iter()
-        .map(|skin| skin.to_ascii_lowercase())
-        .collect();
+    // Hold the lock until the skin is fully extracted so that
+    // concurrent commands don't operate on a partially added skin
+    let mut skin_list = ctx.skin_list().await;
+
+    if let Some(content) = skin_cap_reached(skin_list.get()?.len()) {
+        command.error(&ctx, content).await?;
+
+        return Ok(());
+    }
 
-    let mut needle = OsString::from(filename);
-    needle.make_ascii_lowercase();
+    let name_taken = {
+        let mut needle = OsString::from(filename);
+        needle.make_ascii_lowercase();
 
-    let idx = match skin_list.binary_search(&needle) {
-        Ok(idx) => {
-            let mut suffix = 1;
+        skin_list
+            .get()?
+            .iter()
+            .any(|skin| skin.to_ascii_lowercase() == needle)
+    };
 
-            loop {
-                skin_file.push(format!("{filename}_{suffix}"));
+    if name_taken {
+        let mut suffix = 1;
 
-                if !skin_file.exists() {
-                    break idx + suffix + 1;
-                }
+        loop {
+            skin_file.push(format!("{filename}_{suffix}"));
 
-                skin_file.pop();
-                suffix += 1;
+            if !skin_file.exists() {
+                break;
             }
-        }
-        Err(idx) => {
-            skin_file.push(filename);
 
-            idx + 1
+            skin_file.pop();
+            suffix += 1;
         }
-    };
+    } else {
+        skin_file.push(filename);
+    }
 
     builder = builder.embed("Extracting...");
     command.update(&ctx, &builder).await?;
@@ -126,16 +144,34 @@ pub async fn add(
         return Ok(());
     }
 
-    // Reset the skin list cache
-    ctx.skin_list().clear();
+    // Reset the skin list cache; new skins are appended to the end of the list
+    skin_list.clear();
+    let idx = skin_list.get()?.len();
+    drop(skin_list);
 
-    let content = format!("Added skin to list at index `{idx}`");
+    let mut content = format!("Added skin to list at index `{idx}`");
+
+    if let Some(max_skins) = BotConfig::get().max_skins {
+        let _ = write!(content, " ({idx}/{max_skins} skins)");
+    }
     builder = builder.embed(content);
     command.update(&ctx, &builder).await?;
 
     Ok(())
 }
 
+/// The message to show if no more skins can be added
+fn skin_cap_reached(skin_count: usize) -> Option<String> {
+    let max_skins = BotConfig::get().max_skins?;
+
+    (skin_count >= max_skins).then(|| {
+        format!(
+            "The skinlist is full ({skin_count}/{max_skins} skins).\n\
+            Remove skins that are no longer used with `/skin remove` before adding new ones."
+        )
+    })
+}
+
 fn move_directory(to: &PathBuf) -> Result<bool> {
     let mut skin_folder =
         fs::read_dir(to).with_context(|| format!("failed to read directory at {to:?}"))?;
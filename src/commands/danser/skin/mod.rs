@@ -10,9 +10,10 @@ use crate::{
     util::{interaction::InteractionCommand, InteractionCommandExt},
 };
 
-use self::{add::*, remove::*};
+use self::{add::*, move_skin::*, remove::*};
 
 mod add;
+mod move_skin;
 mod remove;
 
 #[derive(CommandModel, CreateCommand, SlashCommand)]
@@ -22,6 +23,8 @@ mod remove;
 pub enum Skin {
     #[command(name = "add")]
     Add(SkinAdd),
+    #[command(name = "move")]
+    Move(SkinMove),
     #[command(name = "remove")]
     Remove(SkinRemove),
 }
@@ -34,6 +37,18 @@ pub struct SkinAdd {
     skin: Attachment,
 }
 
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "move")]
+/// Move a skin to a different index of the skinlist
+pub struct SkinMove {
+    /// Current index of the skin
+    #[command(min_value = 1, max_value = 65_535)]
+    from: usize,
+    /// New index of the skin
+    #[command(min_value = 1, max_value = 65_535)]
+    to: usize,
+}
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "remove")]
 /// Remove a skin to the skinlist
@@ -46,6 +61,7 @@ pub struct SkinRemove {
 pub async fn slash_skin(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
     match Skin::from_interaction(command.input_data())? {
         Skin::Add(args) => add(ctx, command, args).await,
+        Skin::Move(args) => move_skin(ctx, command, args).await,
         Skin::Remove(args) => remove(ctx, command, args).await,
     }
 }
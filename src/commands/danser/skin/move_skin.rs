@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use eyre::Result;
+
+use crate::{
+    core::Context,
+    util::{builder::MessageBuilder, interaction::InteractionCommand, InteractionCommandExt},
+};
+
+use super::SkinMove;
+
+pub async fn move_skin(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: SkinMove,
+) -> Result<()> {
+    let SkinMove { from, to } = args;
+
+    let moved = match (from.checked_sub(1), to.checked_sub(1)) {
+        (Some(from), Some(to)) => ctx.skin_list().await.move_skin(from, to)?,
+        _ => None,
+    };
+
+    if let Some(skin) = moved {
+        let content = format!(
+            "Moved skin `{}` from index `{from}` to `{to}`",
+            skin.to_string_lossy()
+        );
+        let builder = MessageBuilder::new().embed(content);
+
+        command.callback(&ctx, builder, false).await?;
+    } else {
+        let len = ctx.skin_list().await.get()?.len();
+        let content = format!("Invalid skin index, must be between 1 and {len}");
+        command.error_callback(&ctx, content, false).await?;
+    }
+
+    Ok(())
+}
@@ -1,9 +1,15 @@
-use std::{fs, sync::Arc};
+use std::{
+    ffi::OsStr,
+    fmt::Write,
+    fs::{self, File},
+    path::Path,
+    sync::Arc,
+};
 
 use eyre::{Context as _, Result};
 
 use crate::{
-    core::{BotConfig, Context},
+    core::{settings::DanserSettings, BotConfig, Context},
     util::{builder::MessageBuilder, interaction::InteractionCommand, InteractionCommandExt},
 };
 
@@ -16,31 +22,103 @@ pub async fn remove(
 ) -> Result<()> {
     let SkinRemove { index } = args;
 
-    let skin_path = BotConfig::get().paths.skins();
-    let skin_dir = fs::read_dir(&skin_path).context("failed to read skins folder")?;
+    // Removing the skin and shifting the indices happens while holding
+    // the lock so that no one resolves an index in between
+    let (removed, len) = {
+        let mut skin_list = ctx.skin_list().await;
+
+        let removed = match index.checked_sub(1) {
+            Some(idx) => skin_list.remove(idx)?,
+            None => None,
+        };
+
+        let len = skin_list.get()?.len() + removed.is_some() as usize;
+
+        (removed, len)
+    };
+
+    if let Some(skin) = removed {
+        let mut content = format!("Successfully deleted skin `{}`", skin.to_string_lossy());
 
-    let mut skin_list = skin_dir
-        .collect::<Result<Vec<_>, _>>()
-        .context("failed to read entry in skins folder")?;
+        if len > index {
+            content.push_str("\nSkins after it moved up by one index.");
+        }
 
-    skin_list.sort_unstable_by_key(|entry| entry.file_name().to_ascii_lowercase());
+        match users_with_skin(&skin) {
+            Ok(0) => {}
+            Ok(count) => {
+                let plural = if count == 1 { "" } else { "s" };
 
-    if let Some(skin_to_remove) = skin_list.get(index - 1) {
-        fs::remove_dir_all(skin_to_remove.path())?;
+                let _ = write!(
+                    content,
+                    "\n:warning: {count} user{plural} had this skin selected \
+                    and will render with the fallback skin until they choose a new one."
+                );
+            }
+            Err(err) => warn!(
+                "{:?}",
+                err.wrap_err("failed to check users for removed skin")
+            ),
+        }
 
-        // Reset the skin list cache
-        ctx.skin_list().clear();
+        match ctx.guilds_with_default_skin(&skin) {
+            0 => {}
+            count => {
+                let plural = if count == 1 { "" } else { "s" };
+
+                let _ = write!(
+                    content,
+                    "\n:warning: {count} server{plural} had this skin as default \
+                    and will render with the fallback skin until they choose a new one."
+                );
+            }
+        }
 
-        let skin = skin_to_remove.file_name();
-        let content = format!("Successfully deleted skin `{}`", skin.to_string_lossy());
         let builder = MessageBuilder::new().embed(content);
 
         command.callback(&ctx, builder, false).await?;
     } else {
-        let len = skin_list.len();
         let content = format!("Invalid skin index, must be between 1 and {len}");
         command.error_callback(&ctx, content, false).await?;
     }
 
     Ok(())
 }
+
+/// Count the users whose stored settings reference the given skin
+fn users_with_skin(skin: &OsStr) -> Result<usize> {
+    let mut settings_path = BotConfig::get().paths.danser().to_owned();
+    settings_path.push("settings");
+
+    let dir = fs::read_dir(&settings_path).context("failed to read settings folder")?;
+    let mut count = 0;
+
+    for entry in dir {
+        let path = entry
+            .context("failed to read entry of settings folder")?
+            .path();
+
+        let is_user = path
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .map_or(false, |stem| stem.parse::<u64>().is_ok());
+
+        if !is_user {
+            continue;
+        }
+
+        let file =
+            File::open(&path).with_context(|| format!("failed to open settings file {path:?}"))?;
+
+        let settings: DanserSettings = serde_json::from_reader(file)
+            .with_context(|| format!("failed to deserialize settings file {path:?}"))?;
+
+        let current_skin = Path::new(&settings.skin.current_skin).file_name();
+
+        if current_skin == Some(skin) {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
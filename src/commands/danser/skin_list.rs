@@ -4,7 +4,11 @@ use command_macros::SlashCommand;
 use eyre::Result;
 use twilight_interactions::command::{CommandModel, CreateCommand};
 
-use crate::{core::Context, pagination::SkinListPagination, util::interaction::InteractionCommand};
+use crate::{
+    core::Context,
+    pagination::{PaginationOptions, SkinListPagination},
+    util::interaction::InteractionCommand,
+};
 
 #[derive(CreateCommand, CommandModel, SlashCommand)]
 #[command(name = "skinlist")]
@@ -15,10 +19,14 @@ pub struct SkinList;
 async fn slash_skinlist(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
     let skins = ctx
         .skin_list()
+        .await
         .get()?
         .iter()
         .map(|skin| skin.to_string_lossy().replace('_', " "))
         .collect();
 
-    SkinListPagination::builder(skins).start(ctx, command).await
+    let (kind, pages) = SkinListPagination::kind(skins);
+
+    ctx.paginate(command, kind, pages, PaginationOptions::default())
+        .await
 }
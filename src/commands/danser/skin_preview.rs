@@ -0,0 +1,141 @@
+use std::{sync::Arc, time::Instant};
+
+use command_macros::SlashCommand;
+use eyre::{Context as _, Result};
+use tokio::fs;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::{locale::Msg, BotConfig, Context, RenderOptions, ReplayData, TimePoints},
+    util::{disk, interaction::InteractionCommand, Authored, InteractionCommandExt},
+};
+
+use super::{render_output_channel, track_queue_status};
+
+/// Amount of seconds of the demo replay that will be rendered
+const PREVIEW_DURATION: u32 = 15;
+
+#[derive(CreateCommand, CommandModel, SlashCommand)]
+#[command(name = "skinpreview")]
+#[flags(SKIP_DEFER)]
+/// Render a short demo replay with one of the available skins
+pub struct SkinPreview {
+    #[command(min_value = 1, max_value = 65_535)]
+    /// Index of the skin as shown in /skinlist
+    index: usize,
+}
+
+async fn slash_skinpreview(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
+    let SkinPreview { index } = SkinPreview::from_interaction(command.input_data())?;
+
+    let skin_res = {
+        let mut skin_list = ctx.skin_list().await;
+        let skins = skin_list.get()?;
+
+        index
+            .checked_sub(1)
+            .and_then(|idx| skins.get(idx))
+            .map(|skin| skin.to_string_lossy().into_owned())
+            .ok_or(skins.len())
+    };
+
+    let skin = match skin_res {
+        Ok(skin) => skin,
+        Err(len) => {
+            let content = format!("Invalid skin index, must be between 1 and {len}");
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let demo = match ctx.demo_replay {
+        Some(ref demo) => demo,
+        None => {
+            let content = "No demo replay is available, skin previews are disabled.";
+            command.error_callback(&ctx, content, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    let output_channel = match render_output_channel(&ctx, &command).await? {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+
+    if disk::low_disk_space().is_some() {
+        let content = ctx.guild_locale(command.guild_id).get(Msg::LowDiskSpace);
+        command.error_callback(&ctx, content, false).await?;
+
+        return Ok(());
+    }
+
+    let locale = ctx.guild_locale(command.guild_id);
+
+    if let Some(content) = ctx.replay_queue.capacity_check(&ctx.stats, locale, 1).await {
+        command.error_callback(&ctx, content, false).await?;
+
+        return Ok(());
+    }
+
+    let guild_limit = ctx.guild_queue_limit(command.guild_id);
+    let guild_check = ctx
+        .replay_queue
+        .guild_capacity_check(command.guild_id, guild_limit, locale);
+
+    if let Some(content) = guild_check.await {
+        command.error_callback(&ctx, content, false).await?;
+
+        return Ok(());
+    }
+
+    command.defer(&ctx, false).await?;
+
+    let user = command.user_id()?;
+    let id = ctx.replay_queue.next_id();
+
+    // Copy the demo so that the rendered video gets a unique name
+    let mut replay_file = BotConfig::get().paths.downloads();
+    replay_file.push(format!("skin_preview_{id}.osr"));
+
+    fs::write(&replay_file, &demo.bytes)
+        .await
+        .with_context(|| format!("failed writing to file `{replay_file:?}`"))?;
+
+    let options = RenderOptions {
+        skin: Some(skin.clone()),
+        ..Default::default()
+    };
+
+    let replay_data = ReplayData {
+        id,
+        comparison: None,
+        guild: command.guild_id,
+        input_channel: command.channel_id,
+        interaction_token: Some(command.token.clone()),
+        local_map: None,
+        output_channel,
+        options,
+        path: replay_file,
+        queued_at: Instant::now(),
+        render_for: None,
+        replay: demo.replay.clone(),
+        skin_ini: None,
+        time_points: TimePoints {
+            start: 0,
+            end: PREVIEW_DURATION,
+        },
+        title: None,
+        user,
+    };
+
+    ctx.replay_queue.push(replay_data).await;
+
+    let job_id = locale.get(Msg::JobId);
+    let content =
+        format!("A preview of skin `{skin}` has been pushed to the queue!\n{job_id}: `{id}`");
+    tokio::spawn(track_queue_status(ctx, command, id, content));
+
+    Ok(())
+}
@@ -0,0 +1,621 @@
+use std::{fs, sync::Arc};
+
+use eyre::{ContextCompat, Result};
+use tokio::time::{sleep, Duration};
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    id::{marker::MessageMarker, Id},
+};
+
+use crate::{
+    core::{locale::Msg, Context, ReplayData, TimePoints},
+    util::{
+        builder::{EmbedBuilder, MessageBuilder, ModalBuilder},
+        interaction::{InteractionCommand, InteractionComponent, InteractionModal},
+        mods_string, Authored, ComponentExt, InteractionCommandExt, ModalExt,
+    },
+};
+
+use super::track_queue_status;
+
+/// Seconds by which the buttons move the start or end
+const STEP: u32 = 5;
+
+/// Unconfirmed renders are dropped after this, before their interaction token expires
+const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A render whose start and end are still being adjusted through buttons
+pub struct PendingTrim {
+    command: InteractionCommand,
+    data: ReplayData,
+    trim: Trim,
+    map_title: String,
+    clock_rate: f64,
+    /// Warnings to show alongside the job id once the render is queued
+    warnings: String,
+}
+
+impl PendingTrim {
+    fn build(&self, notice: Option<&str>) -> MessageBuilder<'static> {
+        let TimePoints { start, end } = self.trim.time_points;
+        let map_seconds = self.trim.map_seconds;
+
+        let end = match end {
+            0 => format!("end of map ({})", mm_ss(map_seconds)),
+            end => mm_ss(end),
+        };
+
+        let length = self
+            .trim
+            .time_points
+            .playback_duration(map_seconds, self.clock_rate);
+
+        let mut description = format!(
+            "**{map}** `+{mods}`\n\
+            Start: `{start}` • End: `{end}`\n\
+            Video length: `{length}`\n\n\
+            Confirm to queue the render, without any changes the full replay is rendered.",
+            map = self.map_title,
+            mods = mods_string(self.data.replay.mods),
+            start = mm_ss(start),
+            length = mm_ss(length),
+        );
+
+        if let Some(notice) = notice {
+            description.push_str("\n\n:warning: ");
+            description.push_str(notice);
+        }
+
+        let embed = EmbedBuilder::new()
+            .title("Adjust the render's start and end")
+            .description(description);
+
+        MessageBuilder::new()
+            .embed(embed)
+            .components(self.trim.components())
+    }
+
+    /// Delete the stored replays since the render won't be queued
+    fn remove_files(&self) {
+        let compared = self.data.comparison.as_ref().map(|compared| &compared.path);
+
+        for path in Some(&self.data.path).into_iter().chain(compared) {
+            if let Err(err) = fs::remove_file(path) {
+                warn!("failed to remove replay file {path:?}: {err}");
+            }
+        }
+    }
+}
+
+/// Respond with the trim editor instead of queueing the render right away
+pub(super) async fn start_trim(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    data: ReplayData,
+    map_title: String,
+    map_seconds: u32,
+    warnings: String,
+) -> Result<()> {
+    let speed = data.options.speed.map_or(1.0, |speed| speed.rate);
+    let clock_rate = data.replay.clock_rate() * speed;
+
+    let pending = PendingTrim {
+        trim: Trim::new(data.time_points, map_seconds),
+        command,
+        data,
+        map_title,
+        clock_rate,
+        warnings,
+    };
+
+    let builder = pending.build(None);
+    let msg = pending
+        .command
+        .update(&ctx, &builder)
+        .await?
+        .model()
+        .await?
+        .id;
+
+    ctx.pending_trims.own(msg).await.insert(pending);
+    tokio::spawn(expire_trim(Arc::clone(&ctx), msg));
+
+    Ok(())
+}
+
+async fn expire_trim(ctx: Arc<Context>, msg: Id<MessageMarker>) {
+    sleep(TIMEOUT).await;
+
+    let pending = match ctx.pending_trims.lock(&msg).await.remove() {
+        Some(pending) => pending,
+        None => return,
+    };
+
+    pending.remove_files();
+
+    let embed = EmbedBuilder::new().description("Timed out, the replay was not queued.");
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+
+    if let Err(err) = pending.command.update(&ctx, &builder).await {
+        warn!("failed to remove trim components: {err:?}");
+    }
+}
+
+async fn handle_trim_component(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+    f: fn(&mut Trim),
+) -> Result<()> {
+    let builder = {
+        let mut guard = ctx.pending_trims.lock(&component.message.id).await;
+
+        match guard.get_mut() {
+            Some(pending) if pending.data.user == component.user_id()? => {
+                f(&mut pending.trim);
+
+                pending.build(None)
+            }
+            Some(_) => return Ok(()),
+            None => MessageBuilder::new().components(Vec::new()),
+        }
+    };
+
+    component.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+pub async fn handle_trim_start_back(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_component(ctx, component, |trim| trim.shift_start(-(STEP as i64))).await
+}
+
+pub async fn handle_trim_start_forward(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_component(ctx, component, |trim| trim.shift_start(STEP as i64)).await
+}
+
+pub async fn handle_trim_end_back(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_component(ctx, component, |trim| trim.shift_end(-(STEP as i64))).await
+}
+
+pub async fn handle_trim_end_forward(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_component(ctx, component, |trim| trim.shift_end(STEP as i64)).await
+}
+
+pub async fn handle_trim_reset(ctx: Arc<Context>, component: InteractionComponent) -> Result<()> {
+    handle_trim_component(ctx, component, Trim::reset).await
+}
+
+pub async fn handle_trim_start_custom(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_custom(ctx, component, TrimInput::Start).await
+}
+
+pub async fn handle_trim_end_custom(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    handle_trim_custom(ctx, component, TrimInput::End).await
+}
+
+#[derive(Copy, Clone)]
+enum TrimInput {
+    Start,
+    End,
+}
+
+async fn handle_trim_custom(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+    input: TrimInput,
+) -> Result<()> {
+    let current = {
+        let guard = ctx.pending_trims.lock(&component.message.id).await;
+
+        match guard.get() {
+            Some(pending) if pending.data.user == component.user_id()? => {
+                let TimePoints { start, end } = pending.trim.time_points;
+
+                match input {
+                    TrimInput::Start => start,
+                    TrimInput::End => end,
+                }
+            }
+            Some(_) => return Ok(()),
+            None => {
+                let builder = MessageBuilder::new().components(Vec::new());
+                component.callback(&ctx, builder).await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let (modal_id, title) = match input {
+        TrimInput::Start => ("trim_start", "Start of the render"),
+        TrimInput::End => ("trim_end", "End of the render"),
+    };
+
+    let mut modal = ModalBuilder::new("time_input", "Minutes and seconds, e.g. 1:30")
+        .modal_id(modal_id)
+        .min_len(1)
+        .max_len(6)
+        .title(title);
+
+    // An end of zero means the end of the map so the input starts empty
+    if current > 0 {
+        modal = modal.value(mm_ss(current));
+    }
+
+    component.modal(&ctx, modal).await?;
+
+    Ok(())
+}
+
+pub async fn handle_trim_start_modal(ctx: Arc<Context>, modal: InteractionModal) -> Result<()> {
+    handle_trim_modal(ctx, modal, TrimInput::Start).await
+}
+
+pub async fn handle_trim_end_modal(ctx: Arc<Context>, modal: InteractionModal) -> Result<()> {
+    handle_trim_modal(ctx, modal, TrimInput::End).await
+}
+
+async fn handle_trim_modal(
+    ctx: Arc<Context>,
+    modal: InteractionModal,
+    input: TrimInput,
+) -> Result<()> {
+    let value = modal
+        .data
+        .components
+        .first()
+        .and_then(|component| component.components.first())
+        .and_then(|component| component.value.as_deref())
+        .context("missing input for time point")?;
+
+    let msg = match modal.message {
+        Some(ref msg) => msg.id,
+        None => {
+            warn!("received trim modal without message");
+
+            return Ok(());
+        }
+    };
+
+    let builder = {
+        let mut guard = ctx.pending_trims.lock(&msg).await;
+
+        match guard.get_mut() {
+            Some(pending) if pending.data.user == modal.user_id()? => {
+                let res = TimePoints::parse_single(value).and_then(|seconds| match input {
+                    TrimInput::Start => pending.trim.set_start(seconds),
+                    TrimInput::End => pending.trim.set_end(seconds),
+                });
+
+                pending.build(res.err())
+            }
+            Some(_) => return Ok(()),
+            None => MessageBuilder::new().components(Vec::new()),
+        }
+    };
+
+    modal.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+pub async fn handle_trim_confirm(ctx: Arc<Context>, component: InteractionComponent) -> Result<()> {
+    let pending = match take_pending(&ctx, &component).await? {
+        Some(pending) => pending,
+        None => return Ok(()),
+    };
+
+    let locale = ctx.guild_locale(component.guild_id);
+
+    // The queue might have filled up while the user was adjusting
+    if let Some(content) = ctx.replay_queue.capacity_check(&ctx.stats, locale, 1).await {
+        pending.remove_files();
+
+        let embed = EmbedBuilder::new().description(content);
+        let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+        component.callback(&ctx, builder).await?;
+
+        return Ok(());
+    }
+
+    let PendingTrim {
+        command,
+        mut data,
+        trim,
+        warnings,
+        ..
+    } = pending;
+
+    data.time_points = trim.time_points;
+
+    let id = data.id;
+    let mods = mods_string(data.replay.mods);
+    ctx.render_cooldowns.start(data.user);
+    ctx.replay_queue.push(data).await;
+
+    let content = format!(
+        "{pushed} `+{mods}`\n{job_id}: `{id}`{warnings}",
+        pushed = locale.get(Msg::ReplayPushed),
+        job_id = locale.get(Msg::JobId),
+    );
+
+    let embed = EmbedBuilder::new().description(&content);
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+    component.callback(&ctx, builder).await?;
+
+    tokio::spawn(track_queue_status(ctx, command, id, content));
+
+    Ok(())
+}
+
+pub async fn handle_trim_cancel(ctx: Arc<Context>, component: InteractionComponent) -> Result<()> {
+    let pending = match take_pending(&ctx, &component).await? {
+        Some(pending) => pending,
+        None => return Ok(()),
+    };
+
+    pending.remove_files();
+
+    let embed = EmbedBuilder::new().description("Cancelled, the replay was not queued.");
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+    component.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+/// Remove the pending trim of the component's message if the user is its author.
+///
+/// If there is none, e.g. because it timed out, the components are removed.
+async fn take_pending(
+    ctx: &Context,
+    component: &InteractionComponent,
+) -> Result<Option<PendingTrim>> {
+    let user = component.user_id()?;
+    let mut guard = ctx.pending_trims.lock(&component.message.id).await;
+
+    let is_author = match guard.get() {
+        Some(pending) => pending.data.user == user,
+        None => {
+            drop(guard);
+
+            let builder = MessageBuilder::new().components(Vec::new());
+            component.callback(ctx, builder).await?;
+
+            return Ok(None);
+        }
+    };
+
+    Ok(if is_author { guard.remove() } else { None })
+}
+
+/// Time points of a render within the bounds of its map
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Trim {
+    time_points: TimePoints,
+    map_seconds: u32,
+}
+
+impl Trim {
+    fn new(time_points: TimePoints, map_seconds: u32) -> Self {
+        let mut trim = Self {
+            time_points: TimePoints { start: 0, end: 0 },
+            map_seconds,
+        };
+
+        // Values given through the command might exceed the map
+        if trim.set_end(time_points.end).is_ok() {
+            let _ = trim.set_start(time_points.start);
+        }
+
+        trim
+    }
+
+    /// The end in seconds where zero stands for the end of the map
+    fn end(self) -> u32 {
+        match self.time_points.end {
+            0 => self.map_seconds,
+            end => end.min(self.map_seconds),
+        }
+    }
+
+    fn set_start(&mut self, start: u32) -> Result<(), &'static str> {
+        if start >= self.end() {
+            return Err("The start must be before the end");
+        }
+
+        self.time_points.start = start;
+
+        Ok(())
+    }
+
+    fn set_end(&mut self, end: u32) -> Result<(), &'static str> {
+        if end == 0 || end >= self.map_seconds {
+            self.time_points.end = 0;
+        } else if end <= self.time_points.start {
+            return Err("The end must be after the start");
+        } else {
+            self.time_points.end = end;
+        }
+
+        Ok(())
+    }
+
+    fn shift_start(&mut self, delta: i64) {
+        let max = self.end().saturating_sub(1) as i64;
+        let start = (self.time_points.start as i64 + delta).clamp(0, max.max(0));
+        self.time_points.start = start as u32;
+    }
+
+    fn shift_end(&mut self, delta: i64) {
+        let min = self.time_points.start as i64 + 1;
+        let end = (self.end() as i64 + delta).clamp(min, (self.map_seconds as i64).max(min));
+        let _ = self.set_end(end as u32);
+    }
+
+    fn reset(&mut self) {
+        self.time_points = TimePoints { start: 0, end: 0 };
+    }
+
+    fn components(self) -> Vec<Component> {
+        let TimePoints { start, end } = self.time_points;
+
+        let button = |custom_id: &str, label: String, style, disabled| {
+            Component::Button(Button {
+                custom_id: Some(custom_id.to_owned()),
+                disabled,
+                emoji: None,
+                label: Some(label),
+                style,
+                url: None,
+            })
+        };
+
+        let secondary = ButtonStyle::Secondary;
+
+        let start_row = vec![
+            button(
+                "trim_start_back",
+                format!("Start -{STEP}s"),
+                secondary,
+                start == 0,
+            ),
+            button(
+                "trim_start_forward",
+                format!("Start +{STEP}s"),
+                secondary,
+                start + 1 >= self.end(),
+            ),
+            button(
+                "trim_start_custom",
+                "Set start".to_owned(),
+                secondary,
+                false,
+            ),
+        ];
+
+        let end_row = vec![
+            button(
+                "trim_end_back",
+                format!("End -{STEP}s"),
+                secondary,
+                self.end() <= start + 1,
+            ),
+            button(
+                "trim_end_forward",
+                format!("End +{STEP}s"),
+                secondary,
+                end == 0,
+            ),
+            button("trim_end_custom", "Set end".to_owned(), secondary, false),
+        ];
+
+        let confirm_row = vec![
+            button(
+                "trim_confirm",
+                "Confirm".to_owned(),
+                ButtonStyle::Success,
+                false,
+            ),
+            button("trim_reset", "Reset".to_owned(), secondary, false),
+            button(
+                "trim_cancel",
+                "Cancel".to_owned(),
+                ButtonStyle::Danger,
+                false,
+            ),
+        ];
+
+        [start_row, end_row, confirm_row]
+            .into_iter()
+            .map(|components| Component::ActionRow(ActionRow { components }))
+            .collect()
+    }
+}
+
+fn mm_ss(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trim(start: u32, end: u32) -> Trim {
+        Trim::new(TimePoints { start, end }, 120)
+    }
+
+    #[test]
+    fn full_replay_by_default() {
+        let trim = trim(0, 0);
+
+        assert_eq!(trim.time_points, TimePoints { start: 0, end: 0 });
+        assert_eq!(trim.end(), 120);
+    }
+
+    #[test]
+    fn clamps_command_values() {
+        assert_eq!(trim(30, 500).time_points, TimePoints { start: 30, end: 0 });
+        assert_eq!(trim(90, 60).time_points, TimePoints { start: 0, end: 60 });
+    }
+
+    #[test]
+    fn shifts_within_bounds() {
+        let mut trim = trim(0, 0);
+
+        trim.shift_start(-5);
+        assert_eq!(trim.time_points.start, 0);
+
+        trim.shift_end(5);
+        assert_eq!(trim.time_points.end, 0);
+
+        trim.shift_end(-5);
+        assert_eq!(trim.time_points.end, 115);
+
+        trim.shift_start(200);
+        assert_eq!(trim.time_points.start, 114);
+
+        trim.shift_end(-5);
+        assert_eq!(trim.time_points.end, 115);
+    }
+
+    #[test]
+    fn end_at_map_end_is_zero() {
+        let mut trim = trim(0, 60);
+
+        trim.shift_end(60);
+        assert_eq!(trim.time_points.end, 0);
+
+        assert!(trim.set_end(90).is_ok());
+        assert_eq!(trim.time_points.end, 90);
+
+        assert!(trim.set_end(120).is_ok());
+        assert_eq!(trim.time_points.end, 0);
+    }
+
+    #[test]
+    fn rejects_crossing_values() {
+        let mut trim = trim(30, 60);
+
+        assert!(trim.set_start(60).is_err());
+        assert!(trim.set_end(30).is_err());
+        assert_eq!(trim.time_points, TimePoints { start: 30, end: 60 });
+    }
+}
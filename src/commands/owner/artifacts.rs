@@ -0,0 +1,164 @@
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fmt::Write,
+    fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use eyre::{Context as _, Result};
+
+use crate::{
+    core::BotConfig,
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        numbers::with_comma_int,
+        InteractionCommandExt,
+    },
+    Context,
+};
+
+use super::OwnerArtifacts;
+
+/// Recently modified files are never considered orphaned because
+/// replays are stored shortly before they're pushed into the queue
+const GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+pub async fn artifacts(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: OwnerArtifacts,
+) -> Result<()> {
+    let OwnerArtifacts { clean } = args;
+    let clean = clean.unwrap_or(false);
+
+    // Replay files and rendered videos share the same file stem
+    let mut in_use: HashSet<_> = ctx
+        .replay_queue
+        .queue
+        .lock()
+        .await
+        .iter()
+        .flat_map(|data| {
+            let compared = data.comparison.as_ref().map(|compared| &compared.path);
+
+            [Some(&data.path), compared]
+        })
+        .flatten()
+        .filter_map(|path| path.file_stem().map(ToOwned::to_owned))
+        .collect();
+
+    // Videos that may still be uploaded again
+    in_use.extend(ctx.render_outputs.file_stems());
+
+    let paths = &BotConfig::get().paths;
+    let mut description = String::new();
+    let mut reclaimed = 0;
+
+    for (name, dir) in [
+        ("Downloads", paths.downloads()),
+        ("Replays", paths.replays()),
+    ] {
+        let stats = match scan_dir(&dir, &in_use, clean) {
+            Ok(stats) => stats,
+            Err(err) => {
+                let _ = command
+                    .error_callback(&ctx, "Failed to scan artifacts", false)
+                    .await;
+
+                return Err(err);
+            }
+        };
+
+        let _ = writeln!(
+            description,
+            "**{name}**: {} files ({}), {} orphaned ({})",
+            with_comma_int(stats.count),
+            format_bytes(stats.bytes),
+            with_comma_int(stats.orphaned_count),
+            format_bytes(stats.orphaned_bytes),
+        );
+
+        reclaimed += stats.reclaimed;
+    }
+
+    if clean {
+        let _ = write!(description, "\nReclaimed {}", format_bytes(reclaimed));
+    }
+
+    let embed = EmbedBuilder::new()
+        .title("Render artifacts")
+        .description(description);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct ArtifactStats {
+    count: usize,
+    bytes: u64,
+    orphaned_count: usize,
+    orphaned_bytes: u64,
+    reclaimed: u64,
+}
+
+/// Gather stats about the files in the directory and optionally
+/// delete those that don't belong to a queued render.
+fn scan_dir(dir: &Path, in_use: &HashSet<OsString>, clean: bool) -> Result<ArtifactStats> {
+    let mut stats = ArtifactStats::default();
+    let now = SystemTime::now();
+
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read directory {dir:?}"))?;
+
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read entry of {dir:?}"))?
+            .path();
+
+        let metadata =
+            fs::metadata(&path).with_context(|| format!("failed to get metadata of {path:?}"))?;
+
+        if !metadata.is_file() {
+            continue;
+        }
+
+        stats.count += 1;
+        stats.bytes += metadata.len();
+
+        let is_queued = path.file_stem().map_or(false, |stem| in_use.contains(stem));
+
+        let is_recent = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map_or(true, |age| age < GRACE_PERIOD);
+
+        if is_queued || is_recent {
+            continue;
+        }
+
+        stats.orphaned_count += 1;
+        stats.orphaned_bytes += metadata.len();
+
+        if clean {
+            match fs::remove_file(&path) {
+                Ok(_) => stats.reclaimed += metadata.len(),
+                Err(err) => warn!("failed to remove artifact {path:?}: {err}"),
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+
+    format!("{:.2}MB", bytes as f64 / MB)
+}
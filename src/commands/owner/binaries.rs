@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use eyre::Result;
+
+use crate::{
+    core::BinaryVersions,
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        InteractionCommandExt,
+    },
+    Context,
+};
+
+pub async fn binaries(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let versions = match BinaryVersions::detect().await {
+        Ok(versions) => versions,
+        Err(err) => {
+            let content = format!("Binary check failed: {err:#}");
+            let _ = command.error_callback(&ctx, content, false).await;
+
+            return Err(err);
+        }
+    };
+
+    let mut description = format!(
+        "**danser**: `{}`\n**ffmpeg**: `{}`\n**encoder**: `{}`",
+        versions.danser,
+        versions.ffmpeg,
+        versions.encoder.ffmpeg_name()
+    );
+
+    // The check passed so the hardware encoder gets another chance
+    if ctx.reset_hardware_encoding() {
+        description.push_str("\n\nRenders no longer fall back to `libx264`");
+    }
+
+    *ctx.binary_versions.write().unwrap() = versions;
+
+    let embed = EmbedBuilder::new()
+        .title("Binaries are runnable")
+        .description(description);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use eyre::Result;
+
+use crate::{
+    pagination::{GuildListEntry, GuildListPagination, PaginationOptions},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+    Context,
+};
+
+pub async fn guilds(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let mut guilds = ctx.cache.guilds(|guild| GuildListEntry {
+        id: guild.id(),
+        name: guild.name().to_owned(),
+        members: guild.member_count(),
+        has_output_channel: false,
+    });
+
+    if guilds.is_empty() {
+        command
+            .error_callback(&ctx, "There are no servers in the cache", false)
+            .await?;
+
+        return Ok(());
+    }
+
+    for guild in guilds.iter_mut() {
+        guild.has_output_channel = ctx
+            .guild_settings(guild.id, |s| s.output_channel.is_some())
+            .unwrap_or(false);
+    }
+
+    guilds.sort_unstable_by_key(|guild| Reverse(guild.members));
+
+    let (kind, pages) = GuildListPagination::kind(guilds);
+
+    ctx.paginate(command, kind, pages, PaginationOptions::default())
+        .await
+}
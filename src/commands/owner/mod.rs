@@ -9,24 +9,99 @@ use crate::{
     Context,
 };
 
-use self::cache::*;
+use self::{
+    artifacts::*, binaries::*, cache::*, guilds::*, queue::*, ratelimits::*, reload_guild::*,
+};
 
+mod artifacts;
+mod binaries;
 mod cache;
+mod guilds;
+mod queue;
+mod ratelimits;
+mod reload_guild;
 
 #[derive(CommandModel, CreateCommand, SlashCommand)]
 #[command(name = "owner")]
 #[flags(ONLY_OWNER, SKIP_DEFER)]
 /// You won't be able to use this :^)
 pub enum Owner {
+    #[command(name = "artifacts")]
+    Artifacts(OwnerArtifacts),
+    #[command(name = "binaries")]
+    Binaries(OwnerBinaries),
     #[command(name = "cache")]
     Cache(OwnerCache),
+    #[command(name = "guilds")]
+    Guilds(OwnerGuilds),
+    #[command(name = "queue")]
+    Queue(OwnerQueue),
+    #[command(name = "ratelimits")]
+    Ratelimits(OwnerRatelimits),
+    #[command(name = "reloadguild")]
+    ReloadGuild(OwnerReloadGuild),
 }
 
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "artifacts")]
+/// Display stats about leftover render files
+pub struct OwnerArtifacts {
+    /// Delete files that don't belong to a queued render
+    clean: Option<bool>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "binaries")]
+/// Check that danser and ffmpeg are runnable and display their versions
+pub struct OwnerBinaries;
+
 #[derive(CommandModel, CreateCommand)]
 #[command(name = "cache")]
 /// Display stats about the internal cache
 pub struct OwnerCache;
 
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "guilds")]
+/// List all servers the bot is in
+pub struct OwnerGuilds;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "queue")]
+/// Manage the replay queue
+pub enum OwnerQueue {
+    #[command(name = "clear")]
+    Clear(OwnerQueueClear),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "clear")]
+/// Remove all waiting replays from the queue
+pub struct OwnerQueueClear {
+    /// Also abort the render that's currently in progress
+    force: Option<bool>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "ratelimits")]
+/// Display or adjust the ratelimits of external sites
+pub struct OwnerRatelimits {
+    /// Name of the site to adjust e.g. OsuReplay
+    site: Option<String>,
+    #[command(min_value = 1)]
+    /// Only allow one request per this many milliseconds until reset or restart
+    interval: Option<i64>,
+    /// Restore the site's regular ratelimit
+    reset: Option<bool>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "reloadguild")]
+/// Reload a server's settings from the settings file
+pub struct OwnerReloadGuild {
+    /// Id of the server
+    guild: String,
+}
+
 // * EXAMPLE:
 // #[derive(CommandModel, CreateCommand)]
 // #[command(name = "interval")]
@@ -38,6 +113,12 @@ pub struct OwnerCache;
 
 async fn slash_owner(ctx: Arc<Context>, mut command: InteractionCommand) -> Result<()> {
     match Owner::from_interaction(command.input_data())? {
+        Owner::Artifacts(args) => artifacts(ctx, command, args).await,
+        Owner::Binaries(_) => binaries(ctx, command).await,
         Owner::Cache(_) => cache(ctx, command).await,
+        Owner::Guilds(_) => guilds(ctx, command).await,
+        Owner::Queue(OwnerQueue::Clear(args)) => queue_clear(ctx, command, args).await,
+        Owner::Ratelimits(args) => ratelimits(ctx, command, args).await,
+        Owner::ReloadGuild(args) => reload_guild(ctx, command, args).await,
     }
 }
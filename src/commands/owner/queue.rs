@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use eyre::Result;
+
+use crate::{
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        Authored, ChannelExt, InteractionCommandExt,
+    },
+    Context,
+};
+
+use super::OwnerQueueClear;
+
+pub async fn queue_clear(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: OwnerQueueClear,
+) -> Result<()> {
+    let force = args.force.unwrap_or(false);
+    let (removed, aborted) = ctx.replay_queue.clear(force).await;
+
+    warn!(
+        "Owner {} cleared the queue: removed {} entries{}",
+        command.user_id()?,
+        removed.len(),
+        if aborted {
+            " and aborted the current render"
+        } else {
+            ""
+        },
+    );
+
+    for data in removed.iter() {
+        let content = format!(
+            "<@{user}> your replay `{name}` was removed from the queue by the bot owner.\n\
+            Feel free to submit it again later.",
+            user = data.user,
+            name = data.display_title(),
+        );
+
+        let _ = data.input_channel.error(&ctx, content).await;
+    }
+
+    let mut description = format!("Removed {} entries from the queue", removed.len());
+
+    if aborted {
+        description.push_str("\nThe current render is being aborted");
+    } else if !force {
+        description.push_str("\nThe current render, if any, was kept");
+    }
+
+    let embed = EmbedBuilder::new()
+        .title("Queue cleared")
+        .description(description);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+use eyre::Result;
+
+use crate::{
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        Authored, InteractionCommandExt,
+    },
+    Context,
+};
+
+use super::OwnerRatelimits;
+
+pub async fn ratelimits(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: OwnerRatelimits,
+) -> Result<()> {
+    let OwnerRatelimits {
+        site,
+        interval,
+        reset,
+    } = args;
+
+    let mut content = None;
+
+    if let Some(site) = site {
+        let res = match (reset, interval) {
+            (Some(true), _) => ctx.client().reset_ratelimit(&site),
+            (_, Some(interval)) => {
+                let interval = Duration::from_millis(interval as u64);
+
+                ctx.client().tighten_ratelimit(&site, interval)
+            }
+            _ => {
+                let content = "Specify either an interval or reset the ratelimit";
+                command.error_callback(&ctx, content, false).await?;
+
+                return Ok(());
+            }
+        };
+
+        let info = match res {
+            Ok(info) => info,
+            Err(err) => {
+                let content = format!("Failed to adjust the ratelimit: {err}");
+                command.error_callback(&ctx, content, false).await?;
+
+                return Ok(());
+            }
+        };
+
+        let interval = info.refill_interval.as_millis();
+
+        if info.tightened {
+            warn!(
+                "Owner {} tightened the ratelimit of {} to one request per {interval}ms",
+                command.user_id()?,
+                info.site,
+            );
+
+            content = Some(format!(
+                "Tightened `{}` to one request per {interval}ms until reset or restart",
+                info.site
+            ));
+        } else {
+            warn!(
+                "Owner {} reset the ratelimit of {}",
+                command.user_id()?,
+                info.site,
+            );
+
+            content = Some(format!("Reset `{}` to its regular ratelimit", info.site));
+        }
+    }
+
+    let mut description = String::with_capacity(512);
+
+    for info in ctx.client().ratelimits() {
+        let _ = write!(
+            description,
+            "`{site}`: {tokens}/{max} tokens, +1 every {interval}ms",
+            site = info.site,
+            tokens = info.tokens,
+            max = info.max,
+            interval = info.refill_interval.as_millis(),
+        );
+
+        if info.tightened {
+            let _ = write!(
+                description,
+                " (tightened, regularly {}ms)",
+                info.default_interval.as_millis()
+            );
+        }
+
+        description.push('\n');
+    }
+
+    let embed = EmbedBuilder::new()
+        .title("Ratelimits")
+        .description(description);
+
+    let mut builder = MessageBuilder::new().embed(embed);
+
+    if let Some(content) = content {
+        builder = builder.content(content);
+    }
+
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::{fmt::Write, sync::Arc};
+
+use eyre::{Context as _, Result};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    core::settings::ServerExport,
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        InteractionCommandExt,
+    },
+    Context,
+};
+
+use super::OwnerReloadGuild;
+
+pub async fn reload_guild(
+    ctx: Arc<Context>,
+    command: InteractionCommand,
+    args: OwnerReloadGuild,
+) -> Result<()> {
+    let guild = match args.guild.trim().parse::<Id<GuildMarker>>() {
+        Ok(guild) => guild,
+        Err(_) => {
+            let content = format!("`{}` is not a valid server id", args.guild);
+            command.error_callback(&ctx, content, false).await?;
+
+            return Ok(());
+        }
+    };
+
+    let server = match ctx.reload_guild_settings(guild) {
+        Ok(server) => server,
+        Err(err) => {
+            let content = "Failed to reload the server's settings";
+            let _ = command.error_callback(&ctx, content, false).await;
+
+            return Err(err);
+        }
+    };
+
+    let server = match server {
+        Some(server) => server,
+        None => {
+            let content = format!(
+                "The settings file has no entry for server {guild}, \
+                its cached settings were removed"
+            );
+            let builder = MessageBuilder::new().embed(content);
+            command.callback(&ctx, builder, false).await?;
+
+            return Ok(());
+        }
+    };
+
+    let mut description = String::from("Input channels:");
+
+    if server.input_channels.is_empty() {
+        description.push_str(" None");
+    }
+
+    for channel in server.input_channels.iter() {
+        let _ = write!(description, " <#{channel}>");
+    }
+
+    match server.output_channel {
+        Some(channel) => {
+            let _ = write!(description, "\nOutput channel: <#{channel}>");
+        }
+        None => description.push_str("\nOutput channel: None"),
+    }
+
+    let export = serde_json::to_string_pretty(&ServerExport::from(&server))
+        .context("failed to serialize server settings")?;
+
+    let _ = write!(description, "\n```json\n{export}\n```");
+
+    let embed = EmbedBuilder::new()
+        .title(format!("Reloaded settings of server {guild}"))
+        .description(description);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use command_macros::SlashCommand;
+use eyre::Result;
+use twilight_interactions::command::CreateCommand;
+use twilight_model::channel::embed::EmbedField;
+
+use crate::{
+    core::{
+        settings::DanserSettings, BotConfig, Context, HighlightMode, PlaybackSpeed, QualityPreset,
+        RenderOptions, Resolution, VisualMods,
+    },
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        InteractionCommandExt,
+    },
+};
+
+#[derive(CreateCommand, SlashCommand)]
+#[command(
+    name = "capabilities",
+    help = "Shows what the bot can render and within which limits. \
+    All values are taken from the bot's current configuration."
+)]
+#[flags(SKIP_DEFER)]
+/// Display what the bot can render
+pub struct Capabilities;
+
+async fn slash_capabilities(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let config = BotConfig::get();
+    let skin_count = ctx.skin_list().await.get()?.len();
+
+    let video = match DanserSettings::load_default() {
+        Ok(settings) => {
+            let recording = &settings.recording;
+
+            format!(
+                "{}x{} at {} FPS",
+                recording.frame_width, recording.frame_height, recording.fps
+            )
+        }
+        Err(err) => {
+            warn!("{:?}", err.wrap_err("failed to load default settings"));
+
+            "-".to_owned()
+        }
+    };
+
+    let qualities = [
+        QualityPreset::Fast,
+        QualityPreset::Balanced,
+        QualityPreset::High,
+    ]
+    .map(QualityPreset::name)
+    .join(", ");
+
+    let cooldown = match config.render_cooldown {
+        0 => "None".to_owned(),
+        secs => format!("{secs}s"),
+    };
+
+    let fields = vec![
+        EmbedField {
+            inline: true,
+            name: "Game modes".to_owned(),
+            value: "osu!standard".to_owned(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Mods".to_owned(),
+            value: format!(
+                "All, visuals of {} can be overridden",
+                VisualMods::allowed_label()
+            ),
+        },
+        EmbedField {
+            inline: true,
+            name: "Replay length".to_owned(),
+            value: format!(
+                "Unlimited, highlights up to {} clips of {}s total",
+                HighlightMode::MAX_SEGMENTS,
+                HighlightMode::MAX_TOTAL_SECONDS
+            ),
+        },
+        EmbedField {
+            inline: true,
+            name: "Video".to_owned(),
+            value: video,
+        },
+        EmbedField {
+            inline: true,
+            name: "Quality presets".to_owned(),
+            value: qualities,
+        },
+        EmbedField {
+            inline: true,
+            name: "Resolutions".to_owned(),
+            value: format!(
+                "{} or custom up to 4K with even sides",
+                Resolution::preset_label()
+            ),
+        },
+        EmbedField {
+            inline: true,
+            name: "Playback speed".to_owned(),
+            value: format!(
+                "{}x to {}x",
+                PlaybackSpeed::RATE.start(),
+                PlaybackSpeed::RATE.end()
+            ),
+        },
+        EmbedField {
+            inline: true,
+            name: "Cursor scale".to_owned(),
+            value: format!(
+                "{} to {}",
+                RenderOptions::CURSOR_SCALE.start(),
+                RenderOptions::CURSOR_SCALE.end()
+            ),
+        },
+        EmbedField {
+            inline: true,
+            name: "Skins".to_owned(),
+            value: match config.max_skins {
+                Some(max_skins) => format!("{skin_count}/{max_skins}"),
+                None => skin_count.to_string(),
+            },
+        },
+        EmbedField {
+            inline: true,
+            name: "Queue limit".to_owned(),
+            value: format!("{} per server", ctx.guild_queue_limit(command.guild_id)),
+        },
+        EmbedField {
+            inline: true,
+            name: "Cooldown".to_owned(),
+            value: cooldown,
+        },
+    ];
+
+    let embed = EmbedBuilder::new()
+        .title("What can be rendered")
+        .fields(fields);
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
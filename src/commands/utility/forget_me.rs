@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use command_macros::SlashCommand;
+use eyre::Result;
+use twilight_interactions::command::CreateCommand;
+use twilight_model::{
+    application::component::{button::ButtonStyle, ActionRow, Button, Component},
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    core::settings::DanserSettings,
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::{InteractionCommand, InteractionComponent},
+        Authored, ComponentExt, InteractionCommandExt,
+    },
+    Context,
+};
+
+#[derive(CreateCommand, SlashCommand)]
+#[command(name = "forgetme")]
+#[flags(SKIP_DEFER)]
+/// Delete all data that the bot stores about you
+pub struct ForgetMe;
+
+pub async fn slash_forgetme(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let description = "This deletes your danser settings, your preferences, your renders in \
+        the servers' render histories, and your videos that are kept for re-uploads.\n\
+        Renders that are already uploaded stay online.\n\n\
+        **This can not be undone.**";
+
+    let embed = EmbedBuilder::new()
+        .title("Delete your data?")
+        .description(description);
+
+    let confirm = Button {
+        custom_id: Some("forget_me_confirm".to_owned()),
+        disabled: false,
+        emoji: None,
+        label: Some("Delete my data".to_owned()),
+        style: ButtonStyle::Danger,
+        url: None,
+    };
+
+    let cancel = Button {
+        custom_id: Some("forget_me_cancel".to_owned()),
+        disabled: false,
+        emoji: None,
+        label: Some("Cancel".to_owned()),
+        style: ButtonStyle::Secondary,
+        url: None,
+    };
+
+    let components = vec![Component::ActionRow(ActionRow {
+        components: vec![Component::Button(confirm), Component::Button(cancel)],
+    })];
+
+    let builder = MessageBuilder::new().embed(embed).components(components);
+    command.callback(&ctx, builder, true).await?;
+
+    Ok(())
+}
+
+pub async fn handle_forget_me_confirm(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let user = component.user_id()?;
+
+    let deleted = match forget_user(&ctx, user) {
+        Ok(deleted) => deleted,
+        Err(err) => {
+            let content = "Failed to delete your data, please try again later";
+            let _ = component.error_callback(&ctx, content).await;
+
+            return Err(err.wrap_err(format!("failed to delete data of user {user}")));
+        }
+    };
+
+    let description = format!(
+        "Your data was deleted:\n\
+        - Danser settings: {}\n\
+        - Preferences: {}\n\
+        - Render history entries: {}\n\
+        - Stored videos: {}",
+        if deleted.settings { "deleted" } else { "none" },
+        if deleted.preferences {
+            "deleted"
+        } else {
+            "none"
+        },
+        deleted.history,
+        deleted.videos,
+    );
+
+    let embed = EmbedBuilder::new()
+        .title("Data deleted")
+        .description(description);
+
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+    component.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+pub async fn handle_forget_me_cancel(
+    ctx: Arc<Context>,
+    component: InteractionComponent,
+) -> Result<()> {
+    let embed = EmbedBuilder::new().description("Cancelled, nothing was deleted.");
+    let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+    component.callback(&ctx, builder).await?;
+
+    Ok(())
+}
+
+struct DeletedData {
+    settings: bool,
+    preferences: bool,
+    history: usize,
+    videos: usize,
+}
+
+fn forget_user(ctx: &Context, user: Id<UserMarker>) -> Result<DeletedData> {
+    let settings = DanserSettings::delete_user(user)?;
+    let preferences = ctx.remove_user_settings(user)?;
+    let history = ctx.render_history.remove_user(user)?;
+    let outputs = ctx.render_outputs.remove_user(user);
+
+    for output in &outputs {
+        output.remove_files();
+    }
+
+    ctx.render_cooldowns.remove(user);
+
+    Ok(DeletedData {
+        settings,
+        preferences,
+        history,
+        videos: outputs.len(),
+    })
+}
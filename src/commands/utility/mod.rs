@@ -1,4 +1,7 @@
+mod capabilities;
+mod forget_me;
 mod invite;
 mod ping;
+mod stats;
 
-pub use self::{invite::*, ping::*};
+pub use self::{capabilities::*, forget_me::*, invite::*, ping::*, stats::*};
@@ -0,0 +1,78 @@
+use std::{sync::Arc, time::Instant};
+
+use command_macros::SlashCommand;
+use eyre::Result;
+use twilight_interactions::command::CreateCommand;
+use twilight_model::channel::embed::EmbedField;
+
+use crate::{
+    core::Context,
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        interaction::InteractionCommand,
+        InteractionCommandExt,
+    },
+};
+
+#[derive(CreateCommand, SlashCommand)]
+#[command(
+    name = "stats",
+    help = "Shows how many replays were rendered recently, how long they took \
+    on average, and how many replays are currently waiting in the queue."
+)]
+#[flags(SKIP_DEFER)]
+/// Display how busy the bot has been recently
+pub struct Stats;
+
+async fn slash_stats(ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
+    let throughput = ctx.stats.renders.throughput(Instant::now());
+    let queue_depth = ctx.replay_queue.queue.lock().await.len();
+
+    let average = match throughput.average_duration {
+        Some(duration) => {
+            let secs = duration.as_secs();
+
+            format!("{}:{:02}", secs / 60, secs % 60)
+        }
+        None => "-".to_owned(),
+    };
+
+    let failure_rate = match throughput.failure_rate() {
+        Some(rate) => format!("{rate:.1}%"),
+        None => "-".to_owned(),
+    };
+
+    let fields = vec![
+        EmbedField {
+            inline: true,
+            name: "Renders (last hour)".to_owned(),
+            value: throughput.completed_hour.to_string(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Renders (last day)".to_owned(),
+            value: throughput.completed_day.to_string(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Average render time".to_owned(),
+            value: average,
+        },
+        EmbedField {
+            inline: true,
+            name: "Failure rate".to_owned(),
+            value: failure_rate,
+        },
+        EmbedField {
+            inline: true,
+            name: "Queue length".to_owned(),
+            value: queue_depth.to_string(),
+        },
+    ];
+
+    let embed = EmbedBuilder::new().title("Render stats").fields(fields);
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(&ctx, builder, false).await?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::{ffi::OsStr, process::Stdio, time::Duration};
+
+use eyre::{Context as _, Result};
+use tokio::{process::Command, time::timeout};
+
+use crate::core::{BotConfig, VideoEncoder};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Versions of the external programs that are required for rendering
+#[derive(Clone, Debug)]
+pub struct BinaryVersions {
+    pub danser: String,
+    pub ffmpeg: String,
+    /// The configured encoder which ffmpeg was able to use
+    pub encoder: VideoEncoder,
+}
+
+impl BinaryVersions {
+    /// Make sure danser and ffmpeg can be run, retrieve their versions,
+    /// and check that ffmpeg supports the configured encoder
+    pub async fn detect() -> Result<Self> {
+        let config = BotConfig::get();
+
+        let mut danser_path = config.paths.danser().to_owned();
+        danser_path.push("danser");
+
+        let danser = version(&danser_path, "--version")
+            .await
+            .with_context(|| format!("failed to run danser at {danser_path:?}"))?;
+
+        let ffmpeg = version("ffmpeg", "-version")
+            .await
+            .context("failed to run ffmpeg, make sure it's installed and in the PATH")?;
+
+        let encoder = config.encoder;
+
+        probe_encoder(encoder).await.with_context(|| {
+            format!(
+                "ffmpeg can't encode with {}, \
+                make sure the hardware and drivers support it or set ENCODER to cpu",
+                encoder.ffmpeg_name()
+            )
+        })?;
+
+        Ok(Self {
+            danser,
+            ffmpeg,
+            encoder,
+        })
+    }
+}
+
+/// Encode a single blank frame to check whether the encoder is usable
+async fn probe_encoder(encoder: VideoEncoder) -> Result<()> {
+    let output_fut = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-f", "lavfi"])
+        .args(["-i", "color=c=black:s=256x256:d=0.1", "-frames:v", "1"])
+        .args(["-c:v", encoder.ffmpeg_name(), "-f", "null", "-"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+
+    let output = timeout(TIMEOUT, output_fut)
+        .await
+        .context("timed out")?
+        .context("failed to spawn process")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        bail!("exited with {}: {}", output.status, stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Run the program with the given version argument
+/// and return the first line of its output
+async fn version(program: impl AsRef<OsStr>, arg: &str) -> Result<String> {
+    let output_fut = Command::new(program)
+        .arg(arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+
+    let output = timeout(TIMEOUT, output_fut)
+        .await
+        .context("timed out")?
+        .context("failed to spawn process")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        bail!("exited with {}: {stderr}", output.status);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let line = stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("unknown version");
+
+    Ok(line.to_owned())
+}
@@ -83,6 +83,35 @@ impl Cache {
         Ok(f(&guild))
     }
 
+    /// Collect the result of `f` for each cached guild
+    pub fn guilds<F, T>(&self, f: F) -> Vec<T>
+    where
+        F: Fn(&CachedGuild) -> T,
+    {
+        self.inner
+            .iter()
+            .guilds()
+            .map(|entry| f(entry.value()))
+            .collect()
+    }
+
+    /// Collect the result of `f` for each cached channel of the guild
+    pub fn guild_channels<F, T>(&self, guild: Id<GuildMarker>, f: F) -> Vec<T>
+    where
+        F: Fn(&Channel) -> T,
+    {
+        let channels = match self.inner.guild_channels(guild) {
+            Some(channels) => channels,
+            None => return Vec::new(),
+        };
+
+        channels
+            .iter()
+            .filter_map(|&channel| self.inner.channel(channel))
+            .map(|channel| f(&channel))
+            .collect()
+    }
+
     pub fn member<F, T>(&self, guild: Id<GuildMarker>, user: Id<UserMarker>, f: F) -> Result<T>
     where
         F: FnOnce(&CachedMember) -> T,
@@ -137,3 +166,49 @@ impl Cache {
 }
 
 type ResumeData = HashMap<u64, ResumeSession>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use twilight_gateway::Event;
+    use twilight_model::id::Id;
+
+    use super::Cache;
+
+    fn member_payload(roles: &[&str]) -> serde_json::Value {
+        json!({
+            "guild_id": "1",
+            "user": {
+                "id": "2",
+                "username": "user",
+                "discriminator": "0001",
+                "avatar": null
+            },
+            "roles": roles,
+            "joined_at": "2022-01-01T00:00:00.000000+00:00",
+            "deaf": false,
+            "mute": false,
+            "pending": false
+        })
+    }
+
+    #[tokio::test]
+    async fn member_update_invalidates_roles() {
+        let (cache, _) = Cache::new().await;
+        let guild = Id::new(1);
+        let user = Id::new(2);
+
+        let add = serde_json::from_value(member_payload(&["10", "11"])).unwrap();
+        cache.update(&Event::MemberAdd(Box::new(add)));
+
+        let roles = cache.member(guild, user, |m| m.roles().to_owned()).unwrap();
+        assert_eq!(roles, [Id::new(10), Id::new(11)]);
+
+        // The user lost the role that granted them authority
+        let update = serde_json::from_value(member_payload(&["11"])).unwrap();
+        cache.update(&Event::MemberUpdate(Box::new(update)));
+
+        let roles = cache.member(guild, user, |m| m.roles().to_owned()).unwrap();
+        assert_eq!(roles, [Id::new(11)]);
+    }
+}
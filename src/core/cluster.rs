@@ -19,21 +19,6 @@ pub async fn build_cluster(
         | Intents::DIRECT_MESSAGES
         | Intents::MESSAGE_CONTENT;
 
-    let flags = EventTypeFlags::GATEWAY_INVALIDATE_SESSION
-        | EventTypeFlags::GATEWAY_RECONNECT
-        | EventTypeFlags::GUILD_CREATE
-        | EventTypeFlags::GUILD_DELETE
-        | EventTypeFlags::INTERACTION_CREATE
-        | EventTypeFlags::MESSAGE_CREATE
-        | EventTypeFlags::READY
-        | EventTypeFlags::RESUMED
-        | EventTypeFlags::SHARD_CONNECTED
-        | EventTypeFlags::SHARD_CONNECTING
-        | EventTypeFlags::SHARD_DISCONNECTED
-        | EventTypeFlags::SHARD_IDENTIFYING
-        | EventTypeFlags::SHARD_RECONNECTING
-        | EventTypeFlags::SHARD_RESUMING;
-
     let activity = MinimalActivity {
         kind: ActivityType::Playing,
         name: String::new(),
@@ -44,7 +29,7 @@ pub async fn build_cluster(
         UpdatePresencePayload::new([activity.into()], false, None, Status::Online).unwrap();
 
     let tuple = Cluster::builder(token.to_owned(), intents)
-        .event_types(flags)
+        .event_types(event_flags())
         .http_client(http)
         .resume_sessions(resume_data)
         .presence(presence)
@@ -54,3 +39,34 @@ pub async fn build_cluster(
 
     Ok(tuple)
 }
+
+fn event_flags() -> EventTypeFlags {
+    // Authority checks are based on the cached roles, channels, and members
+    // so their updates must be received to not act on outdated permissions
+    let cache_updates = EventTypeFlags::CHANNEL_CREATE
+        | EventTypeFlags::CHANNEL_DELETE
+        | EventTypeFlags::CHANNEL_UPDATE
+        | EventTypeFlags::GUILD_UPDATE
+        | EventTypeFlags::MEMBER_ADD
+        | EventTypeFlags::MEMBER_REMOVE
+        | EventTypeFlags::MEMBER_UPDATE
+        | EventTypeFlags::ROLE_CREATE
+        | EventTypeFlags::ROLE_DELETE
+        | EventTypeFlags::ROLE_UPDATE;
+
+    EventTypeFlags::GATEWAY_INVALIDATE_SESSION
+        | EventTypeFlags::GATEWAY_RECONNECT
+        | EventTypeFlags::GUILD_CREATE
+        | EventTypeFlags::GUILD_DELETE
+        | EventTypeFlags::INTERACTION_CREATE
+        | EventTypeFlags::MESSAGE_CREATE
+        | EventTypeFlags::READY
+        | EventTypeFlags::RESUMED
+        | EventTypeFlags::SHARD_CONNECTED
+        | EventTypeFlags::SHARD_CONNECTING
+        | EventTypeFlags::SHARD_DISCONNECTED
+        | EventTypeFlags::SHARD_IDENTIFYING
+        | EventTypeFlags::SHARD_RECONNECTING
+        | EventTypeFlags::SHARD_RESUMING
+        | cache_updates
+}
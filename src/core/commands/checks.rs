@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use twilight_model::{
     guild::Permissions,
     id::{
@@ -6,7 +8,7 @@ use twilight_model::{
     },
 };
 
-use crate::core::Context;
+use crate::core::{BotConfig, Context};
 
 /// Is authority -> None
 /// No authority -> Some(message to user)
@@ -27,3 +29,41 @@ pub async fn check_authority(
         Some(content)
     }
 }
+
+/// Whether the user boosts the guild
+pub fn is_supporter(ctx: &Context, user: Id<UserMarker>, guild: Option<Id<GuildMarker>>) -> bool {
+    guild
+        .and_then(|guild| {
+            ctx.cache
+                .member(guild, user, |member| member.premium_since().is_some())
+                .ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Time the user has to wait until they can submit another render.
+///
+/// Owners as well as authorities and supporters of the guild are exempt.
+pub async fn check_render_cooldown(
+    ctx: &Context,
+    author: Id<UserMarker>,
+    channel: Id<ChannelMarker>,
+    guild: Option<Id<GuildMarker>>,
+) -> Option<Duration> {
+    if BotConfig::get().owners.contains(&author) {
+        return None;
+    }
+
+    let remaining = ctx.render_cooldowns.remaining(author)?;
+
+    // Everyone has authority in DMs so it only counts within guilds
+    if guild.is_some() && check_authority(ctx, author, channel, guild).await.is_none() {
+        return None;
+    }
+
+    if is_supporter(ctx, author, guild) {
+        return None;
+    }
+
+    Some(remaining)
+}
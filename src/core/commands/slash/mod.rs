@@ -41,17 +41,24 @@ impl Commands {
         COMMANDS.get_or_init(|| {
             slash_trie! {
                 slash {
+                    Bump => BUMP_SLASH,
+                    Capabilities => CAPABILITIES_SLASH,
+                    ForgetMe => FORGETME_SLASH,
                     Help => HELP_SLASH,
                     Invite => INVITE_SLASH,
                     Owner => OWNER_SLASH,
                     Ping => PING_SLASH,
                     Queue => QUEUE_SLASH,
+                    Recent => RECENT_SLASH,
                     Render => RENDER_SLASH,
+                    Reupload => REUPLOAD_SLASH,
                     Setup => SETUP_SLASH,
                     Skin => SKIN_SLASH,
                     SkinList => SKINLIST_SLASH,
+                    SkinPreview => SKINPREVIEW_SLASH,
                     Settings => SETTINGS_SLASH,
                     Setup => SETUP_SLASH,
+                    Stats => STATS_SLASH,
                 },
                 msg {
                     RENDER_FROM_MSG,
@@ -1,14 +1,44 @@
-use std::{env, path::PathBuf};
+use std::{collections::HashMap, env, fs, path::PathBuf};
 
 use eyre::{Context, ContextCompat, Result};
 use once_cell::sync::OnceCell;
+use serde_json::Value;
 use twilight_model::id::{
     marker::{ChannelMarker, GuildMarker, UserMarker},
     Id,
 };
 
+use crate::core::{digest::DigestSchedule, VideoEncoder};
+
 static CONFIG: OnceCell<BotConfig> = OnceCell::new();
 
+/// Three hours
+const DEFAULT_QUEUE_MAX_AGE: u64 = 3 * 60 * 60;
+
+/// Two gigabytes
+const DEFAULT_MIN_FREE_SPACE: u64 = 2048;
+
+/// Thirty seconds
+const DEFAULT_RENDER_COOLDOWN: u64 = 30;
+
+/// Requests that the custom client may have in flight at once
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// One minute; map files may still be updated while a mapset is pending
+const DEFAULT_MAP_FILE_CACHE_TTL: u64 = 60;
+
+/// Ten minutes
+const DEFAULT_MAPSET_COVER_CACHE_TTL: u64 = 10 * 60;
+
+/// One day
+const DEFAULT_OUTPUT_RETENTION: u64 = 24 * 60 * 60;
+
+/// Replays of a single server that may be queued at once
+const DEFAULT_GUILD_QUEUE_MAX: usize = 5;
+
+/// Thirty days
+const DEFAULT_RENDER_HISTORY_RETENTION: u64 = 30 * 24 * 60 * 60;
+
 #[derive(Debug)]
 pub struct BotConfig {
     pub tokens: Tokens,
@@ -17,6 +47,48 @@ pub struct BotConfig {
     pub owners: Vec<Id<UserMarker>>,
     pub dev_guild: Id<GuildMarker>,
     pub upload_url: String,
+    pub metrics_port: Option<u16>,
+    /// Seconds after which waiting queue entries are dropped
+    pub queue_max_age: u64,
+    /// Amount of entries after which the queue accepts no new ones
+    pub queue_capacity: Option<usize>,
+    /// Amount of a server's replays that may be queued at once;
+    /// servers can lower but not raise it
+    pub guild_queue_max: usize,
+    /// Seconds for which completed renders are kept in the history
+    pub render_history_retention: u64,
+    /// Seconds for which rendered videos can be uploaded again
+    /// and for which their replays are kept
+    pub output_retention: u64,
+    /// Whether replay files are kept for `output_retention` after their render
+    /// or deleted right away to save disk space
+    pub keep_replays: bool,
+    /// Megabytes of free disk space below which renders are rejected
+    pub min_free_space: u64,
+    /// Amount of skins after which no new ones can be added
+    pub max_skins: Option<usize>,
+    /// Requests that the custom client may have in flight at once
+    pub max_concurrent_requests: usize,
+    /// Seconds for which downloaded .osu files are cached in memory
+    pub map_file_cache_ttl: u64,
+    /// Seconds for which downloaded mapset covers are cached in memory
+    pub mapset_cover_cache_ttl: u64,
+    /// Seconds a user has to wait between render submissions
+    pub render_cooldown: u64,
+    /// Channel in which failures of commands and renders are reported
+    pub error_channel: Option<Id<ChannelMarker>>,
+    /// How often a summary of the render activity is posted, never if `None`
+    pub digest: Option<DigestSchedule>,
+    /// Channel in which the digest is posted, defaults to the error channel
+    pub digest_channel: Option<Id<ChannelMarker>>,
+    /// Whether the render in progress is shown in the bot's presence
+    pub render_presence: bool,
+    /// Whether a setup wizard is posted when the bot joins a server
+    pub setup_wizard: bool,
+    /// Encoder that renders are recorded with
+    pub encoder: VideoEncoder,
+    /// Whether each render writes a log file that's kept for `output_retention`
+    pub render_logs: bool,
 }
 
 #[derive(Debug)]
@@ -33,10 +105,22 @@ impl Paths {
         path
     }
 
+    pub fn folders(&self) -> &PathBuf {
+        &self.folders
+    }
+
     pub fn danser(&self) -> &PathBuf {
         &self.danser
     }
 
+    /// Replay that is used to preview skins
+    pub fn demo_replay(&self) -> PathBuf {
+        let mut path = self.folders.clone();
+        path.push("demo.osr");
+
+        path
+    }
+
     pub fn downloads(&self) -> PathBuf {
         let mut path = self.folders.clone();
         path.push("Downloads");
@@ -44,6 +128,21 @@ impl Paths {
         path
     }
 
+    pub fn render_history(&self) -> PathBuf {
+        let mut path = self.folders.clone();
+        path.push("render_history.json");
+
+        path
+    }
+
+    /// Log files of single renders, see `BotConfig::render_logs`
+    pub fn render_logs(&self) -> PathBuf {
+        let mut path = self.folders.clone();
+        path.push("RenderLogs");
+
+        path
+    }
+
     pub fn replays(&self) -> PathBuf {
         let mut path = self.folders.clone();
         path.push("Replays");
@@ -51,6 +150,13 @@ impl Paths {
         path
     }
 
+    pub fn skin_order(&self) -> PathBuf {
+        let mut path = self.folders.clone();
+        path.push("skin_order.json");
+
+        path
+    }
+
     /// Avoid this, use Context::skin_list instead if possible
     pub fn skins(&self) -> PathBuf {
         let mut path = self.folders.clone();
@@ -91,26 +197,68 @@ impl BotConfig {
     }
 
     pub fn init() -> Result<()> {
+        let vars = ConfigVars::load()?;
+
         let config = BotConfig {
             tokens: Tokens {
-                discord: env_var("DISCORD_TOKEN")?,
-                osu_client_id: env_var("OSU_CLIENT_ID")?,
-                osu_client_secret: env_var("OSU_CLIENT_SECRET")?,
-                osu_api_key: env_var("OSU_API_KEY")?,
-                upload_secret: env_var("UPLOAD_SECRET")?,
+                discord: vars.required("DISCORD_TOKEN")?,
+                osu_client_id: vars.required("OSU_CLIENT_ID")?,
+                osu_client_secret: vars.required("OSU_CLIENT_SECRET")?,
+                osu_api_key: vars.required("OSU_API_KEY")?,
+                upload_secret: vars.required("UPLOAD_SECRET")?,
             },
             paths: Paths {
-                danser: env_var("DANSER_PATH")?,
-                folders: env_var("FOLDERS_PATH")?,
+                danser: vars.required("DANSER_PATH")?,
+                folders: vars.required("FOLDERS_PATH")?,
             },
             emojis: Emojis {
-                man_running: env_var("MAN_RUNNING")?,
-                white_check_mark: env_var("WHITE_CHECK_MARK")?,
-                hourglass: env_var("HOURGLASS")?,
+                man_running: vars.required("MAN_RUNNING")?,
+                white_check_mark: vars.required("WHITE_CHECK_MARK")?,
+                hourglass: vars.required("HOURGLASS")?,
             },
-            owners: env_var("OWNERS_USER_ID")?,
-            dev_guild: env_var("DEV_GUILD_ID")?,
-            upload_url: env_var("UPLOAD_URL")?,
+            owners: vars.required("OWNERS_USER_ID")?,
+            dev_guild: vars.required("DEV_GUILD_ID")?,
+            upload_url: vars.required("UPLOAD_URL")?,
+            metrics_port: vars.optional("METRICS_PORT")?,
+            queue_max_age: vars
+                .optional("QUEUE_MAX_AGE")?
+                .unwrap_or(DEFAULT_QUEUE_MAX_AGE),
+            queue_capacity: vars.optional("QUEUE_CAPACITY")?,
+            guild_queue_max: vars
+                .optional("GUILD_QUEUE_MAX")?
+                .unwrap_or(DEFAULT_GUILD_QUEUE_MAX)
+                .max(1),
+            render_history_retention: vars
+                .optional("RENDER_HISTORY_RETENTION")?
+                .unwrap_or(DEFAULT_RENDER_HISTORY_RETENTION),
+            output_retention: vars
+                .optional("OUTPUT_RETENTION")?
+                .unwrap_or(DEFAULT_OUTPUT_RETENTION),
+            keep_replays: vars.optional("KEEP_REPLAYS")?.unwrap_or(true),
+            min_free_space: vars
+                .optional("MIN_FREE_SPACE")?
+                .unwrap_or(DEFAULT_MIN_FREE_SPACE),
+            max_skins: vars.optional("MAX_SKINS")?,
+            max_concurrent_requests: vars
+                .optional("MAX_CONCURRENT_REQUESTS")?
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS)
+                .max(1),
+            map_file_cache_ttl: vars
+                .optional("MAP_FILE_CACHE_TTL")?
+                .unwrap_or(DEFAULT_MAP_FILE_CACHE_TTL),
+            mapset_cover_cache_ttl: vars
+                .optional("MAPSET_COVER_CACHE_TTL")?
+                .unwrap_or(DEFAULT_MAPSET_COVER_CACHE_TTL),
+            render_cooldown: vars
+                .optional("RENDER_COOLDOWN")?
+                .unwrap_or(DEFAULT_RENDER_COOLDOWN),
+            error_channel: vars.optional("ERROR_CHANNEL_ID")?,
+            digest: vars.optional("DIGEST")?,
+            digest_channel: vars.optional("DIGEST_CHANNEL_ID")?,
+            render_presence: vars.optional("RENDER_PRESENCE")?.unwrap_or(false),
+            setup_wizard: vars.optional("SETUP_WIZARD")?.unwrap_or(false),
+            encoder: vars.optional("ENCODER")?.unwrap_or_default(),
+            render_logs: vars.optional("RENDER_LOGS")?.unwrap_or(false),
         };
 
         if CONFIG.set(config).is_err() {
@@ -142,10 +290,14 @@ macro_rules! env_kind {
 }
 
 env_kind! {
+    bool: s => { s.parse().ok() },
     u16: s => { s.parse().ok() },
     u64: s => { s.parse().ok() },
+    usize: s => { s.parse().ok() },
     PathBuf: s => { s.parse().ok() },
     String: s => { Some(s.to_owned()) },
+    VideoEncoder: s => { VideoEncoder::parse(s) },
+    DigestSchedule: s => { DigestSchedule::parse(s) },
     Id<UserMarker>: s => { s.parse().ok().map(Id::new) },
     Id<GuildMarker>: s => { s.parse().ok().map(Id::new) },
     Id<ChannelMarker>: s => { s.parse().ok().map(Id::new) },
@@ -158,13 +310,131 @@ env_kind! {
     },
 }
 
-fn env_var<T: EnvKind>(name: &'static str) -> Result<T> {
-    let value = env::var(name).with_context(|| format!("missing env variable `{name}`"))?;
+/// Looks up config values in the env variables and falls back to the config file
+#[derive(Default)]
+struct ConfigVars {
+    /// Values of the config file keyed by the env variable they stand in for
+    file: HashMap<String, String>,
+}
+
+impl ConfigVars {
+    /// Reads the config file at `CONFIG_FILE` if that variable is set
+    fn load() -> Result<Self> {
+        let path = match env::var("CONFIG_FILE") {
+            Ok(path) => path,
+            Err(_) => return Ok(Self::default()),
+        };
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config file at `{path}`"))?;
+
+        Self::parse(&content).with_context(|| format!("failed to parse config file at `{path}`"))
+    }
+
+    /// The file is a JSON object using the names of the env variables as keys.
+    ///
+    /// Values are converted to the same strings that the env variables would
+    /// hold so that both are validated the same way, e.g. lists are joined by
+    /// commas.
+    fn parse(content: &str) -> Result<Self> {
+        fn stringify(value: Value) -> Result<Option<String>> {
+            let s = match value {
+                Value::Null => return Ok(None),
+                Value::Bool(b) => b.to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s,
+                Value::Array(values) => {
+                    let mut items = Vec::with_capacity(values.len());
+
+                    for value in values {
+                        match stringify(value)? {
+                            Some(item) => items.push(item),
+                            None => bail!("lists must not contain null"),
+                        }
+                    }
+
+                    items.join(",")
+                }
+                Value::Object(_) => bail!("nested objects are not supported"),
+            };
+
+            Ok(Some(s))
+        }
+
+        let values: HashMap<String, Value> =
+            serde_json::from_str(content).context("expected a JSON object")?;
+
+        let mut file = HashMap::with_capacity(values.len());
 
-    T::from_str(&value).with_context(|| {
+        for (name, value) in values {
+            if let Some(value) = stringify(value).with_context(|| format!("invalid `{name}`"))? {
+                file.insert(name, value);
+            }
+        }
+
+        Ok(Self { file })
+    }
+
+    /// Env variables take precedence over the config file
+    fn get(&self, name: &str) -> Option<String> {
+        env::var(name).ok().or_else(|| self.file.get(name).cloned())
+    }
+
+    fn required<T: EnvKind>(&self, name: &'static str) -> Result<T> {
+        let value = self
+            .get(name)
+            .with_context(|| format!("missing env variable `{name}`"))?;
+
+        parse_env_var(name, &value)
+    }
+
+    /// Same as [`ConfigVars::required`] except it does not fail if the variable is not set
+    fn optional<T: EnvKind>(&self, name: &'static str) -> Result<Option<T>> {
+        match self.get(name) {
+            Some(value) => parse_env_var(name, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+fn parse_env_var<T: EnvKind>(name: &'static str, value: &str) -> Result<T> {
+    T::from_str(value).with_context(|| {
         format!(
             "failed to parse env variable `{name}={value}`; expected {expected}",
             expected = T::EXPECTED
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_values_are_stringified() {
+        let content = r#"{
+            "DISCORD_TOKEN": "token",
+            "QUEUE_CAPACITY": 50,
+            "RENDER_PRESENCE": true,
+            "OWNERS_USER_ID": [300628393676767232, "219905108316520448"],
+            "ERROR_CHANNEL_ID": null
+        }"#;
+
+        let vars = ConfigVars::parse(content).unwrap();
+
+        assert_eq!(vars.file["DISCORD_TOKEN"], "token");
+        assert_eq!(vars.file["QUEUE_CAPACITY"], "50");
+        assert_eq!(vars.file["RENDER_PRESENCE"], "true");
+        assert!(!vars.file.contains_key("ERROR_CHANNEL_ID"));
+
+        let owners: Vec<Id<UserMarker>> =
+            parse_env_var("OWNERS_USER_ID", &vars.file["OWNERS_USER_ID"]).unwrap();
+        assert_eq!(owners.len(), 2);
+    }
+
+    #[test]
+    fn nested_objects_are_rejected() {
+        assert!(ConfigVars::parse(r#"{ "PATHS": { "DANSER": "./danser" } }"#).is_err());
+        assert!(ConfigVars::parse("[1, 2]").is_err());
+    }
+}
@@ -1,10 +1,20 @@
-use std::fs::OpenOptions;
+use std::{
+    ffi::OsStr,
+    fs::{self, OpenOptions},
+};
 
 use eyre::{Context as _, Result};
-use twilight_model::id::{marker::GuildMarker, Id};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
 
 use crate::{
-    core::{settings::Server, BotConfig},
+    core::{
+        locale::Locale,
+        settings::{RootSettings, Server, UserSettings},
+        BotConfig,
+    },
     Context,
 };
 
@@ -16,6 +26,64 @@ impl Context {
         self.root_settings.servers.pin().get(&guild_id).map(f)
     }
 
+    /// Amount of guilds whose render defaults use the given skin
+    pub fn guilds_with_default_skin(&self, skin: &OsStr) -> usize {
+        self.root_settings
+            .servers
+            .pin()
+            .values()
+            .filter(|server| server.render_defaults.skin.as_deref().map(OsStr::new) == Some(skin))
+            .count()
+    }
+
+    /// Returns the guild's locale or the default one for DMs or unconfigured guilds
+    pub fn guild_locale(&self, guild_id: Option<Id<GuildMarker>>) -> Locale {
+        guild_id
+            .and_then(|guild_id| self.guild_settings(guild_id, |server| server.locale))
+            .unwrap_or_default()
+    }
+
+    /// Whether embeds for the user should be kept short.
+    ///
+    /// The user's own preference takes precedence over the guild's,
+    /// full embeds if neither is configured.
+    pub fn compact_embeds(&self, user: Id<UserMarker>, guild_id: Option<Id<GuildMarker>>) -> bool {
+        self.user_settings(user, |settings| settings.compact_embeds)
+            .flatten()
+            .or_else(|| {
+                guild_id.and_then(|guild_id| {
+                    self.guild_settings(guild_id, |server| server.compact_embeds)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Whether the guild disabled the command, never for DMs
+    pub fn command_disabled(&self, guild_id: Option<Id<GuildMarker>>, name: &str) -> bool {
+        guild_id
+            .and_then(|guild_id| {
+                self.guild_settings(guild_id, |server| server.disabled_commands.contains(name))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Whether minor replay issues should be shown, hidden for DMs or unconfigured guilds
+    pub fn parse_warnings(&self, guild_id: Option<Id<GuildMarker>>) -> bool {
+        guild_id
+            .and_then(|guild_id| self.guild_settings(guild_id, |server| server.parse_warnings))
+            .unwrap_or(false)
+    }
+
+    /// Amount of the guild's replays that may be queued at once
+    pub fn guild_queue_limit(&self, guild_id: Option<Id<GuildMarker>>) -> usize {
+        let max = BotConfig::get().guild_queue_max;
+
+        guild_id
+            .and_then(|guild_id| self.guild_settings(guild_id, |server| server.queue_limit))
+            .flatten()
+            .map_or(max, |limit| limit.min(max))
+    }
+
     pub fn upsert_guild_settings<F, O>(&self, guild_id: Id<GuildMarker>, f: F) -> Result<O>
     where
         F: FnOnce(&mut Server) -> O,
@@ -43,6 +111,110 @@ impl Context {
         Ok(output)
     }
 
+    pub fn user_settings<F, O>(&self, user: Id<UserMarker>, f: F) -> Option<O>
+    where
+        F: FnOnce(&UserSettings) -> O,
+    {
+        self.root_settings.users.pin().get(&user).map(f)
+    }
+
+    pub fn upsert_user_settings<F, O>(&self, user: Id<UserMarker>, f: F) -> Result<O>
+    where
+        F: FnOnce(&mut UserSettings) -> O,
+    {
+        let output = {
+            let guard = self.root_settings.users.guard();
+
+            let mut settings = self
+                .root_settings
+                .users
+                .get(&user, &guard)
+                .cloned()
+                .unwrap_or_default();
+
+            let output = f(&mut settings);
+
+            self.root_settings.users.insert(user, settings, &guard);
+
+            output
+        };
+
+        self.store_guild_settings()
+            .context("failed to upsert user settings")?;
+
+        Ok(output)
+    }
+
+    /// Remove the user's settings, returns whether there were any
+    pub fn remove_user_settings(&self, user: Id<UserMarker>) -> Result<bool> {
+        let removed = self.root_settings.users.pin().remove(&user).is_some();
+
+        if removed {
+            self.store_guild_settings()
+                .context("failed to store settings after removing user")?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove the settings of guilds that the bot is no longer in
+    /// and return the ids of those guilds.
+    ///
+    /// Nothing is removed while guilds are unavailable since their
+    /// settings would be lost even though the bot is still in them.
+    pub fn evict_stale_guild_settings(&self) -> Result<Vec<Id<GuildMarker>>> {
+        let stats = self.cache.stats();
+
+        if stats.guilds() == 0 || stats.unavailable_guilds() > 0 {
+            return Ok(Vec::new());
+        }
+
+        let evicted: Vec<_> = {
+            let servers = self.root_settings.servers.pin();
+
+            let stale: Vec<_> = servers
+                .keys()
+                .copied()
+                .filter(|&guild| self.cache.guild(guild, |_| ()).is_err())
+                .collect();
+
+            for guild in stale.iter() {
+                servers.remove(guild);
+            }
+
+            stale
+        };
+
+        if !evicted.is_empty() {
+            self.store_guild_settings()
+                .context("failed to store server settings after eviction")?;
+        }
+
+        Ok(evicted)
+    }
+
+    /// Replace the guild's settings with those of the settings file
+    /// to pick up changes that were made while the bot was running.
+    ///
+    /// Returns the reloaded settings or `None` if the file has none for the guild.
+    pub fn reload_guild_settings(&self, guild_id: Id<GuildMarker>) -> Result<Option<Server>> {
+        let path = BotConfig::get().paths.server_settings();
+        let bytes = fs::read(path).context("failed to read server settings file")?;
+
+        let stored: RootSettings =
+            serde_json::from_slice(&bytes).context("failed to deserialize server settings file")?;
+
+        let server = stored.servers.pin().get(&guild_id).cloned();
+        let servers = self.root_settings.servers.pin();
+
+        match server {
+            Some(ref server) => servers.insert(guild_id, server.clone()),
+            None => servers.remove(&guild_id),
+        };
+
+        Ok(server)
+    }
+
     fn store_guild_settings(&self) -> Result<()> {
         let path = BotConfig::get().paths.server_settings();
 
@@ -0,0 +1,32 @@
+use std::sync::atomic::Ordering;
+
+use crate::core::{BotConfig, VideoEncoder};
+
+use super::Context;
+
+impl Context {
+    /// The configured encoder unless it's a hardware encoder that failed before
+    pub fn video_encoder(&self) -> VideoEncoder {
+        let encoder = BotConfig::get().encoder;
+
+        if encoder.is_hardware() && self.hardware_encoding_failed() {
+            VideoEncoder::Cpu
+        } else {
+            encoder
+        }
+    }
+
+    pub fn hardware_encoding_failed(&self) -> bool {
+        self.hardware_encoding_failed.load(Ordering::Acquire)
+    }
+
+    /// Render with the CPU until the fallback is reset or the bot restarts
+    pub fn disable_hardware_encoding(&self) {
+        self.hardware_encoding_failed.store(true, Ordering::Release);
+    }
+
+    /// Try the configured encoder again; returns whether it was disabled
+    pub fn reset_hardware_encoding(&self) -> bool {
+        self.hardware_encoding_failed.swap(false, Ordering::AcqRel)
+    }
+}
@@ -1,9 +1,13 @@
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{atomic::AtomicBool, Arc, RwLock};
 
 use eyre::{Result, WrapErr};
 use flexmap::tokio::TokioMutexMap;
 use rosu_v2::Osu;
-use tokio::{fs, io::AsyncWriteExt};
+use tokio::{
+    fs,
+    io::AsyncWriteExt,
+    sync::{Mutex, MutexGuard},
+};
 use twilight_gateway::{cluster::Events, Cluster};
 use twilight_http::{client::InteractionClient, Client};
 use twilight_model::{
@@ -16,15 +20,25 @@ use twilight_model::{
 use twilight_standby::Standby;
 
 use crate::{
-    core::BotConfig, custom_client::CustomClient, pagination::Pagination,
-    util::hasher::IntBuildHasher,
+    commands::danser::PendingTrim, core::BotConfig, custom_client::CustomClient,
+    pagination::Pagination, util::hasher::IntBuildHasher,
+};
+
+use super::{
+    cluster::build_cluster, replay_queue::DemoReplay, settings::RootSettings, stats::BotStats,
+    BinaryVersions, Cache, ErrorReports, HandledInteractions, RenderCooldowns, RenderHistory,
+    RenderOutputs, ReplayQueue,
 };
 
-use super::{cluster::build_cluster, settings::RootSettings, stats::BotStats, Cache, ReplayQueue};
+pub use self::render_settings::PreparedSettings;
 
 use self::skin_list::SkinList;
 
 mod configs;
+mod encoding;
+mod pagination;
+mod presence;
+mod render_settings;
 mod skin_list;
 
 pub struct Context {
@@ -32,9 +46,24 @@ pub struct Context {
     pub cluster: Cluster,
     pub http: Arc<Client>,
     pub paginations: Arc<TokioMutexMap<Id<MessageMarker>, Pagination, IntBuildHasher>>,
+    /// Renders whose start and end are being adjusted before they're queued
+    pub pending_trims: TokioMutexMap<Id<MessageMarker>, PendingTrim, IntBuildHasher>,
     pub standby: Standby,
     pub stats: Arc<BotStats>,
     pub replay_queue: ReplayQueue,
+    pub render_history: RenderHistory,
+    pub render_outputs: RenderOutputs,
+    pub render_cooldowns: RenderCooldowns,
+    pub handled_interactions: HandledInteractions,
+    /// Versions of danser and ffmpeg as detected on startup or by `/owner binaries`
+    pub binary_versions: RwLock<BinaryVersions>,
+    pub error_reports: ErrorReports,
+    /// Replay to preview skins with, `None` if skin previews are disabled
+    pub demo_replay: Option<DemoReplay>,
+    /// Render that's shown in the bot's presence, if any
+    render_activity: RwLock<Option<String>>,
+    /// Whether the configured hardware encoder failed so that renders fall back to the CPU
+    hardware_encoding_failed: AtomicBool,
     root_settings: RootSettings,
     skin_list: Arc<Mutex<SkinList>>,
     application_id: Id<ApplicationMarker>,
@@ -54,9 +83,12 @@ impl Context {
         &self.clients.custom
     }
 
-    /// Returns a mutex guard, don't hold it for too long!
-    pub fn skin_list(&self) -> MutexGuard<'_, SkinList> {
-        self.skin_list.lock().unwrap()
+    /// Returns a guard of the skin list.
+    ///
+    /// Hold it while modifying the skins folder so that concurrent
+    /// commands don't resolve skins from an outdated list.
+    pub async fn skin_list(&self) -> MutexGuard<'_, SkinList> {
+        self.skin_list.lock().await
     }
 
     pub async fn new() -> Result<(Self, Events)> {
@@ -64,6 +96,14 @@ impl Context {
 
         create_missing_folders_and_files(config).await?;
 
+        let binary_versions = BinaryVersions::detect()
+            .await
+            .context("danser or ffmpeg are not available")?;
+
+        info!("danser version: {}", binary_versions.danser);
+        info!("ffmpeg version: {}", binary_versions.ffmpeg);
+        info!("video encoder: {}", binary_versions.encoder.ffmpeg_name());
+
         let discord_token = &config.tokens.discord;
 
         let bytes = fs::read(config.paths.server_settings())
@@ -73,6 +113,9 @@ impl Context {
         let root_settings =
             serde_json::from_slice(&bytes).context("failed to deserialize server settings file")?;
 
+        let render_history = RenderHistory::load()?;
+        let demo_replay = DemoReplay::load().await;
+
         let mentions = AllowedMentionsBuilder::new()
             .replied_user()
             .roles()
@@ -101,10 +144,10 @@ impl Context {
         let osu_client_secret = &config.tokens.osu_client_secret;
         let osu = Osu::new(osu_client_id, osu_client_secret).await?;
 
-        let custom = CustomClient::new();
+        let stats = Arc::new(BotStats::new());
+        let custom = CustomClient::new(stats.site_requests.clone());
 
         let (cache, resume_data) = Cache::new().await;
-        let stats = Arc::new(BotStats::new());
 
         let clients = Clients::new(osu, custom);
         let (cluster, events) =
@@ -120,9 +163,19 @@ impl Context {
             application_id,
             root_settings,
             paginations: Arc::new(paginations),
+            pending_trims: TokioMutexMap::with_shard_amount_and_hasher(16, IntBuildHasher),
             standby: Standby::new(),
             stats,
             replay_queue: ReplayQueue::new(),
+            render_history,
+            render_outputs: RenderOutputs::default(),
+            render_cooldowns: RenderCooldowns::default(),
+            handled_interactions: HandledInteractions::default(),
+            binary_versions: RwLock::new(binary_versions),
+            error_reports: ErrorReports::default(),
+            demo_replay,
+            render_activity: RwLock::new(None),
+            hardware_encoding_failed: AtomicBool::new(false),
             skin_list: Arc::new(Mutex::default()),
         };
 
@@ -158,6 +211,12 @@ async fn create_missing_folders_and_files(config: &BotConfig) -> Result<()> {
         .await
         .context("failed to create Songs folder")?;
 
+    if config.render_logs {
+        fs::create_dir_all(config.paths.render_logs())
+            .await
+            .context("failed to create RenderLogs folder")?;
+    }
+
     let danser_entry = config
         .paths
         .danser()
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use eyre::Result;
+
+use crate::{
+    pagination::{Pages, PaginationBuilder, PaginationKind, PaginationOptions},
+    util::interaction::InteractionCommand,
+};
+
+use super::Context;
+
+impl Context {
+    /// Respond to the command with the first page and keep
+    /// track of the pagination so its components are handled.
+    pub async fn paginate(
+        self: Arc<Self>,
+        command: InteractionCommand,
+        kind: PaginationKind,
+        pages: Pages,
+        options: PaginationOptions,
+    ) -> Result<()> {
+        let PaginationOptions {
+            attachment,
+            content,
+            deferred,
+            defer_components,
+            disable_on_timeout,
+            timeout,
+            grace_period,
+        } = options;
+
+        let mut builder = PaginationBuilder::new(kind, pages);
+
+        if let Some((name, bytes)) = attachment {
+            builder = builder.attachment(name, bytes);
+        }
+
+        if let Some(content) = content {
+            builder = builder.content(content);
+        }
+
+        if deferred {
+            builder = builder.start_by_update();
+        }
+
+        if defer_components {
+            builder = builder.defer_components();
+        }
+
+        if disable_on_timeout {
+            builder = builder.disable_on_timeout();
+        }
+
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(grace_period) = grace_period {
+            builder = builder.grace_period(grace_period);
+        }
+
+        builder.start(self, command).await
+    }
+}
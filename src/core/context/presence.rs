@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use twilight_model::gateway::{
+    payload::outgoing::UpdatePresence,
+    presence::{ActivityType, MinimalActivity, Status},
+};
+
+use crate::core::BotConfig;
+
+use super::Context;
+
+/// Activity names may not be longer than this
+const MAX_ACTIVITY_LEN: usize = 128;
+
+impl Context {
+    /// The render that's in progress if it's announced, the server count otherwise
+    pub fn activity(&self) -> MinimalActivity {
+        if let Some(ref name) = *self.render_activity.read().unwrap() {
+            return MinimalActivity {
+                kind: ActivityType::Playing,
+                name: name.to_owned(),
+                url: None,
+            };
+        }
+
+        let stats = self.cache.stats();
+        let count = stats.guilds() + stats.unavailable_guilds();
+
+        MinimalActivity {
+            kind: ActivityType::Watching,
+            name: format!("in {count} servers"),
+            url: None,
+        }
+    }
+
+    /// Show the render in the bot's presence if enabled via config
+    pub async fn announce_render(&self, player: Option<&str>, map_path: &Path) {
+        if BotConfig::get().render_presence {
+            *self.render_activity.write().unwrap() = Some(render_activity(player, map_path));
+            self.update_presence().await;
+        }
+    }
+
+    /// Revert the presence to the server count if renders are announced
+    pub async fn announce_idle(&self) {
+        if BotConfig::get().render_presence {
+            *self.render_activity.write().unwrap() = None;
+            self.update_presence().await;
+        }
+    }
+
+    async fn update_presence(&self) {
+        let activity = self.activity().into();
+
+        let req = match UpdatePresence::new(vec![activity], false, None, Status::Online) {
+            Ok(req) => req,
+            Err(err) => {
+                warn!("failed to create presence update: {err}");
+
+                return;
+            }
+        };
+
+        for shard in self.cluster.shards() {
+            if let Err(err) = shard.command(&req).await {
+                warn!("failed to update presence: {err}");
+            }
+        }
+    }
+}
+
+/// Activity for a render based on the player and the map's .osu filename
+/// which is of the form `artist - title (mapper) [version].osu`
+fn render_activity(player: Option<&str>, map_path: &Path) -> String {
+    let map = map_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let mut activity = match player {
+        Some(player) => format!("Rendering {player} - {map}"),
+        None => format!("Rendering {map}"),
+    };
+
+    if activity.chars().count() > MAX_ACTIVITY_LEN {
+        let end = activity
+            .char_indices()
+            .nth(MAX_ACTIVITY_LEN - 3)
+            .map_or(activity.len(), |(idx, _)| idx);
+
+        activity.truncate(end);
+        activity.push_str("...");
+    }
+
+    activity
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn activity_contains_player_and_map() {
+        let path = PathBuf::from("Songs/123/Artist - Title (Mapper) [Insane].osu");
+        let activity = render_activity(Some("mezo"), &path);
+
+        assert_eq!(
+            activity,
+            "Rendering mezo - Artist - Title (Mapper) [Insane]"
+        );
+    }
+
+    #[test]
+    fn long_activity_is_truncated() {
+        let path = PathBuf::from(format!("{}.osu", "ä".repeat(200)));
+        let activity = render_activity(None, &path);
+
+        assert_eq!(activity.chars().count(), MAX_ACTIVITY_LEN);
+        assert!(activity.ends_with("..."));
+    }
+}
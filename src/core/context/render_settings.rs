@@ -0,0 +1,297 @@
+use std::fs;
+
+use eyre::{Context as _, Result};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    core::{
+        replay_queue::{
+            skin_ini::{SkinIniOverrides, TempSkin},
+            watermark,
+        },
+        settings::DanserSettings,
+        BotConfig, RenderOptions,
+    },
+    Context,
+};
+
+/// Built into danser so it's always available
+const FALLBACK_SKIN: &str = "default";
+
+/// Danser settings of a render as they're handed to danser
+pub struct PreparedSettings {
+    pub settings: DanserSettings,
+    /// Name of the configured skin and why the fallback skin is used instead
+    pub broken_skin: Option<(String, String)>,
+    /// Copy of the skin with the render's skin.ini overrides, removed on drop
+    pub temp_skin: Option<TempSkin>,
+}
+
+impl Context {
+    /// The danser settings with which a render of the user is done.
+    ///
+    /// Precedence from highest to lowest:
+    ///   1. the `overrides` of the render itself
+    ///   2. the user's own danser settings
+    ///   3. the server's render defaults
+    ///   4. the global default danser settings
+    ///
+    /// Since a user's settings are complete, the server's defaults
+    /// only come into play for users without their own settings.
+    pub fn resolve_render_settings(
+        &self,
+        user: Id<UserMarker>,
+        guild: Option<Id<GuildMarker>>,
+        overrides: &RenderOptions,
+    ) -> Result<DanserSettings> {
+        let base = match DanserSettings::load_user(user)? {
+            Some(settings) => SettingsBase::User(settings),
+            None => SettingsBase::Default(DanserSettings::load_default()?),
+        };
+
+        let guild_defaults =
+            guild.and_then(|guild| self.guild_settings(guild, |s| s.render_defaults.clone()));
+
+        Ok(resolve(base, guild_defaults.as_ref(), overrides))
+    }
+
+    /// [`Context::resolve_render_settings`] with everything that's not up to the user:
+    /// broken skins fall back to the default one, skin.ini overrides are applied
+    /// to a copy of the skin, and the video encoder and server's watermark are set.
+    ///
+    /// The copy of the skin is named after the job `id`.
+    pub fn prepare_render_settings(
+        &self,
+        user: Id<UserMarker>,
+        guild: Option<Id<GuildMarker>>,
+        options: &RenderOptions,
+        skin_ini: Option<&SkinIniOverrides>,
+        id: u32,
+    ) -> Result<PreparedSettings> {
+        let mut settings = self.resolve_render_settings(user, guild, options)?;
+        let mut broken_skin = None;
+
+        // Better render with the default skin than not at all
+        if let Some(reason) = skin_problem(&settings.skin_name()) {
+            broken_skin = Some((settings.skin_name().into_owned(), reason));
+            settings.set_skin(FALLBACK_SKIN);
+        }
+
+        let temp_skin = match skin_ini {
+            Some(overrides) => {
+                let temp_skin = TempSkin::create(&settings.skin_name(), overrides, id)
+                    .context("failed to create skin with skin.ini overrides")?;
+
+                settings.set_skin(&temp_skin.name());
+
+                Some(temp_skin)
+            }
+            None => None,
+        };
+
+        self.video_encoder().apply(&mut settings.recording);
+
+        let watermark = guild
+            .and_then(|guild| self.guild_settings(guild, |s| s.watermark.clone()))
+            .flatten();
+
+        if let Some(ref text) = watermark {
+            watermark::apply(text, &mut settings.recording);
+        }
+
+        Ok(PreparedSettings {
+            settings,
+            broken_skin,
+            temp_skin,
+        })
+    }
+}
+
+/// Reason why the skin can't be used or `None` if it looks fine
+fn skin_problem(skin: &str) -> Option<String> {
+    if skin == FALLBACK_SKIN {
+        return None;
+    }
+
+    let mut path = BotConfig::get().paths.skins();
+    path.push(skin);
+
+    if !path.is_dir() {
+        return Some("its folder is missing".to_owned());
+    }
+
+    match fs::read_dir(&path) {
+        Ok(mut entries) if entries.next().is_none() => Some("its folder is empty".to_owned()),
+        Ok(_) => None,
+        Err(err) => Some(format!("its folder can't be read: {err}")),
+    }
+}
+
+enum SettingsBase {
+    User(DanserSettings),
+    Default(DanserSettings),
+}
+
+fn resolve(
+    base: SettingsBase,
+    guild_defaults: Option<&RenderOptions>,
+    overrides: &RenderOptions,
+) -> DanserSettings {
+    let mut settings = match base {
+        SettingsBase::User(settings) => settings,
+        SettingsBase::Default(mut settings) => {
+            if let Some(defaults) = guild_defaults {
+                defaults.apply(&mut settings);
+            }
+
+            settings
+        }
+    };
+
+    overrides.apply(&mut settings);
+
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(volume: f64, skin: &str) -> DanserSettings {
+        let mut settings = DanserSettings::default();
+        settings.audio.general_volume = volume;
+        settings.audio.music_volume = volume;
+        settings.playfield.background.dim.normal = volume;
+        settings.skin.current_skin = format!("skins/{skin}");
+
+        settings
+    }
+
+    fn guild_defaults() -> RenderOptions {
+        RenderOptions {
+            background_dim: Some(80),
+            skin: Some("guild".to_owned()),
+            master_volume: Some(20),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn global_default_without_anything_else() {
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, None, &RenderOptions::default());
+
+        assert_eq!(resolved, settings(0.5, "default"));
+    }
+
+    #[test]
+    fn guild_over_global_default() {
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults()), &RenderOptions::default());
+
+        assert_eq!(resolved.playfield.background.dim.normal, 0.8);
+        assert_eq!(resolved.audio.general_volume, 0.2);
+        assert_eq!(resolved.skin_name(), "guild");
+
+        // Values the guild doesn't set remain untouched
+        assert_eq!(resolved.audio.music_volume, 0.5);
+    }
+
+    #[test]
+    fn user_over_guild() {
+        let base = SettingsBase::User(settings(0.3, "user"));
+        let resolved = resolve(base, Some(&guild_defaults()), &RenderOptions::default());
+
+        assert_eq!(resolved, settings(0.3, "user"));
+    }
+
+    #[test]
+    fn overrides_over_user() {
+        let overrides = RenderOptions {
+            skin: Some("override".to_owned()),
+            music_volume: Some(70),
+            ..Default::default()
+        };
+
+        let base = SettingsBase::User(settings(0.3, "user"));
+        let resolved = resolve(base, Some(&guild_defaults()), &overrides);
+
+        assert_eq!(resolved.skin_name(), "override");
+        assert_eq!(resolved.skin.current_skin, "skins/override");
+        assert_eq!(resolved.audio.music_volume, 0.7);
+        assert_eq!(resolved.audio.general_volume, 0.3);
+    }
+
+    #[test]
+    fn overrides_over_guild() {
+        let overrides = RenderOptions {
+            background_dim: Some(10),
+            master_volume: Some(90),
+            ..Default::default()
+        };
+
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults()), &overrides);
+
+        assert_eq!(resolved.playfield.background.dim.normal, 0.1);
+        assert_eq!(resolved.audio.general_volume, 0.9);
+        assert_eq!(resolved.skin_name(), "guild");
+    }
+
+    #[test]
+    fn override_volume_over_guild_no_audio() {
+        let guild_defaults = RenderOptions {
+            no_audio: true,
+            ..Default::default()
+        };
+
+        let overrides = RenderOptions {
+            master_volume: Some(40),
+            ..Default::default()
+        };
+
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults), &overrides);
+
+        assert_eq!(resolved.audio.general_volume, 0.4);
+    }
+
+    #[test]
+    fn override_no_audio_over_guild_volume() {
+        let overrides = RenderOptions {
+            no_audio: true,
+            ..Default::default()
+        };
+
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults()), &overrides);
+
+        assert_eq!(resolved.audio.general_volume, 0.0);
+    }
+
+    #[test]
+    fn storyboard_override() {
+        let guild_defaults = RenderOptions {
+            storyboard: Some(true),
+            ..Default::default()
+        };
+
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults), &RenderOptions::default());
+
+        assert!(resolved.playfield.background.load_storyboards);
+
+        let overrides = RenderOptions {
+            storyboard: Some(false),
+            ..Default::default()
+        };
+
+        let base = SettingsBase::Default(settings(0.5, "default"));
+        let resolved = resolve(base, Some(&guild_defaults), &overrides);
+
+        assert!(!resolved.playfield.background.load_storyboards);
+    }
+}
@@ -1,39 +1,241 @@
-use std::{ffi::OsString, fs};
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use eyre::{Context as _, Result};
 
-use crate::core::BotConfig;
+use crate::core::{replay_queue::skin_ini, BotConfig};
 
-/// Cache skin names to avoid IO interactions
-#[derive(Default)]
+/// Cache skin names to avoid IO interactions.
+///
+/// Since the list mirrors the skins folder, any modification of
+/// that folder should happen while holding the list's lock.
 pub struct SkinList {
     skins: Option<Vec<OsString>>,
+    skins_dir: PathBuf,
+    order_path: PathBuf,
+}
+
+impl Default for SkinList {
+    fn default() -> Self {
+        let paths = &BotConfig::get().paths;
+
+        Self::new(paths.skins(), paths.skin_order())
+    }
 }
 
 impl SkinList {
+    pub fn new(skins_dir: PathBuf, order_path: PathBuf) -> Self {
+        Self {
+            skins: None,
+            skins_dir,
+            order_path,
+        }
+    }
+
     pub fn get(&mut self) -> Result<&[OsString]> {
-        if let Some(ref skins) = self.skins {
-            return Ok(skins);
+        self.get_mut().map(Vec::as_slice)
+    }
+
+    /// Move the skin at index `from` to index `to`, both zero-based.
+    ///
+    /// Returns the name of the moved skin or `None` if an index was out of bounds.
+    pub fn move_skin(&mut self, from: usize, to: usize) -> Result<Option<OsString>> {
+        let skins = self.get_mut()?;
+
+        if from >= skins.len() || to >= skins.len() {
+            return Ok(None);
+        }
+
+        let skin = skins.remove(from);
+        skins.insert(to, skin.clone());
+        self.store_order()?;
+
+        Ok(Some(skin))
+    }
+
+    /// Delete the skin at the zero-based index and shift all
+    /// subsequent skins up by one.
+    ///
+    /// Returns the name of the removed skin or `None` if the index was out of bounds.
+    pub fn remove(&mut self, idx: usize) -> Result<Option<OsString>> {
+        let skins = self.get_mut()?;
+
+        if idx >= skins.len() {
+            return Ok(None);
         }
 
-        let path = BotConfig::get().paths.skins();
+        let skin = skins.remove(idx);
+
+        let mut skin_path = self.skins_dir.clone();
+        skin_path.push(&skin);
+
+        let remove_res = fs::remove_dir_all(&skin_path)
+            .with_context(|| format!("failed to remove skin directory {skin_path:?}"));
+
+        if let Err(err) = remove_res {
+            // The folder might be in an unknown state so the list is read anew
+            self.clear();
 
-        let mut skins = fs::read_dir(&path)
+            return Err(err);
+        }
+
+        self.store_order()?;
+
+        Ok(Some(skin))
+    }
+
+    pub fn clear(&mut self) {
+        self.skins = None;
+
+        info!("Cleared skin list cache");
+    }
+
+    fn store_order(&self) -> Result<()> {
+        store_order(&self.order_path, self.skins.as_deref().unwrap_or_default())
+    }
+
+    fn get_mut(&mut self) -> Result<&mut Vec<OsString>> {
+        if let Some(ref mut skins) = self.skins {
+            return Ok(skins);
+        }
+
+        let mut unordered = fs::read_dir(&self.skins_dir)
             .context("failed to read skins folder")?
             .map(|res| res.map(|entry| entry.file_name()))
-            .collect::<Result<Vec<_>, _>>()
+            .filter(|res| {
+                res.as_ref()
+                    .map_or(true, |name| !skin_ini::is_temp_skin(name))
+            })
+            .collect::<Result<HashSet<_>, _>>()
             .context("failed to read entry of skins folder")?;
 
-        skins.sort_unstable_by_key(|name| name.to_ascii_lowercase());
+        // Skins that have a stored position keep it, new ones are appended
+        let order = read_order(&self.order_path)?;
+        let order_len = order.len();
+        let mut skins = Vec::with_capacity(unordered.len());
+
+        for skin in order {
+            if unordered.remove(&skin) {
+                skins.push(skin);
+            }
+        }
+
+        let stored = skins.len();
+        skins.extend(unordered);
+        skins[stored..].sort_unstable_by_key(|name| name.to_ascii_lowercase());
+
+        if stored < skins.len() || stored < order_len {
+            store_order(&self.order_path, &skins)?;
+        }
 
         info!("Repopulated skin list cache");
 
         Ok(self.skins.insert(skins))
     }
+}
 
-    pub fn clear(&mut self) {
-        self.skins = None;
+fn read_order(path: &Path) -> Result<Vec<OsString>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
 
-        info!("Cleared skin list cache");
+    let bytes = fs::read(path).context("failed to read skin order file")?;
+
+    let order: Vec<String> =
+        serde_json::from_slice(&bytes).context("failed to deserialize skin order file")?;
+
+    Ok(order.into_iter().map(OsString::from).collect())
+}
+
+fn store_order(path: &Path, skins: &[OsString]) -> Result<()> {
+    let order: Vec<_> = skins.iter().map(|skin| skin.to_string_lossy()).collect();
+    let bytes = serde_json::to_vec(&order).context("failed to serialize skin order")?;
+
+    fs::write(path, bytes).context("failed to write skin order file")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, sync::Arc};
+
+    use tokio::sync::Mutex;
+
+    use super::SkinList;
+
+    fn skin_list(name: &str) -> (SkinList, PathBuf) {
+        let mut root = env::temp_dir();
+        root.push(format!("shishabot_skin_list_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let skins_dir = root.join("Skins");
+
+        for i in 0..8 {
+            fs::create_dir_all(skins_dir.join(format!("skin_{i}"))).unwrap();
+        }
+
+        let list = SkinList::new(skins_dir, root.join("skin_order.json"));
+
+        (list, root)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_add_remove_read() {
+        let (list, root) = skin_list("concurrent");
+        let skins_dir = root.join("Skins");
+        let list = Arc::new(Mutex::new(list));
+        let mut tasks = Vec::new();
+
+        for i in 0..8 {
+            let list = Arc::clone(&list);
+            let skins_dir = skins_dir.clone();
+
+            // Add a new skin the way `/skin add` does it
+            tasks.push(tokio::spawn(async move {
+                let mut list = list.lock().await;
+                fs::create_dir(skins_dir.join(format!("added_{i}"))).unwrap();
+                list.clear();
+
+                let added = format!("added_{i}");
+                assert!(list.get().unwrap().iter().any(|skin| *skin == *added));
+            }));
+
+            let list = Arc::clone(&list);
+
+            tasks.push(tokio::spawn(async move {
+                let removed = list.lock().await.remove(0).unwrap();
+                assert!(removed.is_some());
+            }));
+
+            let list = Arc::clone(&list);
+            let skins_dir = skins_dir.clone();
+
+            // Resolve every index the way `/skinpreview` does it
+            tasks.push(tokio::spawn(async move {
+                let mut list = list.lock().await;
+
+                for skin in list.get().unwrap() {
+                    assert!(skins_dir.join(skin).exists(), "skin {skin:?} not found");
+                }
+            }));
+        }
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // 8 initial skins + 8 added - 8 removed
+        assert_eq!(list.lock().await.get().unwrap().len(), 8);
+
+        // The persisted order must match the folder after reloading
+        let mut list = list.lock().await;
+        let cached = list.get().unwrap().to_vec();
+        list.clear();
+        assert_eq!(list.get().unwrap(), cached.as_slice());
+
+        fs::remove_dir_all(root).unwrap();
     }
 }
@@ -0,0 +1,330 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    hash::{BuildHasher, Hash},
+    sync::Arc,
+    time::Duration,
+};
+
+use eyre::Report;
+use time::OffsetDateTime;
+use tokio::time::{interval, MissedTickBehavior};
+use twilight_model::{
+    channel::embed::EmbedField,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    core::{
+        stats::{BotStats, FailureReason, RenderActivity},
+        Context,
+    },
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        ChannelExt,
+    },
+};
+
+/// Amount of users and servers that are listed as the busiest ones
+const TOP_AMOUNT: usize = 5;
+
+/// How often the digest is posted
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DigestSchedule {
+    Daily,
+    Weekly,
+}
+
+impl DigestSchedule {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" | "day" => Some(Self::Daily),
+            "weekly" | "week" => Some(Self::Weekly),
+            _ => None,
+        }
+    }
+
+    fn period(self) -> Duration {
+        match self {
+            Self::Daily => Duration::from_secs(24 * 60 * 60),
+            Self::Weekly => Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Daily => "Daily digest",
+            Self::Weekly => "Weekly digest",
+        }
+    }
+}
+
+/// Values of the render counters at the time of a digest
+struct CounterSnapshot {
+    completed: u64,
+    failed: [u64; FailureReason::ALL.len()],
+    duration_sum: f64,
+    duration_count: u64,
+}
+
+impl CounterSnapshot {
+    fn take(stats: &BotStats) -> Self {
+        Self {
+            completed: stats.renders.completed_total(),
+            failed: FailureReason::ALL.map(|reason| stats.renders.failed_total(reason)),
+            duration_sum: stats.render_duration.get_sample_sum(),
+            duration_count: stats.render_duration.get_sample_count(),
+        }
+    }
+}
+
+/// Render activity between two digests
+struct Digest {
+    completed: u64,
+    /// Only reasons that occurred at least once
+    failed: Vec<(FailureReason, u64)>,
+    average_duration: Option<Duration>,
+    top_users: Vec<(Id<UserMarker>, usize)>,
+    top_guilds: Vec<(Id<GuildMarker>, usize)>,
+}
+
+impl Digest {
+    fn new(
+        previous: &CounterSnapshot,
+        current: &CounterSnapshot,
+        activity: &RenderActivity,
+    ) -> Self {
+        let failed = FailureReason::ALL
+            .into_iter()
+            .zip(previous.failed.iter().zip(current.failed.iter()))
+            .map(|(reason, (prev, curr))| (reason, curr.saturating_sub(*prev)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        let count = current
+            .duration_count
+            .saturating_sub(previous.duration_count);
+        let sum = current.duration_sum - previous.duration_sum;
+
+        let average_duration =
+            (count > 0).then(|| Duration::from_secs_f64((sum / count as f64).max(0.0)));
+
+        Self {
+            completed: current.completed.saturating_sub(previous.completed),
+            failed,
+            average_duration,
+            top_users: top(&activity.users),
+            top_guilds: top(&activity.guilds),
+        }
+    }
+
+    fn failed_total(&self) -> u64 {
+        self.failed.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// The entries with the highest counts, highest first
+fn top<K: Copy + Eq + Hash + Ord, S: BuildHasher>(
+    counts: &HashMap<K, usize, S>,
+) -> Vec<(K, usize)> {
+    let mut entries: Vec<_> = counts.iter().map(|(&key, &count)| (key, count)).collect();
+    entries.sort_unstable_by(|(a_key, a), (b_key, b)| b.cmp(a).then(a_key.cmp(b_key)));
+    entries.truncate(TOP_AMOUNT);
+
+    entries
+}
+
+/// Post a summary of the render activity into the channel on the given schedule
+pub fn schedule(ctx: Arc<Context>, schedule: DigestSchedule, channel: Id<ChannelMarker>) {
+    tokio::spawn(async move {
+        let mut interval = interval(schedule.period());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        // The first tick completes immediately
+        interval.tick().await;
+        let mut previous = CounterSnapshot::take(&ctx.stats);
+        ctx.stats.renders.take_activity();
+
+        info!("Posting a {schedule:?} digest into channel {channel}");
+
+        loop {
+            interval.tick().await;
+
+            let current = CounterSnapshot::take(&ctx.stats);
+            let activity = ctx.stats.renders.take_activity();
+            let digest = Digest::new(&previous, &current, &activity);
+            previous = current;
+
+            // Server names are nicer to read than their ids
+            let guild_name = |guild: Id<GuildMarker>| {
+                ctx.cache
+                    .guild(guild, |g| g.name().to_owned())
+                    .unwrap_or_else(|_| guild.to_string())
+            };
+
+            let embed = digest_embed(schedule, &digest, guild_name);
+            let builder = MessageBuilder::new().embed(embed);
+
+            if let Err(err) = channel.create_message(&ctx, &builder).await {
+                let err = Report::from(err).wrap_err("failed to post digest");
+                warn!("{err:?}");
+            }
+        }
+    });
+}
+
+fn digest_embed<F>(schedule: DigestSchedule, digest: &Digest, guild_name: F) -> EmbedBuilder
+where
+    F: Fn(Id<GuildMarker>) -> String,
+{
+    let mut fields = vec![
+        EmbedField {
+            inline: true,
+            name: "Completed".to_owned(),
+            value: digest.completed.to_string(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Failed".to_owned(),
+            value: digest.failed_total().to_string(),
+        },
+        EmbedField {
+            inline: true,
+            name: "Average render time".to_owned(),
+            value: match digest.average_duration {
+                Some(duration) => {
+                    let secs = duration.as_secs();
+
+                    format!("{}:{:02}", secs / 60, secs % 60)
+                }
+                None => "-".to_owned(),
+            },
+        },
+    ];
+
+    if !digest.failed.is_empty() {
+        let mut value = String::new();
+
+        for (reason, count) in digest.failed.iter() {
+            let _ = writeln!(value, "{}: {count}", reason.as_str());
+        }
+
+        fields.push(EmbedField {
+            inline: false,
+            name: "Failures by reason".to_owned(),
+            value,
+        });
+    }
+
+    let users = digest
+        .top_users
+        .iter()
+        .map(|&(user, count)| (format!("<@{user}>"), count));
+
+    let guilds = digest
+        .top_guilds
+        .iter()
+        .map(|&(guild, count)| (guild_name(guild), count));
+
+    if let Some(field) = ranking_field("Busiest users", users) {
+        fields.push(field);
+    }
+
+    if let Some(field) = ranking_field("Busiest servers", guilds) {
+        fields.push(field);
+    }
+
+    EmbedBuilder::new()
+        .title(schedule.title())
+        .fields(fields)
+        .timestamp(OffsetDateTime::now_utc())
+}
+
+/// Numbered list of the entries or `None` if there are none
+fn ranking_field(name: &str, entries: impl Iterator<Item = (String, usize)>) -> Option<EmbedField> {
+    let mut value = String::new();
+
+    for (i, (entry, count)) in entries.enumerate() {
+        let _ = writeln!(value, "{}. {entry}: {count}", i + 1);
+    }
+
+    (!value.is_empty()).then(|| EmbedField {
+        inline: true,
+        name: name.to_owned(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(completed: u64, danser_failures: u64, sum: f64) -> CounterSnapshot {
+        let mut failed = [0; FailureReason::ALL.len()];
+        failed[2] = danser_failures;
+
+        CounterSnapshot {
+            completed,
+            failed,
+            duration_sum: sum,
+            duration_count: completed,
+        }
+    }
+
+    #[test]
+    fn digest_covers_period() {
+        let previous = snapshot(10, 1, 600.0);
+        let current = snapshot(14, 3, 1080.0);
+
+        let mut activity = RenderActivity::default();
+        activity.users.insert(Id::new(1), 1);
+        activity.users.insert(Id::new(2), 3);
+        activity.guilds.insert(Id::new(3), 4);
+
+        let digest = Digest::new(&previous, &current, &activity);
+
+        assert_eq!(digest.completed, 4);
+        assert_eq!(digest.failed, [(FailureReason::Danser, 2)]);
+        assert_eq!(digest.failed_total(), 2);
+        assert_eq!(digest.average_duration, Some(Duration::from_secs(120)));
+        assert_eq!(digest.top_users, [(Id::new(2), 3), (Id::new(1), 1)]);
+        assert_eq!(digest.top_guilds, [(Id::new(3), 4)]);
+    }
+
+    #[test]
+    fn quiet_period() {
+        let snapshot = snapshot(10, 1, 600.0);
+        let digest = Digest::new(&snapshot, &snapshot, &RenderActivity::default());
+
+        assert_eq!(digest.completed, 0);
+        assert!(digest.failed.is_empty());
+        assert_eq!(digest.average_duration, None);
+    }
+
+    #[test]
+    fn top_is_truncated() {
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+
+        for i in 0..10 {
+            counts.insert(i, i as usize);
+        }
+
+        let top = top(&counts);
+
+        assert_eq!(top.len(), TOP_AMOUNT);
+        assert_eq!(top[0], (9, 9));
+    }
+
+    #[test]
+    fn parses_schedule() {
+        assert_eq!(
+            DigestSchedule::parse("Weekly"),
+            Some(DigestSchedule::Weekly)
+        );
+        assert_eq!(DigestSchedule::parse(" daily"), Some(DigestSchedule::Daily));
+        assert_eq!(DigestSchedule::parse("hourly"), None);
+    }
+}
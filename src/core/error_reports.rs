@@ -0,0 +1,209 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    mem,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use eyre::Report;
+use time::OffsetDateTime;
+use twilight_model::{
+    channel::embed::EmbedField,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    core::{BotConfig, Context},
+    util::{
+        builder::{EmbedBuilder, FooterBuilder, MessageBuilder},
+        constants::RED,
+        ChannelExt,
+    },
+};
+
+/// Identical errors are reported at most once within this duration
+const THROTTLE: Duration = Duration::from_secs(10 * 60);
+
+/// Embed descriptions may not be longer than this
+const MAX_DESCRIPTION_LEN: usize = 4096;
+
+/// Where an error occurred and who was affected by it
+pub struct ErrorReport<'a> {
+    /// E.g. the command's name or `render`
+    pub origin: &'a str,
+    pub user: Option<Id<UserMarker>>,
+    pub guild: Option<Id<GuildMarker>>,
+    pub err: &'a Report,
+}
+
+/// Keeps track of recently reported errors so
+/// that repeated ones don't spam the error channel.
+#[derive(Default)]
+pub struct ErrorReports {
+    recent: Mutex<HashMap<String, RecentError>>,
+}
+
+struct RecentError {
+    reported_at: Instant,
+    suppressed: usize,
+}
+
+impl ErrorReports {
+    /// Returns how often the error was suppressed since its last report
+    /// if it should be reported, or `None` if it's throttled.
+    fn check(&self, summary: &str, now: Instant) -> Option<usize> {
+        let mut recent = self.recent.lock().unwrap();
+
+        let res = match recent.get_mut(summary) {
+            Some(error) if now.duration_since(error.reported_at) < THROTTLE => {
+                error.suppressed += 1;
+
+                None
+            }
+            Some(error) => {
+                error.reported_at = now;
+
+                Some(mem::take(&mut error.suppressed))
+            }
+            None => {
+                let error = RecentError {
+                    reported_at: now,
+                    suppressed: 0,
+                };
+
+                recent.insert(summary.to_owned(), error);
+
+                Some(0)
+            }
+        };
+
+        // Errors whose throttle is over are no longer needed
+        recent.retain(|_, error| now.duration_since(error.reported_at) < THROTTLE);
+
+        res
+    }
+}
+
+impl Context {
+    /// Post a summary of the error into the configured error channel.
+    ///
+    /// Does nothing if no channel is configured or if the
+    /// identical error was reported recently.
+    pub async fn report_error(&self, report: ErrorReport<'_>) {
+        let channel = match BotConfig::get().error_channel {
+            Some(channel) => channel,
+            None => return,
+        };
+
+        let summary = summarize(report.err);
+
+        let suppressed = match self.error_reports.check(&summary, Instant::now()) {
+            Some(suppressed) => suppressed,
+            None => return,
+        };
+
+        let mut fields = vec![EmbedField {
+            inline: true,
+            name: "Origin".to_owned(),
+            value: format!("`{}`", report.origin),
+        }];
+
+        if let Some(user) = report.user {
+            fields.push(EmbedField {
+                inline: true,
+                name: "User".to_owned(),
+                value: format!("<@{user}> ({user})"),
+            });
+        }
+
+        if let Some(guild) = report.guild {
+            fields.push(EmbedField {
+                inline: true,
+                name: "Guild".to_owned(),
+                value: guild.to_string(),
+            });
+        }
+
+        let mut embed = EmbedBuilder::new()
+            .color(RED)
+            .title("Error report")
+            .description(summary)
+            .fields(fields)
+            .timestamp(OffsetDateTime::now_utc());
+
+        if suppressed > 0 {
+            let footer = format!("Occurred {suppressed} more time(s) since the last report");
+            embed = embed.footer(FooterBuilder::new(footer));
+        }
+
+        let builder = MessageBuilder::new().embed(embed);
+
+        if let Err(err) = channel.create_message(self, &builder).await {
+            let err = Report::from(err).wrap_err("failed to send error report");
+            warn!("{err:?}");
+        }
+    }
+}
+
+/// Code block of the error chain, one cause per line
+fn summarize(err: &Report) -> String {
+    let mut summary = String::from("```\n");
+
+    for (i, cause) in err.chain().enumerate() {
+        let _ = writeln!(summary, "{i}: {cause}");
+    }
+
+    // Leave room for the closing backticks
+    if summary.len() > MAX_DESCRIPTION_LEN - 8 {
+        let mut end = MAX_DESCRIPTION_LEN - 8 - 3;
+
+        while !summary.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        summary.truncate(end);
+        summary.push_str("...\n");
+    }
+
+    summary.push_str("```");
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_contains_chain() {
+        let err = eyre!("inner").wrap_err("outer");
+
+        assert_eq!(summarize(&err), "```\n0: outer\n1: inner\n```");
+    }
+
+    #[test]
+    fn summary_is_truncated() {
+        let err = eyre!("{}", "a".repeat(5000));
+        let summary = summarize(&err);
+
+        assert!(summary.len() <= MAX_DESCRIPTION_LEN);
+        assert!(summary.ends_with("...\n```"));
+    }
+
+    #[test]
+    fn identical_errors_are_throttled() {
+        let reports = ErrorReports::default();
+        let now = Instant::now();
+
+        assert_eq!(reports.check("a", now), Some(0));
+        assert_eq!(reports.check("a", now + Duration::from_secs(1)), None);
+        assert_eq!(reports.check("b", now + Duration::from_secs(1)), Some(0));
+
+        assert_eq!(reports.check("a", now + THROTTLE), Some(1));
+        assert_eq!(reports.check("a", now + THROTTLE), None);
+    }
+}
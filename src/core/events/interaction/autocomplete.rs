@@ -5,7 +5,7 @@ use eyre::Context as _;
 use crate::{
     commands::danser::slash_settings,
     core::{events::EventLocation, Context},
-    util::{interaction::InteractionCommand, Authored},
+    util::{global_ratelimit, interaction::InteractionCommand, Authored},
 };
 
 pub async fn handle_autocomplete(ctx: Arc<Context>, mut command: InteractionCommand) {
@@ -27,6 +27,11 @@ pub async fn handle_autocomplete(ctx: Arc<Context>, mut command: InteractionComm
     };
 
     if let Err(err) = res.with_context(|| format!("failed to process autocomplete `{name}`")) {
-        error!("{err:?}");
+        // Autocompletes can't respond with a message so there's no one to notify
+        if let Some(retry_after) = global_ratelimit(&err) {
+            warn!("Globally ratelimited during autocomplete `{name}`, retry after {retry_after:?}");
+        } else {
+            error!("{err:?}");
+        }
     }
 }
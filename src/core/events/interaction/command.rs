@@ -1,6 +1,7 @@
 use std::{mem, sync::Arc};
 
 use eyre::{Context as _, Result};
+use tracing::{field, Instrument};
 
 use crate::{
     core::{
@@ -9,9 +10,10 @@ use crate::{
             slash::{Command, Commands, SlashCommand},
         },
         events::{EventLocation, ProcessResult},
-        BotConfig, Context,
+        locale::Msg,
+        BotConfig, Context, ErrorReport,
     },
-    util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
+    util::{global_ratelimit, interaction::InteractionCommand, Authored, InteractionCommandExt},
 };
 
 pub async fn handle_command(ctx: Arc<Context>, mut command: InteractionCommand) {
@@ -32,22 +34,79 @@ pub async fn handle_command(ctx: Arc<Context>, mut command: InteractionCommand)
         None => return error!("unknown slash command `{name}`"),
     };
 
-    let res = process_command(ctx, command, slash)
+    // Correlates all logs that are emitted while processing the command
+    let span = info_span!(
+        "command",
+        name = %name,
+        user = field::Empty,
+        guild = field::Empty,
+        replay = field::Empty,
+    );
+
+    let user = command.user_id().ok();
+    let guild = command.guild_id;
+    // Kept to let the user know if the command fails due to a global ratelimit
+    let notifier = command.clone();
+
+    if let Some(user) = user {
+        span.record("user", &user.get());
+    }
+
+    if let Some(guild) = guild {
+        span.record("guild", &guild.get());
+    }
+
+    let res = process_command(Arc::clone(&ctx), command, &name, slash)
+        .instrument(span)
         .await
         .with_context(|| format!("failed to process slash command `{name}`"));
 
     match res {
         Ok(ProcessResult::Success) => info!("Processed slash command `{name}`"),
         Ok(res) => info!("Command `/{name}` was not processed: {res:?}"),
-        Err(err) => error!("{err:?}"),
+        Err(err) => {
+            // Not worth reporting since it's not a bug and the report would be ratelimited too
+            if let Some(retry_after) = global_ratelimit(&err) {
+                warn!(
+                    "Globally ratelimited by discord during `/{name}`, retry after {retry_after:?}"
+                );
+
+                if let Err(err) = notifier.ratelimited(&ctx).await {
+                    warn!("Failed to notify about global ratelimit: {err}");
+                }
+
+                return;
+            }
+
+            error!("{err:?}");
+
+            let origin = format!("/{name}");
+
+            let report = ErrorReport {
+                origin: &origin,
+                user,
+                guild,
+                err: &err,
+            };
+
+            ctx.report_error(report).await;
+        }
     }
 }
 
 async fn process_command(
     ctx: Arc<Context>,
     command: InteractionCommand,
+    name: &str,
     cmd: Command,
 ) -> Result<ProcessResult> {
+    if ctx.command_disabled(command.guild_id, name) {
+        let content = ctx.guild_locale(command.guild_id).get(Msg::CommandDisabled);
+        command.error_callback(&ctx, content, true).await?;
+
+        return Ok(ProcessResult::Disabled);
+    }
+
     match cmd {
         Command::Slash(slash) => match pre_process_command(&ctx, &command, slash).await? {
             Some(result) => Ok(result),
@@ -3,10 +3,20 @@ use std::{mem, sync::Arc};
 use eyre::Context as _;
 
 use crate::{
-    commands::help::{handle_help_basecommand, handle_help_subcommand},
+    commands::{
+        danser::{
+            handle_setup_wizard_dismiss, handle_setup_wizard_done, handle_setup_wizard_input,
+            handle_setup_wizard_locale, handle_setup_wizard_output, handle_trim_cancel,
+            handle_trim_confirm, handle_trim_end_back, handle_trim_end_custom,
+            handle_trim_end_forward, handle_trim_reset, handle_trim_start_back,
+            handle_trim_start_custom, handle_trim_start_forward,
+        },
+        help::{handle_help_basecommand, handle_help_subcommand},
+        utility::{handle_forget_me_cancel, handle_forget_me_confirm},
+    },
     core::{events::EventLocation, Context},
     pagination::components::*,
-    util::{interaction::InteractionComponent, Authored},
+    util::{global_ratelimit, interaction::InteractionComponent, Authored, ComponentExt},
 };
 
 pub async fn handle_component(ctx: Arc<Context>, mut component: InteractionComponent) {
@@ -22,21 +32,53 @@ pub async fn handle_component(ctx: Arc<Context>, mut component: InteractionCompo
         info!("[{location}] {username} invoked component `{name}`");
     }
 
-    let res = match name.as_str() {
-        "help_basecommand" => handle_help_basecommand(&ctx, component).await,
-        "help_subcommand" => handle_help_subcommand(&ctx, component).await,
-        "pagination_start" => handle_pagination_start(ctx, component).await,
-        "pagination_back" => handle_pagination_back(ctx, component).await,
-        "pagination_custom" => handle_pagination_custom(ctx, component).await,
-        "pagination_step" => handle_pagination_step(ctx, component).await,
-        "pagination_end" => handle_pagination_end(ctx, component).await,
-        "profile_compact" => handle_profile_compact(ctx, component).await,
-        "profile_medium" => handle_profile_medium(ctx, component).await,
-        "profile_full" => handle_profile_full(ctx, component).await,
-        _ => return error!("unknown message component `{name}`"),
+    // Kept to let the user know if the component fails due to a global ratelimit
+    let notifier = component.clone();
+
+    let res = {
+        // The context is still needed for the ratelimit notice
+        let ctx = Arc::clone(&ctx);
+
+        match name.as_str() {
+            "forget_me_cancel" => handle_forget_me_cancel(ctx, component).await,
+            "forget_me_confirm" => handle_forget_me_confirm(ctx, component).await,
+            "help_basecommand" => handle_help_basecommand(&ctx, component).await,
+            "help_subcommand" => handle_help_subcommand(&ctx, component).await,
+            "pagination_start" => handle_pagination_start(ctx, component).await,
+            "pagination_back" => handle_pagination_back(ctx, component).await,
+            "pagination_custom" => handle_pagination_custom(ctx, component).await,
+            "pagination_step" => handle_pagination_step(ctx, component).await,
+            "pagination_end" => handle_pagination_end(ctx, component).await,
+            "profile_compact" => handle_profile_compact(ctx, component).await,
+            "profile_medium" => handle_profile_medium(ctx, component).await,
+            "profile_full" => handle_profile_full(ctx, component).await,
+            "setup_wizard_input" => handle_setup_wizard_input(ctx, component).await,
+            "setup_wizard_output" => handle_setup_wizard_output(ctx, component).await,
+            "setup_wizard_locale" => handle_setup_wizard_locale(ctx, component).await,
+            "setup_wizard_done" => handle_setup_wizard_done(ctx, component).await,
+            "setup_wizard_dismiss" => handle_setup_wizard_dismiss(ctx, component).await,
+            "trim_start_back" => handle_trim_start_back(ctx, component).await,
+            "trim_start_forward" => handle_trim_start_forward(ctx, component).await,
+            "trim_start_custom" => handle_trim_start_custom(ctx, component).await,
+            "trim_end_back" => handle_trim_end_back(ctx, component).await,
+            "trim_end_forward" => handle_trim_end_forward(ctx, component).await,
+            "trim_end_custom" => handle_trim_end_custom(ctx, component).await,
+            "trim_reset" => handle_trim_reset(ctx, component).await,
+            "trim_confirm" => handle_trim_confirm(ctx, component).await,
+            "trim_cancel" => handle_trim_cancel(ctx, component).await,
+            _ => return error!("unknown message component `{name}`"),
+        }
     };
 
     if let Err(err) = res.with_context(|| format!("failed to process component `{name}`")) {
-        error!("{err:?}");
+        if let Some(retry_after) = global_ratelimit(&err) {
+            warn!("Globally ratelimited during component `{name}`, retry after {retry_after:?}");
+
+            if let Err(err) = notifier.ratelimited(&ctx).await {
+                warn!("Failed to notify about global ratelimit: {err}");
+            }
+        } else {
+            error!("{err:?}");
+        }
     }
 }
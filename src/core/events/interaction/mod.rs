@@ -32,6 +32,12 @@ pub async fn handle_interaction(ctx: Arc<Context>, interaction: Interaction) {
         ..
     } = interaction;
 
+    // Discord occasionally delivers the same interaction twice which
+    // would otherwise e.g. queue a render twice
+    if !ctx.handled_interactions.first_delivery(id) {
+        return warn!("ignoring duplicate delivery of interaction {id}");
+    }
+
     let channel_id = match channel_id {
         Some(channel_id) => channel_id,
         None => return warn!("no channel id for interaction kind {kind:?}"),
@@ -3,9 +3,10 @@ use std::{mem, sync::Arc};
 use eyre::Context as _;
 
 use crate::{
+    commands::danser::{handle_trim_end_modal, handle_trim_start_modal},
     core::{events::EventLocation, Context},
     pagination::components::handle_pagination_modal,
-    util::{interaction::InteractionModal, Authored},
+    util::{global_ratelimit, interaction::InteractionModal, Authored, ModalExt},
 };
 
 pub async fn handle_modal(ctx: Arc<Context>, mut modal: InteractionModal) {
@@ -21,12 +22,30 @@ pub async fn handle_modal(ctx: Arc<Context>, mut modal: InteractionModal) {
         info!("[{location}] {username} invoked modal `{name}`");
     }
 
-    let res = match name.as_str() {
-        "pagination_page" => handle_pagination_modal(ctx, modal).await,
-        _ => return error!("unknown modal `{name}`: {modal:#?}"),
+    // Kept to let the user know if the modal fails due to a global ratelimit
+    let notifier = modal.clone();
+
+    let res = {
+        // The context is still needed for the ratelimit notice
+        let ctx = Arc::clone(&ctx);
+
+        match name.as_str() {
+            "pagination_page" => handle_pagination_modal(ctx, modal).await,
+            "trim_start" => handle_trim_start_modal(ctx, modal).await,
+            "trim_end" => handle_trim_end_modal(ctx, modal).await,
+            _ => return error!("unknown modal `{name}`: {modal:#?}"),
+        }
     };
 
     if let Err(err) = res.with_context(|| format!("failed to process modal `{name}`")) {
-        error!("{err:?}");
+        if let Some(retry_after) = global_ratelimit(&err) {
+            warn!("Globally ratelimited during modal `{name}`, retry after {retry_after:?}");
+
+            if let Err(err) = notifier.ratelimited(&ctx).await {
+                warn!("Failed to notify about global ratelimit: {err}");
+            }
+        } else {
+            error!("{err:?}");
+        }
     }
 }
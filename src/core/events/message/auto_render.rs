@@ -0,0 +1,95 @@
+use std::time::Instant;
+
+use eyre::{ContextCompat, Result};
+use twilight_model::channel::{Attachment, Message};
+
+use crate::{
+    commands::danser::{
+        enqueue, fetch_replay, render_rejection, replay_warnings, store_replay, Requester,
+    },
+    core::{locale::Msg, Context, RenderOptions, ReplayData, ReplaySlim, TimePoints},
+    util::{builder::MessageBuilder, ChannelExt},
+};
+
+/// Queue the replay of a message that was sent in an input
+/// channel of a server that has auto-render enabled.
+pub(super) async fn auto_render(
+    ctx: &Context,
+    msg: &Message,
+    attachment: &Attachment,
+) -> Result<()> {
+    let guild = msg.guild_id.context("expected guild id")?;
+    let locale = ctx.guild_locale(Some(guild));
+
+    // Auto-render is a shortcut for /render so it's disabled alongside it
+    if ctx.command_disabled(Some(guild), "render") {
+        msg.error(ctx, locale.get(Msg::CommandDisabled)).await?;
+
+        return Ok(());
+    }
+
+    let output_channel = match ctx.guild_settings(guild, |s| s.output_channel).flatten() {
+        Some(channel) => channel,
+        None => {
+            msg.error(ctx, locale.get(Msg::MissingOutputChannel))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let user = msg.author.id;
+
+    if let Some(content) = render_rejection(ctx, user, msg.channel_id, Some(guild)).await {
+        msg.error(ctx, content).await?;
+
+        return Ok(());
+    }
+
+    let requester = Requester::Message(msg);
+
+    let (bytes, replay) = match fetch_replay(ctx, requester, attachment).await? {
+        Some(tuple) => tuple,
+        None => return Ok(()),
+    };
+
+    if replay.beatmap_hash.is_none() {
+        let content = "The replay does not contain the hash of its map.\n\
+            Use `/render` and attach the map as `beatmap` instead.";
+        msg.error(ctx, content).await?;
+
+        return Ok(());
+    }
+
+    let replay_file = store_replay(ctx, requester, &attachment.filename, &bytes).await?;
+
+    let (replay, parse_warnings) = ReplaySlim::with_warnings(replay);
+    let time_points = TimePoints { start: 0, end: 0 };
+
+    let replay_data = ReplayData {
+        id: ctx.replay_queue.next_id(),
+        comparison: None,
+        guild: Some(guild),
+        input_channel: msg.channel_id,
+        interaction_token: None,
+        local_map: None,
+        output_channel,
+        options: RenderOptions::default(),
+        path: replay_file,
+        queued_at: Instant::now(),
+        render_for: None,
+        replay,
+        skin_ini: None,
+        time_points,
+        title: None,
+        user,
+    };
+
+    let warnings = replay_warnings(ctx, Some(guild), &bytes, parse_warnings);
+    let content = enqueue(ctx, replay_data, vec![time_points], locale, &warnings).await;
+
+    let builder = MessageBuilder::new().embed(content);
+    msg.create_message(ctx, &builder).await?;
+
+    Ok(())
+}
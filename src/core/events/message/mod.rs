@@ -2,25 +2,69 @@ use std::sync::Arc;
 
 use twilight_model::channel::Message;
 
-use crate::{core::Context, util::ChannelExt};
+use crate::{
+    core::{Context, ErrorReport},
+    util::{global_ratelimit, ChannelExt},
+};
+
+use self::auto_render::auto_render;
+
+mod auto_render;
 
 pub async fn handle_message(ctx: Arc<Context>, msg: Message) {
-    if let Some(attachment) = msg.attachments.first() {
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return;
+    }
+
+    let attachment = msg
+        .attachments
+        .iter()
+        .find(|attachment| matches!(attachment.filename.split('.').last(), Some("osr")));
+
+    if let Some(attachment) = attachment {
         let content = "Hey! Looks like you tried to send a replay\nPlease use **/render** as we have fully migrated to slash commands.";
-        if matches!(attachment.filename.split('.').last(), Some("osr")) {
-            let valid_input_channel = msg
-                .guild_id
-                .map(|f| ctx.guild_settings(f, |s| s.input_channels.contains(&msg.channel_id)));
-
-            match valid_input_channel {
-                Some(Some(true)) => {
-                    let _ = msg.error(&ctx, content).await;
-                }
-                Some(Some(false) | None) => {}
-                None => {
-                    let _ = msg.error(&ctx, content).await;
+
+        let valid_input_channel = msg.guild_id.map(|f| {
+            ctx.guild_settings(f, |s| {
+                s.input_channels
+                    .contains(&msg.channel_id)
+                    .then_some(s.auto_render)
+            })
+        });
+
+        match valid_input_channel {
+            Some(Some(Some(true))) => {
+                if let Err(err) = auto_render(&ctx, &msg, attachment).await {
+                    if let Some(retry_after) = global_ratelimit(&err) {
+                        warn!(
+                            "Globally ratelimited by discord during auto-render, \
+                            retry after {retry_after:?}"
+                        );
+                        let _ = msg.ratelimited(&ctx).await;
+
+                        return;
+                    }
+
+                    let err = err.wrap_err("failed to auto-render replay");
+                    warn!("{err:?}");
+
+                    let report = ErrorReport {
+                        origin: "auto-render",
+                        user: Some(msg.author.id),
+                        guild: msg.guild_id,
+                        err: &err,
+                    };
+
+                    ctx.report_error(report).await;
                 }
             }
+            Some(Some(Some(false))) => {
+                let _ = msg.error(&ctx, content).await;
+            }
+            Some(Some(None) | None) => {}
+            None => {
+                let _ = msg.error(&ctx, content).await;
+            }
         }
     }
 }
@@ -6,12 +6,9 @@ use std::{
 use eyre::{Context as _, Result};
 use futures::StreamExt;
 use twilight_gateway::{cluster::Events, Event};
-use twilight_model::gateway::{
-    payload::outgoing::UpdatePresence,
-    presence::{ActivityType, MinimalActivity, Status},
-};
+use twilight_model::gateway::{payload::outgoing::UpdatePresence, presence::Status};
 
-use crate::util::Authored;
+use crate::{commands::danser::post_setup_wizard, util::Authored};
 
 use self::{interaction::handle_interaction, message::handle_message};
 
@@ -25,6 +22,7 @@ enum ProcessResult {
     Success,
     NoOwner,
     NoAuthority,
+    Disabled,
 }
 
 struct EventLocation<'a> {
@@ -88,29 +86,24 @@ async fn handle_event(ctx: Arc<Context>, event: Event, shard_id: u64) -> Result<
             warn!("Gateway invalidated session for shard {shard_id}")
         }
         Event::GatewayReconnect => {
-            info!("Gateway requested shard {shard_id} to reconnect")
+            info!("Gateway requested shard {shard_id} to reconnect");
+            evict_stale_guild_settings(&ctx)?;
         }
-        Event::GuildCreate(_) | Event::GuildDelete(_) => {
-            let stats = ctx.cache.stats();
-            let count = stats.guilds() + stats.unavailable_guilds();
-
-            let activity = MinimalActivity {
-                kind: ActivityType::Watching,
-                name: format!("in {count} servers"),
-                url: None,
-            };
-
-            let req = UpdatePresence::new(vec![activity.into()], false, None, Status::Online)?;
-
-            ctx.cluster
-                .command(shard_id, &req)
-                .await
-                .context("failed to update activity")?;
+        Event::GuildCreate(e) => {
+            update_activity(&ctx, shard_id).await?;
+
+            if let Err(err) = post_setup_wizard(&ctx, &e.0).await {
+                warn!("{:?}", err.wrap_err("failed to post setup wizard"));
+            }
         }
+        Event::GuildDelete(_) => update_activity(&ctx, shard_id).await?,
         Event::InteractionCreate(e) => handle_interaction(ctx, e.0).await,
         Event::MessageCreate(msg) => handle_message(ctx, msg.0).await,
         Event::Ready(_) => info!("Shard {shard_id} is ready"),
-        Event::Resumed => info!("Shard {shard_id} is resumed"),
+        Event::Resumed => {
+            info!("Shard {shard_id} is resumed");
+            evict_stale_guild_settings(&ctx)?;
+        }
         Event::ShardConnected(_) => info!("Shard {shard_id} is connected"),
         Event::ShardConnecting(_) => info!("Shard {shard_id} is connecting..."),
         Event::ShardDisconnected(_) => info!("Shard {shard_id} is disconnected"),
@@ -122,3 +115,26 @@ async fn handle_event(ctx: Arc<Context>, event: Event, shard_id: u64) -> Result<
 
     Ok(())
 }
+
+/// Guilds may have been left while the shard was disconnected
+fn evict_stale_guild_settings(ctx: &Context) -> Result<()> {
+    let evicted = ctx
+        .evict_stale_guild_settings()
+        .context("failed to evict stale guild settings")?;
+
+    for guild in evicted {
+        info!("Evicted settings of guild {guild} which the bot is no longer in");
+    }
+
+    Ok(())
+}
+
+async fn update_activity(ctx: &Context, shard_id: u64) -> Result<()> {
+    let activity = ctx.activity();
+    let req = UpdatePresence::new(vec![activity.into()], false, None, Status::Online)?;
+
+    ctx.cluster
+        .command(shard_id, &req)
+        .await
+        .context("failed to update activity")
+}
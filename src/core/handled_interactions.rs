@@ -0,0 +1,90 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use twilight_model::id::{marker::InteractionMarker, Id};
+
+/// How long an interaction id is remembered; interaction tokens
+/// are only valid for 15 minutes so redeliveries can't come later
+const TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Upper bound of remembered ids so that a burst of interactions can't grow the set indefinitely
+const MAX_ENTRIES: usize = 1024;
+
+/// Ids of recently handled interactions so that interactions
+/// which Discord delivers more than once are only handled once.
+#[derive(Default)]
+pub struct HandledInteractions {
+    /// Ids in the order in which they were handled
+    ids: Mutex<VecDeque<(Id<InteractionMarker>, Instant)>>,
+}
+
+impl HandledInteractions {
+    /// Remember the interaction and return whether it's the first time it was seen
+    pub fn first_delivery(&self, id: Id<InteractionMarker>) -> bool {
+        self.first_delivery_at(id, Instant::now())
+    }
+
+    fn first_delivery_at(&self, id: Id<InteractionMarker>, now: Instant) -> bool {
+        let mut ids = self.ids.lock().unwrap();
+
+        while let Some(&(_, handled_at)) = ids.front() {
+            if now.saturating_duration_since(handled_at) < TTL && ids.len() < MAX_ENTRIES {
+                break;
+            }
+
+            ids.pop_front();
+        }
+
+        if ids.iter().any(|&(handled, _)| handled == id) {
+            return false;
+        }
+
+        ids.push_back((id, now));
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_duplicate_delivery() {
+        let handled = HandledInteractions::default();
+        let id = Id::new(1);
+
+        assert!(handled.first_delivery(id));
+        assert!(!handled.first_delivery(id));
+        assert!(handled.first_delivery(Id::new(2)));
+    }
+
+    #[test]
+    fn forgets_after_ttl() {
+        let handled = HandledInteractions::default();
+        let id = Id::new(1);
+        let now = Instant::now();
+
+        assert!(handled.first_delivery_at(id, now));
+        assert!(!handled.first_delivery_at(id, now + TTL / 2));
+        assert!(handled.first_delivery_at(id, now + TTL));
+    }
+
+    #[test]
+    fn bounded_size() {
+        let handled = HandledInteractions::default();
+        let now = Instant::now();
+
+        for id in 1..=MAX_ENTRIES as u64 + 10 {
+            assert!(handled.first_delivery_at(Id::new(id), now));
+        }
+
+        assert_eq!(handled.ids.lock().unwrap().len(), MAX_ENTRIES);
+
+        // The oldest ids made room for the newer ones
+        assert!(handled.first_delivery_at(Id::new(1), now));
+    }
+}
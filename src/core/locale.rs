@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+/// Language in which the bot responds within a server
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, CommandOption, CreateOption, Deserialize, Serialize,
+)]
+pub enum Locale {
+    #[default]
+    #[option(name = "English", value = "en")]
+    #[serde(rename = "en")]
+    English,
+    #[option(name = "Deutsch", value = "de")]
+    #[serde(rename = "de")]
+    German,
+}
+
+/// Keys of all localized user-facing messages
+#[derive(Copy, Clone, Debug)]
+pub enum Msg {
+    CommandDisabled,
+    GuildQueueFull,
+    JobId,
+    JobNotFound,
+    JobPosition,
+    JobStart,
+    LazerMods,
+    LocaleUpdated,
+    LowDiskSpace,
+    MapHashMismatch,
+    MissingOutputChannel,
+    NotInputChannel,
+    NowRendering,
+    QueueEmpty,
+    QueueFull,
+    QueueFullWait,
+    QueueProgress,
+    QueueTitle,
+    QueueUpcoming,
+    RenderAborted,
+    RenderCooldown,
+    RenderFailed,
+    RenderFinished,
+    ReplayPushed,
+    ReuploadCleanedUp,
+    ReuploadDone,
+    ReuploadNotFound,
+    ReuploadNotOwn,
+    StatusDownloading,
+    StatusEncoding,
+    StatusRendering,
+    StatusUploading,
+}
+
+impl Locale {
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+        }
+    }
+
+    /// Returns the message in this language
+    pub fn get(self, msg: Msg) -> &'static str {
+        match self {
+            Locale::English => english(msg),
+            Locale::German => german(msg),
+        }
+    }
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::CommandDisabled => "That command is disabled in this server",
+        Msg::GuildQueueFull => {
+            "This server's queue is full, please try again once some of its replays are done."
+        }
+        Msg::JobId => "Job id",
+        Msg::JobNotFound => {
+            "There is no such job in the queue, it might have been completed already"
+        }
+        Msg::JobPosition => "Position",
+        Msg::JobStart => "Estimated start",
+        Msg::LazerMods => "Lazer mods that danser can't render and will be left out",
+        Msg::LocaleUpdated => "Bot messages in this server will now be in english",
+        Msg::LowDiskSpace => {
+            "The bot is running low on disk space so no new renders are accepted.\n\
+            Please try again later."
+        }
+        Msg::MapHashMismatch => {
+            "The given map does not match the one the replay was set on, \
+            so the render might look off"
+        }
+        Msg::MissingOutputChannel => {
+            "Looks like this server has not setup their output channel yet.\n\
+            Be sure to use `/setup` first."
+        }
+        Msg::NotInputChannel => {
+            "This channel is not setup as input channel.\n\
+            Check out `/setup` for more info."
+        }
+        Msg::NowRendering => "Now rendering",
+        Msg::QueueEmpty => "The queue is empty",
+        Msg::QueueFull => "The queue is full, please try again later. Replays in the queue",
+        Msg::QueueFullWait => "The next slot is expected to free up",
+        Msg::QueueProgress => "Progress",
+        Msg::QueueTitle => "Current queue",
+        Msg::QueueUpcoming => "Upcoming",
+        Msg::RenderAborted => "Aborted rendering",
+        Msg::RenderCooldown => "You are submitting renders too quickly, you can submit again in",
+        Msg::RenderFailed => "Failed rendering",
+        Msg::RenderFinished => "Finished rendering",
+        Msg::ReplayPushed => "Replay has been pushed to the queue!",
+        Msg::ReuploadCleanedUp => {
+            "The video of that job was already deleted, it has to be rendered again"
+        }
+        Msg::ReuploadDone => "Here's your video again:",
+        Msg::ReuploadNotFound => "There is no recently completed job with that id",
+        Msg::ReuploadNotOwn => {
+            "Only the user who requested the render or the one it was made for \
+            can upload it again"
+        }
+        Msg::StatusDownloading => "Downloading",
+        Msg::StatusEncoding => "Encoding",
+        Msg::StatusRendering => "Rendering",
+        Msg::StatusUploading => "Uploading",
+    }
+}
+
+fn german(msg: Msg) -> &'static str {
+    match msg {
+        Msg::CommandDisabled => "Dieser Befehl ist in diesem Server deaktiviert",
+        Msg::GuildQueueFull => {
+            "Die Warteschlange dieses Servers ist voll, bitte versuche es erneut, sobald einige \
+            seiner Replays fertig sind."
+        }
+        Msg::JobId => "Auftragsnummer",
+        Msg::JobNotFound => {
+            "Dieser Auftrag ist nicht in der Warteschlange, eventuell wurde er bereits abgeschlossen"
+        }
+        Msg::JobPosition => "Position",
+        Msg::JobStart => "Voraussichtlicher Start",
+        Msg::LazerMods => "Lazer-Mods, die danser nicht rendern kann und daher weglässt",
+        Msg::LocaleUpdated => "Bot-Nachrichten in diesem Server sind jetzt auf Deutsch",
+        Msg::LowDiskSpace => {
+            "Der Bot hat kaum noch freien Speicherplatz, daher werden keine neuen Renders angenommen.\n\
+            Bitte versuche es später erneut."
+        }
+        Msg::MapHashMismatch => {
+            "Die angegebene Map stimmt nicht mit der des Replays überein, \
+            daher könnte der Render seltsam aussehen"
+        }
+        Msg::MissingOutputChannel => {
+            "Dieser Server hat noch keinen Ausgabekanal festgelegt.\n\
+            Nutze zuerst `/setup`."
+        }
+        Msg::NotInputChannel => {
+            "Dieser Kanal ist nicht als Eingabekanal eingerichtet.\n\
+            Mehr Infos unter `/setup`."
+        }
+        Msg::NowRendering => "Jetzt wird gerendert",
+        Msg::QueueEmpty => "Die Warteschlange ist leer",
+        Msg::QueueFull => {
+            "Die Warteschlange ist voll, bitte versuche es später erneut. Replays in der Warteschlange"
+        }
+        Msg::QueueFullWait => "Der nächste Platz wird voraussichtlich frei",
+        Msg::QueueProgress => "Fortschritt",
+        Msg::QueueTitle => "Aktuelle Warteschlange",
+        Msg::QueueUpcoming => "Als Nächstes",
+        Msg::RenderAborted => "Rendern abgebrochen",
+        Msg::RenderCooldown => "Du reichst zu schnell Renders ein, du kannst wieder einreichen in",
+        Msg::RenderFailed => "Rendern fehlgeschlagen",
+        Msg::RenderFinished => "Fertig gerendert",
+        Msg::ReplayPushed => "Das Replay wurde zur Warteschlange hinzugefügt!",
+        Msg::ReuploadCleanedUp => {
+            "Das Video dieses Auftrags wurde bereits gelöscht, es muss erneut gerendert werden"
+        }
+        Msg::ReuploadDone => "Hier ist dein Video nochmal:",
+        Msg::ReuploadNotFound => "Es gibt keinen kürzlich abgeschlossenen Auftrag mit dieser ID",
+        Msg::ReuploadNotOwn => {
+            "Nur wer den Render angefordert hat oder für wen er gemacht wurde, \
+            kann ihn erneut hochladen"
+        }
+        Msg::StatusDownloading => "Herunterladen",
+        Msg::StatusEncoding => "Kodieren",
+        Msg::StatusRendering => "Rendern",
+        Msg::StatusUploading => "Hochladen",
+    }
+}
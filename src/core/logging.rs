@@ -13,7 +13,7 @@ use tracing_subscriber::{
     fmt::{
         format::Writer,
         time::{FormatTime, UtcTime},
-        FmtContext, FormatEvent, FormatFields, Layer,
+        FmtContext, FormatEvent, FormatFields, FormattedFields, Layer,
     },
     layer::SubscriberExt,
     registry::LookupSpan,
@@ -122,6 +122,23 @@ where
             metadata.line().unwrap_or(0),
         )?;
 
+        // Prefix the event with all of its spans and their fields
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                write!(writer, "{}", span.name())?;
+
+                let extensions = span.extensions();
+
+                if let Some(fields) = extensions.get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, "{{{fields}}}")?;
+                    }
+                }
+
+                write!(writer, ": ")?;
+            }
+        }
+
         ctx.field_format().format_fields(writer.by_ref(), event)?;
 
         writeln!(writer)
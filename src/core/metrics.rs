@@ -0,0 +1,78 @@
+use std::{convert::Infallible, fmt::Write, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use prometheus::{Encoder, TextEncoder};
+
+use crate::core::Context;
+
+/// Serve the prometheus metrics on `0.0.0.0:{port}/metrics`
+/// and the state of external sites on `0.0.0.0:{port}/health`
+pub fn serve(ctx: Arc<Context>, port: u16) {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+    let make_svc = make_service_fn(move |_| {
+        let ctx = Arc::clone(&ctx);
+
+        async move {
+            let svc = service_fn(move |req| handle_request(Arc::clone(&ctx), req));
+
+            Ok::<_, Infallible>(svc)
+        }
+    });
+
+    tokio::spawn(async move {
+        info!("Serving metrics on {addr}");
+
+        if let Err(err) = Server::bind(&addr).serve(make_svc).await {
+            error!("metrics server failed: {err}");
+        }
+    });
+}
+
+async fn handle_request(
+    ctx: Arc<Context>,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") => {
+            let queue_len = ctx.replay_queue.queue.lock().await.len();
+            ctx.stats.queue_depth.set(queue_len as i64);
+
+            let encoder = TextEncoder::new();
+            let mut buf = Vec::new();
+
+            match encoder.encode(&ctx.stats.registry.gather(), &mut buf) {
+                Ok(_) => Response::builder()
+                    .header(CONTENT_TYPE, encoder.format_type())
+                    .body(Body::from(buf)),
+                Err(err) => {
+                    warn!("failed to encode metrics: {err}");
+
+                    Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(Body::empty())
+                }
+            }
+        }
+        (&Method::GET, "/health") => {
+            let mut body = String::new();
+
+            for (site, state) in ctx.client().breaker_states() {
+                let _ = writeln!(body, "{site}: {state}");
+            }
+
+            Response::builder()
+                .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(Body::from(body))
+        }
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+
+    Ok(response.expect("failed to build metrics response"))
+}
@@ -1,9 +1,19 @@
 pub use self::{
+    binaries::BinaryVersions,
     cache::Cache,
     config::BotConfig,
-    context::Context,
+    context::{Context, PreparedSettings},
+    error_reports::{ErrorReport, ErrorReports},
     events::event_loop,
-    replay_queue::{ReplayData, ReplayQueue, ReplayStatus, TimePoints},
+    handled_interactions::HandledInteractions,
+    render_cooldown::RenderCooldowns,
+    render_history::{RenderHistory, RenderRecord},
+    render_outputs::{RenderOutput, RenderOutputs},
+    replay_queue::{
+        ComparedReplay, HighlightMode, LocalMap, ParseWarning, PitchMode, PlaybackSpeed,
+        QualityPreset, RenderFor, RenderOptions, ReplayData, ReplayQueue, ReplaySlim, ReplayStatus,
+        Resolution, TimePoints, VideoEncoder, VisualMods,
+    },
 };
 
 mod cache;
@@ -12,8 +22,17 @@ mod config;
 mod context;
 mod events;
 
+pub mod binaries;
 pub mod commands;
+pub mod digest;
+pub mod error_reports;
+pub mod handled_interactions;
+pub mod locale;
 pub mod logging;
+pub mod metrics;
+pub mod render_cooldown;
+pub mod render_history;
+pub mod render_outputs;
 pub mod replay_queue;
 pub mod settings;
 pub mod stats;
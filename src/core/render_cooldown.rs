@@ -0,0 +1,49 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{core::BotConfig, util::hasher::IntBuildHasher};
+
+/// Keeps track of when users last submitted a render so that
+/// a single user can't fill up the queue on their own.
+#[derive(Default)]
+pub struct RenderCooldowns {
+    last_submit: Mutex<HashMap<Id<UserMarker>, Instant, IntBuildHasher>>,
+}
+
+impl RenderCooldowns {
+    /// Returns the time the user has to wait until they can submit again.
+    ///
+    /// Exemptions are handled by [`check_render_cooldown`].
+    ///
+    /// [`check_render_cooldown`]: crate::core::commands::checks::check_render_cooldown
+    pub fn remaining(&self, user: Id<UserMarker>) -> Option<Duration> {
+        let cooldown = Duration::from_secs(BotConfig::get().render_cooldown);
+
+        self.last_submit
+            .lock()
+            .unwrap()
+            .get(&user)
+            .and_then(|last| cooldown.checked_sub(last.elapsed()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Forget when the user last submitted; returns whether there was an entry
+    pub fn remove(&self, user: Id<UserMarker>) -> bool {
+        self.last_submit.lock().unwrap().remove(&user).is_some()
+    }
+
+    /// Start the cooldown for the user's next submission
+    pub fn start(&self, user: Id<UserMarker>) {
+        let cooldown = Duration::from_secs(BotConfig::get().render_cooldown);
+        let mut last_submit = self.last_submit.lock().unwrap();
+
+        // Entries whose cooldown is over are no longer needed
+        last_submit.retain(|_, last| last.elapsed() < cooldown);
+        last_submit.insert(user, Instant::now());
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{core::BotConfig, util::hasher::IntBuildHasher};
+
+type Records = HashMap<Id<GuildMarker>, VecDeque<RenderRecord>, IntBuildHasher>;
+
+/// Recently completed renders of each server
+pub struct RenderHistory {
+    records: Mutex<Records>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RenderRecord {
+    pub user: Id<UserMarker>,
+    /// Title of the map as shown by danser
+    pub map: String,
+    pub mapset_id: Option<u32>,
+    pub link: String,
+    /// Unix timestamp in seconds
+    pub completed_at: u64,
+}
+
+impl RenderRecord {
+    pub fn new(user: Id<UserMarker>, map: String, mapset_id: Option<u32>, link: String) -> Self {
+        Self {
+            user,
+            map,
+            mapset_id,
+            link,
+            completed_at: unix_now(),
+        }
+    }
+}
+
+impl RenderHistory {
+    pub fn load() -> Result<Self> {
+        let path = BotConfig::get().paths.render_history();
+
+        let records = if path.exists() {
+            let bytes = fs::read(&path).context("failed to read render history file")?;
+
+            serde_json::from_slice(&bytes).context("failed to deserialize render history file")?
+        } else {
+            Records::default()
+        };
+
+        Ok(Self {
+            records: Mutex::new(records),
+        })
+    }
+
+    /// Add a completed render and drop those that are past the retention period
+    pub fn push(&self, guild: Id<GuildMarker>, record: RenderRecord) -> Result<()> {
+        let bytes = {
+            let mut records = self.records.lock().unwrap();
+            records.entry(guild).or_default().push_back(record);
+            prune(&mut records);
+
+            serde_json::to_vec(&*records).context("failed to serialize render history")?
+        };
+
+        let path = BotConfig::get().paths.render_history();
+
+        fs::write(path, bytes).context("failed to write render history file")
+    }
+
+    /// Forget all renders of the user and return how many there were
+    pub fn remove_user(&self, user: Id<UserMarker>) -> Result<usize> {
+        let (removed, bytes) = {
+            let mut records = self.records.lock().unwrap();
+            let before: usize = records.values().map(VecDeque::len).sum();
+
+            records.retain(|_, records| {
+                records.retain(|record| record.user != user);
+
+                !records.is_empty()
+            });
+
+            let after: usize = records.values().map(VecDeque::len).sum();
+
+            if before == after {
+                return Ok(0);
+            }
+
+            let bytes =
+                serde_json::to_vec(&*records).context("failed to serialize render history")?;
+
+            (before - after, bytes)
+        };
+
+        let path = BotConfig::get().paths.render_history();
+        fs::write(path, bytes).context("failed to write render history file")?;
+
+        Ok(removed)
+    }
+
+    /// The server's renders within the retention period, most recent first
+    pub fn recent(&self, guild: Id<GuildMarker>) -> Vec<RenderRecord> {
+        let cutoff = retention_cutoff();
+
+        self.records
+            .lock()
+            .unwrap()
+            .get(&guild)
+            .map(|records| {
+                records
+                    .iter()
+                    .rev()
+                    .take_while(|record| record.completed_at >= cutoff)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn prune(records: &mut Records) {
+    let cutoff = retention_cutoff();
+
+    records.retain(|_, records| {
+        while records
+            .front()
+            .map_or(false, |record| record.completed_at < cutoff)
+        {
+            records.pop_front();
+        }
+
+        !records.is_empty()
+    });
+}
+
+fn retention_cutoff() -> u64 {
+    unix_now().saturating_sub(BotConfig::get().render_history_retention)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
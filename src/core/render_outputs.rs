@@ -0,0 +1,138 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{core::BotConfig, util::hasher::IntBuildHasher};
+
+/// Rendered videos of recently completed jobs so that
+/// they can be uploaded again without re-rendering.
+#[derive(Default)]
+pub struct RenderOutputs {
+    outputs: Mutex<HashMap<u32, RenderOutput, IntBuildHasher>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderOutput {
+    /// The user who submitted the replay
+    pub user: Id<UserMarker>,
+    /// The user the render was made for if that's someone else
+    pub render_for: Option<Id<UserMarker>>,
+    pub path: PathBuf,
+    /// Title with which the video was uploaded
+    pub title: String,
+    /// Replay files of the render that are kept as long as the video
+    pub replays: Vec<PathBuf>,
+    completed_at: Instant,
+}
+
+impl RenderOutput {
+    pub fn new(
+        user: Id<UserMarker>,
+        render_for: Option<Id<UserMarker>>,
+        path: PathBuf,
+        title: String,
+        replays: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            user,
+            render_for,
+            path,
+            title,
+            replays,
+            completed_at: Instant::now(),
+        }
+    }
+
+    /// Whether the user submitted the render or it was made for them
+    pub fn is_owner(&self, user: Id<UserMarker>) -> bool {
+        self.user == user || self.render_for == Some(user)
+    }
+
+    /// Delete the video and the replays of the render
+    pub fn remove_files(&self) {
+        for path in Some(&self.path).into_iter().chain(self.replays.iter()) {
+            if let Err(err) = fs::remove_file(path) {
+                if err.kind() != ErrorKind::NotFound {
+                    warn!("failed to remove render file {path:?}: {err}");
+                }
+            }
+        }
+    }
+}
+
+impl RenderOutputs {
+    /// Keep track of the job's video
+    pub fn insert(&self, id: u32, output: RenderOutput) {
+        self.outputs.lock().unwrap().insert(id, output);
+    }
+
+    /// Forget the videos past the retention period and return them so their files can be deleted
+    pub fn remove_expired(&self) -> Vec<RenderOutput> {
+        let retention = retention();
+        let mut outputs = self.outputs.lock().unwrap();
+
+        let expired: Vec<_> = outputs
+            .iter()
+            .filter(|(_, output)| output.completed_at.elapsed() >= retention)
+            .map(|(&id, _)| id)
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|id| outputs.remove(&id))
+            .collect()
+    }
+
+    /// The job's video if it completed within the retention period
+    pub fn get(&self, id: u32) -> Option<RenderOutput> {
+        let retention = retention();
+
+        self.outputs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .filter(|output| output.completed_at.elapsed() < retention)
+            .cloned()
+    }
+
+    /// Forget all videos of the user and return them so their files can be deleted
+    pub fn remove_user(&self, user: Id<UserMarker>) -> Vec<RenderOutput> {
+        let mut outputs = self.outputs.lock().unwrap();
+
+        let ids: Vec<_> = outputs
+            .iter()
+            .filter(|(_, output)| output.is_owner(user))
+            .map(|(&id, _)| id)
+            .collect();
+
+        ids.into_iter()
+            .filter_map(|id| outputs.remove(&id))
+            .collect()
+    }
+
+    /// File stems of all videos within the retention period
+    pub fn file_stems(&self) -> Vec<OsString> {
+        let retention = retention();
+
+        self.outputs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|output| output.completed_at.elapsed() < retention)
+            .flat_map(|output| Some(&output.path).into_iter().chain(output.replays.iter()))
+            .filter_map(|path| path.file_stem().map(ToOwned::to_owned))
+            .collect()
+    }
+}
+
+fn retention() -> Duration {
+    Duration::from_secs(BotConfig::get().output_retention)
+}
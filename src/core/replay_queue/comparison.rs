@@ -0,0 +1,120 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context as _, Result};
+
+use crate::core::{settings::DanserSettings, BotConfig};
+
+use super::ReplaySlim;
+
+/// danser's knockout mode in which neither player is ever knocked out
+const ONE_VS_ONE: u8 = 3;
+
+/// Second replay of a render that compares two plays on the same map
+#[derive(Clone)]
+pub struct ComparedReplay {
+    pub path: PathBuf,
+    pub replay: ReplaySlim,
+}
+
+/// Label for both players of a comparison, numbered in the order they were submitted
+pub fn comparison_label(first: &ReplaySlim, second: &ReplaySlim) -> String {
+    let player = |replay: &ReplaySlim| {
+        replay
+            .player_name
+            .clone()
+            .unwrap_or_else(|| "<unknown player>".to_owned())
+    };
+
+    format!("1. {} vs 2. {}", player(first), player(second))
+}
+
+/// Folder that contains both replays of a comparison so that danser's
+/// knockout mode plays them alongside each other.
+///
+/// The folder is deleted on drop.
+pub struct KnockoutDir {
+    dir: PathBuf,
+}
+
+impl KnockoutDir {
+    pub fn create(id: u32, replays: [&Path; 2]) -> Result<Self> {
+        let mut dir = BotConfig::get().paths.downloads();
+        dir.push(format!("knockout-{id}"));
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("failed to remove leftover knockout dir {dir:?}"))?;
+        }
+
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create directory {dir:?}"))?;
+
+        let knockout_dir = Self { dir };
+
+        for (i, replay) in replays.into_iter().enumerate() {
+            let target = knockout_dir.dir.join(format!("{}.osr", i + 1));
+
+            fs::copy(replay, &target)
+                .with_context(|| format!("failed to copy {replay:?} to {target:?}"))?;
+        }
+
+        Ok(knockout_dir)
+    }
+
+    /// Let danser pick up the replays of this folder and keep both players until the end
+    pub fn apply(&self, settings: &mut DanserSettings) {
+        settings.general.osu_replays_dir = self.dir.to_string_lossy().into_owned();
+
+        let knockout = &mut settings.knockout;
+        knockout.mode = ONE_VS_ONE;
+        knockout.add_danser = false;
+        knockout.min_players = 2;
+        knockout.max_players = 2;
+        knockout.revive_players_at_end = true;
+    }
+}
+
+impl Drop for KnockoutDir {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_dir_all(&self.dir) {
+            warn!("failed to remove knockout dir {:?}: {err}", self.dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(player_name: Option<&str>) -> ReplaySlim {
+        ReplaySlim {
+            beatmap_hash: None,
+            count_300: 0,
+            count_100: 0,
+            count_50: 0,
+            count_geki: 0,
+            count_katsu: 0,
+            count_miss: 0,
+            lazer: false,
+            max_combo: 0,
+            mods: 0,
+            player_name: player_name.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn label_numbers_players() {
+        let label = comparison_label(&replay(Some("mezo")), &replay(Some("mezo")));
+
+        assert_eq!(label, "1. mezo vs 2. mezo");
+    }
+
+    #[test]
+    fn label_handles_missing_names() {
+        let label = comparison_label(&replay(Some("mezo")), &replay(None));
+
+        assert_eq!(label, "1. mezo vs 2. <unknown player>");
+    }
+}
@@ -1,24 +1,95 @@
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::RangeInclusive,
+    path::PathBuf,
+    time::Instant,
+};
 
 use osu_db::Replay;
-use twilight_model::id::{
-    marker::{ChannelMarker, UserMarker},
-    Id,
+use rosu_v2::prelude::GameMods;
+use serde::{Deserialize, Serialize};
+use twilight_model::{
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+    user::User,
 };
 
-use crate::util::CowUtils;
+use crate::{core::settings::DanserSettings, util::CowUtils};
+
+use super::{
+    skin_ini::SkinIniOverrides, ComparedReplay, PlaybackSpeed, QualityPreset, Resolution,
+    StoredMap, VisualMods, LAZER_VERSION,
+};
 
 #[derive(Clone)]
 pub struct ReplayData {
+    /// Identifies the entry while it's in the queue, see [`ReplayQueue::next_id`]
+    ///
+    /// [`ReplayQueue::next_id`]: super::ReplayQueue::next_id
+    pub id: u32,
+    /// Second replay on the same map that's rendered alongside this one
+    pub comparison: Option<ComparedReplay>,
+    /// Server in which the replay was queued, `None` for DMs
+    pub guild: Option<Id<GuildMarker>>,
     pub input_channel: Id<ChannelMarker>,
+    /// Token of the interaction that queued the replay, if any
+    pub interaction_token: Option<String>,
+    /// The map if it was uploaded alongside the replay
+    pub local_map: Option<StoredMap>,
     pub output_channel: Id<ChannelMarker>,
+    pub options: RenderOptions,
+    /// The replay is stored when it's queued since attachment urls expire
     pub path: PathBuf,
+    pub queued_at: Instant,
+    /// The user on whose behalf the replay was submitted, see [`RenderFor`]
+    pub render_for: Option<RenderFor>,
     pub replay: ReplaySlim,
+    /// Overrides for the skin.ini of the render's skin
+    pub skin_ini: Option<SkinIniOverrides>,
     pub time_points: TimePoints,
+    /// Note of the user to tell their renders apart, see [`ReplayData::sanitize_title`]
+    pub title: Option<String>,
+    /// The user who submitted the replay
     pub user: Id<UserMarker>,
 }
 
 impl ReplayData {
+    /// Maximum amount of characters of a title
+    pub const MAX_TITLE_LEN: usize = 50;
+
+    /// Strip mentions and markdown from a user-supplied title and put it
+    /// on a single line. Returns `None` if nothing remains.
+    ///
+    /// Titles longer than [`ReplayData::MAX_TITLE_LEN`] are cut off.
+    pub fn sanitize_title(title: &str) -> Option<String> {
+        const MARKDOWN: &[char] = &['*', '_', '~', '`', '|', '\\', '<', '>', '@', '#', '[', ']'];
+
+        let sanitized: String = strip_mentions(title)
+            .split_whitespace()
+            .map(|word| word.replace(MARKDOWN, ""))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(Self::MAX_TITLE_LEN)
+            .collect();
+
+        let sanitized = sanitized.trim_end();
+
+        (!sanitized.is_empty()).then(|| sanitized.to_owned())
+    }
+
+    /// The user's title or the name of the replay file if there is none
+    pub fn display_title(&self) -> Cow<'_, str> {
+        match self.title {
+            Some(ref title) => Cow::Borrowed(title),
+            None => self.replay_name(),
+        }
+    }
+
     pub fn replay_name(&self) -> Cow<'_, str> {
         let name = self
             .path
@@ -48,7 +119,69 @@ impl ReplayData {
     }
 }
 
-#[derive(Copy, Clone)]
+/// Remove user, role, and channel mentions
+fn strip_mentions(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let mention_len = rest
+            .find('>')
+            .filter(|_| rest[1..].starts_with(['@', '#']))
+            .map(|end| end + 1);
+
+        match mention_len {
+            Some(len) => rest = &rest[len..],
+            None => {
+                stripped.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    stripped.push_str(rest);
+
+    stripped
+}
+
+/// The user a render is meant for if it was submitted by someone else, e.g. a coach.
+///
+/// Attribution is split as follows:
+/// - The submitter owns the job: their settings, cooldown, and queue limits
+///   apply and failures are reported for them.
+/// - The result mentions this user and shows their name and avatar.
+/// - If the result can't be posted, the link is sent to this user's DMs.
+/// - The render is listed under this user in `/recent`.
+/// - Both the submitter and this user can `/reupload` the video.
+#[derive(Clone, Debug)]
+pub struct RenderFor {
+    pub user: Id<UserMarker>,
+    pub name: String,
+    pub avatar_url: String,
+}
+
+impl RenderFor {
+    pub fn new(user: &User) -> Self {
+        let avatar_url = match user.avatar {
+            Some(ref hash) => format!("https://cdn.discordapp.com/avatars/{}/{hash}.png", user.id),
+            None => format!(
+                "https://cdn.discordapp.com/embed/avatars/{}.png",
+                user.discriminator % 5
+            ),
+        };
+
+        Self {
+            user: user.id,
+            name: user.name.clone(),
+            avatar_url,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct TimePoints {
     pub start: u32,
     pub end: u32,
@@ -65,18 +198,165 @@ impl TimePoints {
             _ => Err("A value you supplied is not a number!"),
         }
     }
+
+    /// Seconds it takes to play back the selected part of a map that
+    /// is `map_seconds` long when playing at the given clock rate.
+    pub fn playback_duration(self, map_seconds: u32, clock_rate: f64) -> u32 {
+        let end = match self.end {
+            0 => map_seconds,
+            end => end.min(map_seconds),
+        };
+
+        (end.saturating_sub(self.start) as f64 / clock_rate).round() as u32
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Settings that override the user's danser settings for a single render
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    pub background_dim: Option<u8>,
+    pub cursor_scale: Option<f64>,
+    pub skin: Option<String>,
+    pub master_volume: Option<u8>,
+    pub music_volume: Option<u8>,
+    pub hitsound_volume: Option<u8>,
+    pub no_audio: bool,
+    pub quality: Option<QualityPreset>,
+    pub resolution: Option<Resolution>,
+    pub storyboard: Option<bool>,
+    /// Passed to danser as arguments since it's not part of the settings file
+    pub speed: Option<PlaybackSpeed>,
+    /// Applied to a copy of the replay since danser takes the mods from the replay
+    pub visual_mods: Option<VisualMods>,
+}
+
+impl RenderOptions {
+    pub const PERCENT: RangeInclusive<u8> = 0..=100;
+    pub const CURSOR_SCALE: RangeInclusive<f64> = 0.1..=2.0;
+
+    /// Returns an error message if any value is out of its bounds
+    pub fn validate(&self) -> Result<(), String> {
+        let percentages = [
+            ("background dim", self.background_dim),
+            ("master volume", self.master_volume),
+            ("music volume", self.music_volume),
+            ("hitsound volume", self.hitsound_volume),
+        ];
+
+        for (name, value) in percentages {
+            if let Some(value) = value.filter(|value| !Self::PERCENT.contains(value)) {
+                return Err(format!(
+                    "Invalid {name} `{value}`, must be between {} and {}",
+                    Self::PERCENT.start(),
+                    Self::PERCENT.end()
+                ));
+            }
+        }
+
+        if let Some(scale) = self
+            .cursor_scale
+            .filter(|scale| !Self::CURSOR_SCALE.contains(scale))
+        {
+            return Err(format!(
+                "Invalid cursor scale `{scale}`, must be between {} and {}",
+                Self::CURSOR_SCALE.start(),
+                Self::CURSOR_SCALE.end()
+            ));
+        }
+
+        if let Some(speed) = self
+            .speed
+            .filter(|speed| !PlaybackSpeed::RATE.contains(&speed.rate))
+        {
+            return Err(format!(
+                "Invalid speed `{}`, must be between {} and {}",
+                speed.rate,
+                PlaybackSpeed::RATE.start(),
+                PlaybackSpeed::RATE.end()
+            ));
+        }
+
+        if let Some(resolution) = self.resolution {
+            resolution.validate()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&self, settings: &mut DanserSettings) {
+        fn percent(value: u8) -> f64 {
+            value as f64 / 100.0
+        }
+
+        if let Some(dim) = self.background_dim {
+            settings.playfield.background.dim.normal = percent(dim);
+        }
+
+        if let Some(scale) = self.cursor_scale {
+            settings.skin.cursor.scale = scale;
+        }
+
+        if let Some(ref skin) = self.skin {
+            settings.set_skin(skin);
+        }
+
+        if let Some(volume) = self.master_volume {
+            settings.audio.general_volume = percent(volume);
+        }
+
+        if let Some(volume) = self.music_volume {
+            settings.audio.music_volume = percent(volume);
+        }
+
+        if let Some(volume) = self.hitsound_volume {
+            settings.audio.sample_volume = percent(volume);
+        }
+
+        if self.no_audio {
+            settings.audio.general_volume = 0.0;
+        }
+
+        if let Some(quality) = self.quality {
+            quality.apply(&mut settings.recording);
+        }
+
+        if let Some(resolution) = self.resolution {
+            resolution.apply(&mut settings.recording);
+        }
+
+        if let Some(storyboard) = self.storyboard {
+            settings.playfield.background.load_storyboards = storyboard;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ReplayStatus {
     Waiting,
+    /// Position within the queue, starting at 1 for the entry that's being processed
+    Queued(usize),
     Downloading,
     Rendering(u8),
     Encoding(u8),
     Uploading,
 }
 
-#[derive(Clone)]
+impl Display for ReplayStatus {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ReplayStatus::Waiting => f.write_str("Waiting"),
+            ReplayStatus::Queued(position) => write!(f, "Queued (#{position})"),
+            ReplayStatus::Downloading => f.write_str("Downloading"),
+            ReplayStatus::Rendering(progress) => write!(f, "Rendering ({progress}%)"),
+            ReplayStatus::Encoding(progress) => write!(f, "Encoding ({progress}%)"),
+            ReplayStatus::Uploading => f.write_str("Uploading"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ReplaySlim {
     pub beatmap_hash: Option<String>,
     pub count_300: u16,
@@ -85,7 +365,11 @@ pub struct ReplaySlim {
     pub count_geki: u16,
     pub count_katsu: u16,
     pub count_miss: u16,
+    /// Whether the replay was set on osu!lazer
+    #[serde(default)]
+    pub lazer: bool,
     pub max_combo: u16,
+    /// Bitflags of the mods, kept numeric so that they round-trip as is
     pub mods: u32,
     pub player_name: Option<String>,
 }
@@ -95,6 +379,19 @@ impl ReplaySlim {
         self.count_300 + self.count_100 + self.count_50 + self.count_miss
     }
 
+    /// Speed at which the replay is played back
+    pub fn clock_rate(&self) -> f64 {
+        let mods = GameMods::from_bits_truncate(self.mods);
+
+        if mods.intersects(GameMods::DoubleTime | GameMods::NightCore) {
+            1.5
+        } else if mods.contains(GameMods::HalfTime) {
+            0.75
+        } else {
+            1.0
+        }
+    }
+
     pub fn accuracy(&self) -> f32 {
         let numerator = (self.count_50 as u32 * 50
             + self.count_100 as u32 * 100
@@ -106,20 +403,237 @@ impl ReplaySlim {
     }
 }
 
+/// Minor issue of a parsed replay that does not prevent it from being rendered
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseWarning {
+    MissingPlayerName,
+    NoHits,
+    /// Bits of the mods that don't belong to any known mod
+    UnknownMods(u32),
+    /// Mods that can't be played together
+    ConflictingMods(u32),
+    Autoplay,
+}
+
+impl Display for ParseWarning {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::MissingPlayerName => f.write_str("The replay does not contain a player name"),
+            Self::NoHits => f.write_str("The replay does not contain any hits"),
+            Self::UnknownMods(bits) => write!(f, "The replay contains unknown mods `{bits:#x}`"),
+            Self::ConflictingMods(bits) => write!(
+                f,
+                "The replay contains mods that conflict with each other `+{}`",
+                GameMods::from_bits_truncate(*bits)
+            ),
+            Self::Autoplay => f.write_str("The replay was made by autoplay"),
+        }
+    }
+}
+
+/// Pairs of mods that can't be enabled at the same time
+const CONFLICTING_MODS: [(GameMods, GameMods); 3] = [
+    (GameMods::HardRock, GameMods::Easy),
+    (GameMods::DoubleTime, GameMods::HalfTime),
+    (GameMods::Relax, GameMods::Autopilot),
+];
+
+impl ReplaySlim {
+    /// Convert the replay while collecting minor issues along the way
+    pub fn with_warnings(replay: Replay) -> (Self, Vec<ParseWarning>) {
+        let replay = Self::from(replay);
+        let warnings = replay.parse_warnings();
+
+        (replay, warnings)
+    }
+
+    fn parse_warnings(&self) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+
+        if self.player_name.as_deref().map_or(true, str::is_empty) {
+            warnings.push(ParseWarning::MissingPlayerName);
+        }
+
+        if self.total_hits() == 0 {
+            warnings.push(ParseWarning::NoHits);
+        }
+
+        let unknown = self.mods & !GameMods::all().bits();
+
+        if unknown != 0 {
+            warnings.push(ParseWarning::UnknownMods(unknown));
+        }
+
+        let mods = GameMods::from_bits_truncate(self.mods);
+
+        let conflicting = CONFLICTING_MODS
+            .iter()
+            .filter(|(a, b)| mods.contains(*a) && mods.contains(*b))
+            .fold(GameMods::empty(), |conflicting, (a, b)| {
+                conflicting | *a | *b
+            });
+
+        if !conflicting.is_empty() {
+            warnings.push(ParseWarning::ConflictingMods(conflicting.bits()));
+        }
+
+        if mods.contains(GameMods::Autoplay) {
+            warnings.push(ParseWarning::Autoplay);
+        }
+
+        warnings
+    }
+}
+
 impl From<Replay> for ReplaySlim {
     #[inline]
     fn from(replay: Replay) -> Self {
         Self {
-            beatmap_hash: replay.beatmap_hash,
+            // Treated the same as a missing hash since no map can be found with it
+            beatmap_hash: replay.beatmap_hash.filter(|hash| !hash.is_empty()),
             count_300: replay.count_300,
             count_100: replay.count_100,
             count_50: replay.count_50,
             count_geki: replay.count_geki,
             count_katsu: replay.count_katsu,
             count_miss: replay.count_miss,
+            lazer: replay.version >= LAZER_VERSION,
             max_combo: replay.max_combo,
+            // Lazer replays also contain legacy bits for all mods that stable knows
             mods: replay.mods.bits(),
             player_name: replay.player_name,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rosu_v2::prelude::GameMods;
+
+    use super::{ParseWarning, ReplayData, ReplaySlim, TimePoints};
+
+    fn replay(mods: GameMods) -> ReplaySlim {
+        ReplaySlim {
+            beatmap_hash: None,
+            count_300: 0,
+            count_100: 0,
+            count_50: 0,
+            count_geki: 0,
+            count_katsu: 0,
+            count_miss: 0,
+            lazer: false,
+            max_combo: 0,
+            mods: mods.bits(),
+            player_name: None,
+        }
+    }
+
+    const FULL: TimePoints = TimePoints { start: 0, end: 0 };
+
+    #[test]
+    fn nomod_duration() {
+        let rate = replay(GameMods::Hidden | GameMods::HardRock).clock_rate();
+
+        assert_eq!(FULL.playback_duration(120, rate), 120);
+    }
+
+    #[test]
+    fn doubletime_duration() {
+        let rate = replay(GameMods::DoubleTime).clock_rate();
+        assert_eq!(FULL.playback_duration(120, rate), 80);
+
+        let rate = replay(GameMods::NightCore).clock_rate();
+        assert_eq!(FULL.playback_duration(120, rate), 80);
+    }
+
+    #[test]
+    fn halftime_duration() {
+        let rate = replay(GameMods::HalfTime).clock_rate();
+
+        assert_eq!(FULL.playback_duration(120, rate), 160);
+    }
+
+    #[test]
+    fn clipped_duration() {
+        let rate = replay(GameMods::DoubleTime).clock_rate();
+        let time_points = TimePoints { start: 30, end: 90 };
+        assert_eq!(time_points.playback_duration(120, rate), 40);
+
+        // End beyond the map's length and start after the end
+        let time_points = TimePoints {
+            start: 60,
+            end: 500,
+        };
+        assert_eq!(time_points.playback_duration(120, rate), 40);
+
+        let time_points = TimePoints { start: 200, end: 0 };
+        assert_eq!(time_points.playback_duration(120, rate), 0);
+    }
+
+    #[test]
+    fn title_strips_mentions_and_markdown() {
+        let title = ReplayData::sanitize_title("**my new PB** <@123> @everyone <#456> ~~x~~");
+
+        assert_eq!(title.as_deref(), Some("my new PB everyone x"));
+    }
+
+    #[test]
+    fn title_single_line() {
+        let title = ReplayData::sanitize_title("  first\n\n  second  ");
+
+        assert_eq!(title.as_deref(), Some("first second"));
+    }
+
+    #[test]
+    fn title_is_cut_off() {
+        let title = ReplayData::sanitize_title(&"a".repeat(100)).unwrap();
+
+        assert_eq!(title.chars().count(), ReplayData::MAX_TITLE_LEN);
+    }
+
+    #[test]
+    fn title_without_content() {
+        assert_eq!(ReplayData::sanitize_title("  ** <@123> "), None);
+    }
+
+    #[test]
+    fn replay_round_trip() {
+        let mut replay = replay(GameMods::Hidden | GameMods::DoubleTime);
+        replay.player_name = Some("mezo".to_owned());
+        replay.max_combo = 727;
+
+        let json = serde_json::to_value(&replay).unwrap();
+        assert_eq!(json["mods"], 72);
+
+        let deserialized: ReplaySlim = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, replay);
+    }
+
+    #[test]
+    fn parse_warnings() {
+        let mut replay = replay(GameMods::HardRock | GameMods::Easy | GameMods::Hidden);
+        replay.mods |= 1 << 31;
+        replay.player_name = Some(String::new());
+
+        assert_eq!(
+            replay.parse_warnings(),
+            [
+                ParseWarning::MissingPlayerName,
+                ParseWarning::NoHits,
+                ParseWarning::UnknownMods(1 << 31),
+                ParseWarning::ConflictingMods((GameMods::HardRock | GameMods::Easy).bits()),
+            ]
+        );
+    }
+
+    #[test]
+    fn time_points_round_trip() {
+        let time_points = TimePoints { start: 30, end: 90 };
+
+        let json = serde_json::to_string(&time_points).unwrap();
+        let deserialized: TimePoints = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized, time_points);
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use eyre::{Context as _, ContextCompat, Result};
+use tokio::fs;
+
+use crate::core::BotConfig;
+
+use super::{parse_replay, ReplaySlim};
+
+/// Replay that is rendered to preview skins, loaded once on startup
+pub struct DemoReplay {
+    pub bytes: Vec<u8>,
+    pub replay: ReplaySlim,
+}
+
+impl DemoReplay {
+    /// Read and validate the demo replay.
+    ///
+    /// Returns `None` if it's missing or unusable in which case skin previews are disabled.
+    pub async fn load() -> Option<Self> {
+        let path = BotConfig::get().paths.demo_replay();
+
+        if !path.exists() {
+            warn!("No demo replay at {path:?}, skin previews are disabled");
+
+            return None;
+        }
+
+        match Self::read(&path).await {
+            Ok(demo) => Some(demo),
+            Err(err) => {
+                let err = err.wrap_err("invalid demo replay, skin previews are disabled");
+                warn!("{err:?}");
+
+                None
+            }
+        }
+    }
+
+    async fn read(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)
+            .await
+            .with_context(|| format!("failed to read demo replay at {path:?}"))?;
+
+        let replay = ReplaySlim::from(parse_replay(&bytes).context("failed to parse demo replay")?);
+
+        // Without a hash danser can't find the demo's map
+        replay
+            .beatmap_hash
+            .as_ref()
+            .context("demo replay does not contain a map hash")?;
+
+        Ok(Self { bytes, replay })
+    }
+}
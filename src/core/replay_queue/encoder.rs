@@ -0,0 +1,108 @@
+use crate::core::settings::Recording;
+
+/// Messages of ffmpeg's hardware encoders if the device or its driver can't be used
+const INIT_FAILURES: [&str; 6] = [
+    "Cannot load",
+    "OpenEncodeSessionEx failed",
+    "No capable devices found",
+    "No NVENC capable devices found",
+    "MFX session",
+    "Device creation failed",
+];
+
+/// Video encoder that danser hands to ffmpeg when recording
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum VideoEncoder {
+    /// Software encoding which works on any machine
+    #[default]
+    Cpu,
+    /// Hardware encoding on NVIDIA GPUs
+    Nvenc,
+    /// Hardware encoding on Intel GPUs through Quick Sync
+    Qsv,
+}
+
+impl VideoEncoder {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "cpu" | "libx264" => Some(Self::Cpu),
+            "nvenc" | "h264_nvenc" => Some(Self::Nvenc),
+            "qsv" | "h264_qsv" => Some(Self::Qsv),
+            _ => None,
+        }
+    }
+
+    /// Name of the encoder as known to ffmpeg
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            VideoEncoder::Cpu => "libx264",
+            VideoEncoder::Nvenc => "h264_nvenc",
+            VideoEncoder::Qsv => "h264_qsv",
+        }
+    }
+
+    pub fn is_hardware(self) -> bool {
+        self != VideoEncoder::Cpu
+    }
+
+    pub fn apply(self, recording: &mut Recording) {
+        recording.encoder = self.ffmpeg_name().to_owned();
+    }
+
+    /// Whether the output of a render shows that this hardware encoder
+    /// could not be initialised, in which case no render can use it.
+    pub fn init_failed(self, stderr: &str) -> bool {
+        if !self.is_hardware() {
+            return false;
+        }
+
+        // ffmpeg prefixes messages of the encoder with its name
+        let prefix = format!("[{} @", self.ffmpeg_name());
+
+        stderr.lines().any(|line| {
+            line.contains(&prefix) && INIT_FAILURES.iter().any(|failure| line.contains(failure))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(VideoEncoder::parse("CPU"), Some(VideoEncoder::Cpu));
+        assert_eq!(VideoEncoder::parse("h264_nvenc"), Some(VideoEncoder::Nvenc));
+        assert_eq!(VideoEncoder::parse(" qsv "), Some(VideoEncoder::Qsv));
+        assert_eq!(VideoEncoder::parse("vaapi"), None);
+    }
+
+    #[test]
+    fn init_failure() {
+        let stderr = "[h264_nvenc @ 0x55d0c8a4b2c0] OpenEncodeSessionEx failed: \
+            unsupported device (2): (no details)\n\
+            Error initializing output stream 0:0";
+
+        assert!(VideoEncoder::Nvenc.init_failed(stderr));
+        assert!(!VideoEncoder::Qsv.init_failed(stderr));
+        assert!(!VideoEncoder::Cpu.init_failed(stderr));
+    }
+
+    #[test]
+    fn other_failures_keep_hardware_encoder() {
+        let stderr = "panic: runtime error: index out of range [3] with length 3\n\
+            goroutine 1 [running]:\n\
+            github.com/wieku/danser-go/app/rulesets/osu.(*OsuRuleSet).Update";
+
+        assert!(!VideoEncoder::Nvenc.init_failed(stderr));
+        assert!(!VideoEncoder::Nvenc.init_failed(""));
+    }
+
+    #[test]
+    fn apply_sets_encoder() {
+        let mut recording = Recording::default();
+        VideoEncoder::Nvenc.apply(&mut recording);
+
+        assert_eq!(recording.encoder, "h264_nvenc");
+    }
+}
@@ -0,0 +1,189 @@
+use std::iter;
+
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use super::TimePoints;
+
+/// Seconds that a clip starts before the section in which a miss happened
+const LEAD_IN: u32 = 3;
+
+/// Seconds that a clip continues after the section in which a miss happened
+const LEAD_OUT: u32 = 2;
+
+/// Life drops smaller than this are considered noise rather than misses
+const MIN_DROP: f64 = 0.02;
+
+/// Which parts of a replay are rendered instead of the whole play
+#[derive(Copy, Clone, Debug, Eq, PartialEq, CommandOption, CreateOption)]
+pub enum HighlightMode {
+    #[option(name = "Misses: a clip around each miss", value = "misses")]
+    Misses,
+    #[option(
+        name = "Biggest break: the miss that ended the longest combo",
+        value = "break"
+    )]
+    BiggestBreak,
+}
+
+impl HighlightMode {
+    /// Renders a single highlight request may queue at most
+    pub const MAX_SEGMENTS: usize = 5;
+
+    /// Seconds that all clips of a highlight request may add up to
+    pub const MAX_TOTAL_SECONDS: u32 = 90;
+
+    /// Time windows of the clips to render.
+    ///
+    /// Misses are approximated from the replay's life bar graph which only
+    /// holds a sample every few seconds. The `count_miss` biggest drops in life
+    /// are taken as misses so the clips are wide enough to contain them.
+    ///
+    /// Returns an empty list if no misses could be found.
+    pub fn windows(self, life_graph: &str, count_miss: usize) -> Vec<TimePoints> {
+        let mut misses = life_drops(life_graph);
+        misses.sort_unstable_by(|a, b| b.drop.total_cmp(&a.drop));
+        misses.truncate(count_miss);
+        misses.sort_unstable_by_key(|miss| miss.start);
+
+        let windows = match self {
+            HighlightMode::Misses => misses.iter().map(LifeDrop::window).collect(),
+            HighlightMode::BiggestBreak => {
+                // The combo before each miss lasted since the end of the previous one
+                let prev_ends = iter::once(0).chain(misses.iter().map(|miss| miss.end));
+
+                misses
+                    .iter()
+                    .zip(prev_ends)
+                    .map(|(miss, prev_end)| (miss, miss.start.saturating_sub(prev_end)))
+                    .max_by_key(|(_, combo_duration)| *combo_duration)
+                    .map(|(miss, _)| miss.window())
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        cap_windows(merge_windows(windows))
+    }
+}
+
+/// A section of the replay in which life dropped
+struct LifeDrop {
+    /// Milliseconds
+    start: u32,
+    /// Milliseconds
+    end: u32,
+    drop: f64,
+}
+
+impl LifeDrop {
+    fn window(&self) -> TimePoints {
+        TimePoints {
+            start: (self.start / 1000).saturating_sub(LEAD_IN),
+            end: (self.end + 999) / 1000 + LEAD_OUT,
+        }
+    }
+}
+
+/// Parse the life graph of the form `time|life,time|life,...`
+/// and collect all sections in which life dropped noticeably
+fn life_drops(life_graph: &str) -> Vec<LifeDrop> {
+    let samples: Vec<(u32, f64)> = life_graph
+        .split(',')
+        .filter_map(|sample| {
+            let (time, life) = sample.split_once('|')?;
+
+            Some((time.trim().parse().ok()?, life.trim().parse().ok()?))
+        })
+        .collect();
+
+    samples
+        .windows(2)
+        .filter_map(|pair| {
+            let [(start, prev), (end, curr)] = [pair[0], pair[1]];
+            let drop = prev - curr;
+
+            (drop >= MIN_DROP).then_some(LifeDrop { start, end, drop })
+        })
+        .collect()
+}
+
+/// Combine overlapping windows into one; expects the windows to be sorted
+fn merge_windows(windows: Vec<TimePoints>) -> Vec<TimePoints> {
+    let mut merged: Vec<TimePoints> = Vec::with_capacity(windows.len());
+
+    for window in windows {
+        match merged.last_mut() {
+            Some(last) if window.start <= last.end => last.end = last.end.max(window.end),
+            _ => merged.push(window),
+        }
+    }
+
+    merged
+}
+
+/// Keep the first windows within the segment and length limits
+fn cap_windows(windows: Vec<TimePoints>) -> Vec<TimePoints> {
+    let mut total = 0;
+
+    windows
+        .into_iter()
+        .take(HighlightMode::MAX_SEGMENTS)
+        .take_while(|window| {
+            total += window.end - window.start;
+
+            total <= HighlightMode::MAX_TOTAL_SECONDS
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIFE_GRAPH: &str = "0|1,2000|1,4000|0.7,6000|0.75,8000|0.8,\
+        20000|0.85,22000|0.5,24000|0.55,26000|0.54,";
+
+    #[test]
+    fn clip_around_each_miss() {
+        let windows = HighlightMode::Misses.windows(LIFE_GRAPH, 2);
+
+        let expected = [
+            TimePoints { start: 0, end: 6 },
+            TimePoints { start: 17, end: 24 },
+        ];
+
+        assert_eq!(windows, expected);
+    }
+
+    #[test]
+    fn biggest_break_after_longest_combo() {
+        let windows = HighlightMode::BiggestBreak.windows(LIFE_GRAPH, 2);
+
+        assert_eq!(windows, [TimePoints { start: 17, end: 24 }]);
+    }
+
+    #[test]
+    fn drops_beyond_miss_count_ignored() {
+        let windows = HighlightMode::Misses.windows(LIFE_GRAPH, 1);
+
+        assert_eq!(windows, [TimePoints { start: 17, end: 24 }]);
+    }
+
+    #[test]
+    fn overlapping_windows_merged() {
+        let windows = HighlightMode::Misses.windows("0|1,2000|0.6,4000|0.2", 2);
+
+        assert_eq!(windows, [TimePoints { start: 0, end: 6 }]);
+    }
+
+    #[test]
+    fn windows_capped() {
+        let life_graph: String = (0..20)
+            .map(|i| format!("{}|1,{}|0.5,", i * 20_000, i * 20_000 + 2000))
+            .collect();
+
+        let windows = HighlightMode::Misses.windows(&life_graph, 20);
+
+        assert_eq!(windows.len(), HighlightMode::MAX_SEGMENTS);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::core::{settings::DanserSettings, BotConfig};
+
+/// Log file of a single render if enabled through `RENDER_LOGS`.
+///
+/// Failing to write the log never fails the render, it's only warned about.
+pub struct JobLog {
+    file: Mutex<Option<File>>,
+    start: Instant,
+}
+
+impl JobLog {
+    pub fn create(id: u32) -> Self {
+        let file = BotConfig::get()
+            .render_logs
+            .then(|| Self::path(id))
+            .and_then(|path| match File::create(&path) {
+                Ok(file) => Some(file),
+                Err(err) => {
+                    warn!("failed to create render log at {path:?}: {err}");
+
+                    None
+                }
+            });
+
+        Self {
+            file: Mutex::new(file),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn path(id: u32) -> PathBuf {
+        let mut path = BotConfig::get().paths.render_logs();
+        path.push(format!("{id}.log"));
+
+        path
+    }
+
+    /// Append a line prefixed by the time since the render started
+    pub fn line(&self, line: impl Display) {
+        let elapsed = self.start.elapsed().as_secs_f32();
+
+        self.write(|file| writeln!(file, "[{elapsed:>7.2}s] {line}"));
+    }
+
+    /// Append the resolved danser settings of the render
+    pub fn settings(&self, settings: &DanserSettings) {
+        self.line("Resolved settings:");
+
+        self.write(|file| {
+            serde_json::to_writer_pretty(&mut *file, settings)?;
+
+            writeln!(file)
+        });
+    }
+
+    fn write<F>(&self, f: F)
+    where
+        F: FnOnce(&mut File) -> std::io::Result<()>,
+    {
+        let mut guard = self.file.lock().unwrap();
+
+        if let Some(file) = guard.as_mut() {
+            if let Err(err) = f(file) {
+                warn!("failed to write render log: {err}");
+
+                // Don't keep trying if the disk is full or similar
+                *guard = None;
+            }
+        }
+    }
+}
+
+/// Delete render logs that are older than `max_age`
+pub fn remove_old_logs(max_age: Duration) {
+    let dir = BotConfig::get().paths.render_logs();
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        // The folder only exists if render logs are enabled
+        Err(_) => return,
+    };
+
+    let now = SystemTime::now();
+
+    for entry in entries.flatten() {
+        let is_old = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map_or(false, |age| age > max_age);
+
+        if is_old {
+            if let Err(err) = fs::remove_file(entry.path()) {
+                warn!("failed to remove render log {:?}: {err}", entry.path());
+            }
+        }
+    }
+}
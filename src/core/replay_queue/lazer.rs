@@ -0,0 +1,213 @@
+use std::io::Cursor;
+
+use osu_db::{Error as OsrError, Replay};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::util::parse_mods;
+
+use super::visual_mods::{mods_offset, skip_string};
+
+/// Replays of osu!lazer have a game version of at least this and append
+/// an additional compressed block of score info after the legacy fields
+pub const LAZER_VERSION: u32 = 30_000_000;
+
+/// Lazer-only mods that danser plays the same as it would without them
+const IGNORED: [&str; 1] = [
+    // danser already behaves like stable which is what classic emulates
+    "CL",
+];
+
+/// Parse a .osr file, retrying without the lazer block if that's what trips up the parser.
+///
+/// An empty map hash is turned into `None`.
+pub fn parse_replay(bytes: &[u8]) -> Result<Replay, OsrError> {
+    let res = match Replay::from_bytes(bytes) {
+        Ok(replay) => Ok(replay),
+        Err(err) => match lazer_block(bytes) {
+            Some((legacy_end, _)) => Replay::from_bytes(&bytes[..legacy_end]).map_err(|_| err),
+            None => Err(err),
+        },
+    };
+
+    res.map(|mut replay| {
+        replay.beatmap_hash = replay.beatmap_hash.filter(|hash| !hash.is_empty());
+
+        replay
+    })
+}
+
+/// Acronyms of the mods of a lazer replay that danser can't render.
+///
+/// Mods with custom settings are included too since danser would render
+/// them with their default settings. Returns an empty list for stable replays
+/// or if the lazer block could not be read.
+pub fn unrenderable_mods(bytes: &[u8]) -> Vec<String> {
+    let mods = match lazer_mods(bytes) {
+        Some(mods) => mods,
+        None => return Vec::new(),
+    };
+
+    mods.into_iter()
+        .filter(|m| !IGNORED.contains(&m.acronym.as_str()))
+        .filter_map(|m| match parse_mods(&m.acronym) {
+            Ok(_) if m.acronym.len() == 2 && m.settings.is_empty() => None,
+            Ok(_) if m.acronym.len() == 2 => Some(format!("{} (custom settings)", m.acronym)),
+            _ => Some(m.acronym),
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct LazerScoreInfo {
+    #[serde(default)]
+    mods: Vec<LazerMod>,
+}
+
+#[derive(Deserialize)]
+struct LazerMod {
+    acronym: String,
+    #[serde(default)]
+    settings: Map<String, Value>,
+}
+
+fn lazer_mods(bytes: &[u8]) -> Option<Vec<LazerMod>> {
+    let (_, compressed) = lazer_block(bytes)?;
+    let mut json = Vec::new();
+
+    if let Err(err) = lzma_rs::lzma_decompress(&mut Cursor::new(compressed), &mut json) {
+        warn!("failed to decompress lazer replay info: {err}");
+
+        return None;
+    }
+
+    match serde_json::from_slice::<LazerScoreInfo>(&json) {
+        Ok(info) => Some(info.mods),
+        Err(err) => {
+            warn!("failed to deserialize lazer replay info: {err}");
+
+            None
+        }
+    }
+}
+
+/// The offset at which the legacy fields end alongside the compressed lazer block.
+///
+/// `None` if the replay is not from lazer or too short.
+// https://osu.ppy.sh/wiki/en/Client/File_formats/Osr_%28file_format%29
+fn lazer_block(bytes: &[u8]) -> Option<(usize, &[u8])> {
+    let version = u32::from_le_bytes(bytes.get(1..5)?.try_into().unwrap());
+
+    if version < LAZER_VERSION {
+        return None;
+    }
+
+    // mods and life bar graph
+    let mut offset = skip_string(bytes, mods_offset(bytes)? + 4)?;
+
+    // timestamp
+    offset += 8;
+
+    // replay data
+    let data_len = read_i32(bytes, offset)?;
+    offset += 4 + usize::try_from(data_len).ok()?;
+
+    // online score id
+    offset += 8;
+    let legacy_end = offset;
+
+    let block_len = read_i32(bytes, offset)?;
+    offset += 4;
+    let block = bytes.get(offset..offset + usize::try_from(block_len).ok()?)?;
+
+    Some((legacy_end, block))
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> Option<i32> {
+    let slice = bytes.get(offset..offset + 4)?;
+
+    Some(i32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{replay_queue::test_util::OsrBuilder, ReplaySlim};
+
+    use super::*;
+
+    const HASH: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+    /// A minimal lazer replay whose score info contains the given json
+    fn lazer_replay(version: u32, json: &str) -> Vec<u8> {
+        replay_with_hash(version, HASH, json)
+    }
+
+    fn replay_with_hash(version: u32, hash: &str, json: &str) -> Vec<u8> {
+        OsrBuilder::new()
+            .version(version)
+            .map_hash(hash)
+            .lazer_info(json)
+            .build()
+    }
+
+    const LAZER_INFO: &str = r#"{
+        "online_id": -1,
+        "mods": [
+            { "acronym": "HD" },
+            { "acronym": "DT", "settings": { "speed_change": 1.3 } },
+            { "acronym": "CL" },
+            { "acronym": "WU" }
+        ],
+        "statistics": { "great": 0 },
+        "client_version": "2024.1009.1"
+    }"#;
+
+    #[test]
+    fn parses_lazer_replay() {
+        let bytes = lazer_replay(30_000_016, LAZER_INFO);
+        let replay = parse_replay(&bytes).unwrap();
+
+        assert_eq!(replay.version, 30_000_016);
+        assert_eq!(replay.player_name.as_deref(), Some("mezo"));
+    }
+
+    #[test]
+    fn finds_unrenderable_mods() {
+        let bytes = lazer_replay(30_000_016, LAZER_INFO);
+
+        assert_eq!(
+            unrenderable_mods(&bytes),
+            ["DT (custom settings)".to_owned(), "WU".to_owned()]
+        );
+    }
+
+    #[test]
+    fn ignores_stable_replay() {
+        let bytes = lazer_replay(20_230_326, LAZER_INFO);
+
+        assert!(lazer_block(&bytes).is_none());
+        assert!(unrenderable_mods(&bytes).is_empty());
+    }
+
+    #[test]
+    fn hashless_replay() {
+        let bytes = replay_with_hash(30_000_016, "", LAZER_INFO);
+        let replay = ReplaySlim::from(parse_replay(&bytes).unwrap());
+
+        assert_eq!(replay.beatmap_hash, None);
+
+        let bytes = replay_with_hash(30_000_016, HASH, LAZER_INFO);
+        let replay = ReplaySlim::from(parse_replay(&bytes).unwrap());
+
+        assert_eq!(replay.beatmap_hash.as_deref(), Some(HASH));
+    }
+
+    #[test]
+    fn corrupt_lazer_block() {
+        let mut bytes = lazer_replay(30_000_016, LAZER_INFO);
+        let len = bytes.len();
+        bytes[len - 10..].fill(0xff);
+
+        assert!(unrenderable_mods(&bytes).is_empty());
+    }
+}
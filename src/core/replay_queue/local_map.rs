@@ -0,0 +1,289 @@
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context as _, Report, Result};
+use zip::ZipArchive;
+
+use crate::core::BotConfig;
+
+/// A map that was uploaded alongside a replay because it can't be
+/// retrieved from the osu!api or mirrors, or whose mapset was downloaded
+/// because it overrides the replay's map.
+pub struct LocalMap {
+    /// Content of the .osu file whose hash matches the replay's
+    osu_file: String,
+    /// Name of the .osu file within the mapset
+    osu_filename: String,
+    /// The full .osz archive
+    archive: Vec<u8>,
+}
+
+impl LocalMap {
+    /// Pick the difficulty with the given hash out of the .osz archive.
+    ///
+    /// Without a hash, the archive may only contain a single
+    /// difficulty. Fails with [`InvalidMapset`] if the archive can't be rendered.
+    pub fn from_upload(bytes: Vec<u8>, hash: Option<&str>) -> Result<Self> {
+        let (osu_filename, osu_file) = {
+            let mut archive = ZipArchive::new(Cursor::new(bytes.as_slice()))
+                .context("failed to create zip archive")?;
+
+            let (osu_filename, osu_file) = find_osu_file(&mut archive, hash)?;
+
+            // danser would render the map silently without its audio
+            let audio = audio_filename(&osu_file);
+
+            if !audio.map_or(false, |audio| archive.by_name(audio).is_ok()) {
+                let audio = audio.unwrap_or_default().to_owned();
+
+                return Err(Report::from(InvalidMapset::MissingAudio(audio)));
+            }
+
+            (osu_filename, osu_file)
+        };
+
+        Ok(Self {
+            osu_file,
+            osu_filename,
+            archive: bytes,
+        })
+    }
+
+    /// Whether the .osu file has the given hash
+    pub fn matches_hash(&self, hash: &str) -> bool {
+        matches_hash(self.osu_file.as_bytes(), hash)
+    }
+
+    /// Title of the map in the form `artist - title [version]`
+    pub fn title(&self) -> String {
+        let metadata = |key: &str| {
+            self.osu_file
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .map_or("", str::trim)
+        };
+
+        format!(
+            "{} - {} [{}]",
+            metadata("Artist:"),
+            metadata("Title:"),
+            metadata("Version:"),
+        )
+    }
+
+    /// Time in seconds until the map's last hit object
+    pub fn seconds_total(&self) -> u32 {
+        let hit_objects = self
+            .osu_file
+            .split_once("[HitObjects]")
+            .map_or("", |(_, hit_objects)| hit_objects);
+
+        let last_ms = hit_objects
+            .lines()
+            .filter_map(|line| line.split(',').nth(2))
+            .filter_map(|time| time.trim().parse::<f64>().ok())
+            .fold(0.0, f64::max);
+
+        (last_ms / 1000.0).ceil() as u32
+    }
+
+    /// Store the map in the songs folder so that danser can find it.
+    ///
+    /// The map has to be removed through [`StoredMap::remove`] once it's rendered.
+    pub fn store(self) -> Result<StoredMap> {
+        let hash = format!("{:x}", md5::compute(&self.osu_file));
+        let mut mapset_dir = BotConfig::get().paths.songs();
+        mapset_dir.push(format!("local-{hash}"));
+
+        let mut archive =
+            ZipArchive::new(Cursor::new(self.archive)).context("failed to create zip archive")?;
+
+        archive
+            .extract(&mapset_dir)
+            .with_context(|| format!("failed to extract zip archive at {mapset_dir:?}"))?;
+
+        mapset_dir.push(&self.osu_filename);
+
+        Ok(StoredMap {
+            osu_file: mapset_dir,
+            hash,
+        })
+    }
+}
+
+/// A [`LocalMap`] within the songs folder
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredMap {
+    /// Path to the .osu file
+    pub osu_file: PathBuf,
+    /// MD5 hash of the .osu file
+    pub hash: String,
+}
+
+impl StoredMap {
+    /// Delete the map's folder from the songs folder
+    pub fn remove(&self) {
+        let dir = self.osu_file.parent().unwrap_or_else(|| Path::new(""));
+
+        if let Err(err) = fs::remove_dir_all(dir) {
+            warn!("failed to remove local map {dir:?}: {err}");
+        }
+    }
+}
+
+/// Reason why an uploaded .osz archive can't be rendered
+#[derive(Debug)]
+pub enum InvalidMapset {
+    /// No difficulty matches the hash of the replay's map
+    NoMatch,
+    /// The replay has no map hash and the archive contains several difficulties
+    Ambiguous,
+    /// The audio file that the difficulty refers to is missing
+    MissingAudio(String),
+}
+
+impl Display for InvalidMapset {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::NoMatch => f.write_str(
+                "The uploaded mapset does not contain the replay's map.\n\
+                Make sure to upload the mapset that the replay was set on.",
+            ),
+            Self::Ambiguous => f.write_str(
+                "The replay does not contain the hash of its map so the difficulty \
+                can't be picked from the mapset.\n\
+                Upload a .osz that only contains the replay's difficulty instead.",
+            ),
+            Self::MissingAudio(audio) if audio.is_empty() => {
+                f.write_str("The uploaded map does not specify its audio file")
+            }
+            Self::MissingAudio(audio) => write!(
+                f,
+                "The uploaded mapset does not contain the map's audio file `{audio}`"
+            ),
+        }
+    }
+}
+
+impl StdError for InvalidMapset {}
+
+/// Search the .osz archive for the .osu file that matches the given hash
+/// and return its name and content.
+///
+/// Without a hash, the archive must contain exactly one .osu file.
+fn find_osu_file<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    hash: Option<&str>,
+) -> Result<(String, String)> {
+    let mut found = None;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .with_context(|| format!("failed to get zip entry #{i}"))?;
+
+        if !entry.name().ends_with(".osu") {
+            continue;
+        }
+
+        let mut content = Vec::with_capacity(entry.size() as usize);
+
+        entry
+            .read_to_end(&mut content)
+            .with_context(|| format!("failed to read zip entry `{}`", entry.name()))?;
+
+        match hash {
+            Some(hash) if !matches_hash(&content, hash) => continue,
+            Some(_) => {}
+            None if found.is_some() => return Err(Report::from(InvalidMapset::Ambiguous)),
+            None => {}
+        }
+
+        let osu_file = String::from_utf8(content).context("the .osu file is not valid UTF-8")?;
+        found = Some((entry.name().to_owned(), osu_file));
+
+        if hash.is_some() {
+            break;
+        }
+    }
+
+    found.ok_or_else(|| Report::from(InvalidMapset::NoMatch))
+}
+
+/// Name of the audio file as specified in the .osu file
+fn audio_filename(osu_file: &str) -> Option<&str> {
+    osu_file
+        .lines()
+        .find_map(|line| line.strip_prefix("AudioFilename:"))
+        .map(str::trim)
+        .filter(|audio| !audio.is_empty())
+}
+
+fn matches_hash(bytes: &[u8], hash: &str) -> bool {
+    format!("{:x}", md5::compute(bytes)).eq_ignore_ascii_case(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::*;
+
+    const EASY: &str = "AudioFilename: audio.mp3\nVersion:Easy\n";
+    const HARD: &str = "AudioFilename: audio.mp3\nVersion:Hard\n";
+
+    fn osz(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (name, content) in files {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn invalid(res: Result<LocalMap>) -> InvalidMapset {
+        match res {
+            Ok(_) => panic!("expected an invalid mapset"),
+            Err(err) => err.downcast().unwrap(),
+        }
+    }
+
+    #[test]
+    fn picks_difficulty_by_hash() {
+        let bytes = osz(&[("easy.osu", EASY), ("hard.osu", HARD), ("audio.mp3", "")]);
+        let hash = format!("{:x}", md5::compute(HARD));
+
+        let map = LocalMap::from_upload(bytes.clone(), Some(&hash)).unwrap();
+        assert_eq!(map.osu_filename, "hard.osu");
+
+        let res = LocalMap::from_upload(bytes, Some("d41d8cd98f00b204e9800998ecf8427e"));
+        assert!(matches!(invalid(res), InvalidMapset::NoMatch));
+    }
+
+    #[test]
+    fn hashless_requires_single_difficulty() {
+        let bytes = osz(&[("easy.osu", EASY), ("audio.mp3", "")]);
+        assert!(LocalMap::from_upload(bytes, None).is_ok());
+
+        let bytes = osz(&[("easy.osu", EASY), ("hard.osu", HARD), ("audio.mp3", "")]);
+        let res = LocalMap::from_upload(bytes, None);
+        assert!(matches!(invalid(res), InvalidMapset::Ambiguous));
+    }
+
+    #[test]
+    fn requires_audio() {
+        let bytes = osz(&[("easy.osu", EASY)]);
+        let res = LocalMap::from_upload(bytes, None);
+        assert!(matches!(invalid(res), InvalidMapset::MissingAudio(audio) if audio == "audio.mp3"));
+    }
+}
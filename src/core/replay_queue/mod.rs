@@ -1,20 +1,69 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
 
+use time::OffsetDateTime;
 use tokio::sync::{
     mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-    Mutex,
+    Mutex, Notify,
 };
+use twilight_model::id::{marker::GuildMarker, Id};
 
-pub use self::data::*;
+use crate::core::{
+    locale::{Locale, Msg},
+    stats::BotStats,
+    BotConfig,
+};
 
+pub use self::{
+    comparison::ComparedReplay,
+    data::*,
+    demo::DemoReplay,
+    encoder::VideoEncoder,
+    highlight::HighlightMode,
+    job_log::JobLog,
+    lazer::{parse_replay, unrenderable_mods, LAZER_VERSION},
+    local_map::{InvalidMapset, LocalMap, StoredMap},
+    quality::QualityPreset,
+    resolution::Resolution,
+    speed::{PitchMode, PlaybackSpeed},
+    visual_mods::VisualMods,
+};
+
+mod comparison;
 mod data;
+mod demo;
+mod encoder;
+mod highlight;
+mod job_log;
+mod lazer;
+mod local_map;
+mod patched_replay;
 mod process;
+mod quality;
+mod resolution;
+mod speed;
+mod sweep;
+mod visual_mods;
+
+#[cfg(test)]
+mod test_util;
+
+pub mod skin_ini;
+pub mod watermark;
 
 pub struct ReplayQueue {
     pub queue: Mutex<VecDeque<ReplayData>>,
     pub status: Mutex<ReplayStatus>,
     tx: UnboundedSender<()>,
     rx: Mutex<UnboundedReceiver<()>>,
+    next_id: AtomicU32,
+    /// Whether the entry that's currently processed should be aborted
+    cancel_current: AtomicBool,
+    cancel_notify: Notify,
 }
 
 impl ReplayQueue {
@@ -22,6 +71,93 @@ impl ReplayQueue {
         Self::default()
     }
 
+    /// A fresh id for an entry that's about to be pushed
+    pub fn next_id(&self) -> u32 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Returns a message for the user if the queue has no room for `jobs` more entries,
+    /// stating its length and when enough slots are expected to free up.
+    pub async fn capacity_check(
+        &self,
+        stats: &BotStats,
+        locale: Locale,
+        jobs: usize,
+    ) -> Option<String> {
+        let capacity = BotConfig::get().queue_capacity?;
+        let len = self.queue.lock().await.len();
+
+        if len + jobs <= capacity {
+            return None;
+        }
+
+        let mut content = format!("{}: {len}", locale.get(Msg::QueueFull));
+
+        // Each entry that's done frees up a slot
+        if let Some(wait) = stats.estimated_wait(len + jobs - capacity) {
+            let timestamp = OffsetDateTime::now_utc() + wait;
+            let wait_msg = locale.get(Msg::QueueFullWait);
+            let _ = write!(content, "\n{wait_msg} <t:{}:R>", timestamp.unix_timestamp());
+        }
+
+        Some(content)
+    }
+
+    /// Returns a message for the user if the guild already has `limit`
+    /// replays in the queue.
+    pub async fn guild_capacity_check(
+        &self,
+        guild: Option<Id<GuildMarker>>,
+        limit: usize,
+        locale: Locale,
+    ) -> Option<String> {
+        let guild = guild?;
+        let count = self.guild_count(guild).await;
+
+        (count >= limit).then(|| Self::guild_queue_full(count, limit, locale))
+    }
+
+    /// Response for a guild that has `count` of its `limit` replays in the queue
+    pub fn guild_queue_full(count: usize, limit: usize, locale: Locale) -> String {
+        format!("{} ({count}/{limit})", locale.get(Msg::GuildQueueFull))
+    }
+
+    /// Amount of the guild's replays that are currently in the queue
+    pub async fn guild_count(&self, guild: Id<GuildMarker>) -> usize {
+        self.queue
+            .lock()
+            .await
+            .iter()
+            .filter(|data| data.guild == Some(guild))
+            .count()
+    }
+
+    /// Amount of entries that render the given replay file, e.g. clips of the same replay
+    pub async fn replay_count(&self, path: &Path) -> usize {
+        self.queue
+            .lock()
+            .await
+            .iter()
+            .filter(|data| {
+                data.path == path
+                    || data
+                        .comparison
+                        .as_ref()
+                        .map_or(false, |compared| compared.path == path)
+            })
+            .count()
+    }
+
+    /// Amount of entries that render on the given uploaded map
+    pub async fn local_map_count(&self, local_map: &StoredMap) -> usize {
+        self.queue
+            .lock()
+            .await
+            .iter()
+            .filter(|data| data.local_map.as_ref() == Some(local_map))
+            .count()
+    }
+
     pub async fn push(&self, data: ReplayData) {
         self.queue.lock().await.push_back(data);
         let _ = self.tx.send(());
@@ -35,12 +171,113 @@ impl ReplayQueue {
         trace!("Locking channel receiver...");
         let mut guard = self.rx.lock().await;
         trace!("Locked receiver, awaiting entry...");
-        let _ = guard.recv().await;
-        trace!("Received entry, locking queue...");
-        let queue_guard = self.queue.lock().await;
-        trace!("Locked queue");
 
-        queue_guard.front().unwrap().to_owned()
+        // Entries might have been removed without consuming their
+        // notification so the queue can be empty even after receiving
+        loop {
+            let _ = guard.recv().await;
+            trace!("Received entry, locking queue...");
+            let queue_guard = self.queue.lock().await;
+            trace!("Locked queue");
+
+            if let Some(data) = queue_guard.front() {
+                return data.to_owned();
+            }
+        }
+    }
+
+    /// Status of the entry with the given id.
+    ///
+    /// Returns `None` if the entry is no longer in the queue.
+    pub async fn entry_status(&self, id: u32) -> Option<ReplayStatus> {
+        let idx = self
+            .queue
+            .lock()
+            .await
+            .iter()
+            .position(|data| data.id == id)?;
+
+        if idx == 0 {
+            Some(*self.status.lock().await)
+        } else {
+            Some(ReplayStatus::Queued(idx + 1))
+        }
+    }
+
+    /// Remove all waiting entries at once and return them.
+    ///
+    /// The entry that's currently processed is kept unless `force` is set in
+    /// which case its render is aborted. The returned bool indicates whether
+    /// there was such an entry to abort.
+    pub async fn clear(&self, force: bool) -> (Vec<ReplayData>, bool) {
+        let mut queue = self.queue.lock().await;
+
+        // The first entry is currently being processed so it's skipped
+        let removed = if queue.len() > 1 {
+            queue.drain(1..).collect()
+        } else {
+            Vec::new()
+        };
+
+        let abort = force && !queue.is_empty();
+
+        if abort {
+            self.cancel_current.store(true, Ordering::SeqCst);
+            self.cancel_notify.notify_waiters();
+        }
+
+        (removed, abort)
+    }
+
+    /// Move a waiting entry of the guild right behind the one that's currently processed.
+    ///
+    /// Returns the entry's id and its previous position.
+    pub async fn bump(
+        &self,
+        entry: QueueEntry,
+        guild: Id<GuildMarker>,
+    ) -> Result<(u32, usize), BumpError> {
+        let mut queue = self.queue.lock().await;
+
+        let idx = match entry {
+            QueueEntry::Id(id) => queue.iter().position(|data| data.id == id),
+            QueueEntry::Position(position) => {
+                position.checked_sub(1).filter(|&idx| idx < queue.len())
+            }
+        };
+
+        let idx = idx.ok_or(BumpError::NotFound)?;
+        let data = &queue[idx];
+
+        // The first entry is currently being processed so it can't be moved
+        if idx == 0 {
+            return Err(BumpError::InProgress);
+        } else if data.guild != Some(guild) {
+            return Err(BumpError::OtherGuild);
+        }
+
+        let id = data.id;
+        move_behind_front(&mut queue, idx);
+
+        Ok((id, idx + 1))
+    }
+
+    /// Whether the entry that's currently processed should be aborted
+    fn is_cancelled(&self) -> bool {
+        self.cancel_current.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the entry that's currently processed should be aborted
+    async fn cancelled(&self) {
+        loop {
+            let notified = self.cancel_notify.notified();
+
+            if self.is_cancelled() {
+                return;
+            }
+
+            notified.await;
+        }
     }
 
     pub async fn set_status(&self, status: ReplayStatus) {
@@ -52,12 +289,41 @@ impl ReplayQueue {
     pub async fn reset_peek(&self) {
         trace!("Resetting peek...");
         *self.status.lock().await = ReplayStatus::Waiting;
+        self.cancel_current.store(false, Ordering::SeqCst);
         trace!("Peek reset, popping queue...");
         let _ = self.pop().await;
         trace!("Popped queue");
     }
 }
 
+/// Identifies an entry of the queue
+#[derive(Copy, Clone, Debug)]
+pub enum QueueEntry {
+    Id(u32),
+    /// Starting at 1 for the entry that's currently processed
+    Position(usize),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BumpError {
+    NotFound,
+    /// The entry is already being processed
+    InProgress,
+    /// The entry was queued from a different server
+    OtherGuild,
+}
+
+/// Move the item at `idx` to the second slot, keeping the first one in place
+fn move_behind_front<T>(queue: &mut VecDeque<T>, idx: usize) {
+    if idx <= 1 {
+        return;
+    }
+
+    if let Some(item) = queue.remove(idx) {
+        queue.insert(1, item);
+    }
+}
+
 impl Default for ReplayQueue {
     #[inline]
     fn default() -> Self {
@@ -68,6 +334,33 @@ impl Default for ReplayQueue {
             tx,
             rx: Mutex::new(rx),
             status: Mutex::new(ReplayStatus::Waiting),
+            next_id: AtomicU32::new(1),
+            cancel_current: AtomicBool::new(false),
+            cancel_notify: Notify::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_behind_front() {
+        let mut queue: VecDeque<_> = (1..=5).collect();
+        move_behind_front(&mut queue, 3);
+
+        assert_eq!(queue, [1, 4, 2, 3, 5]);
+    }
+
+    #[test]
+    fn keeps_front() {
+        let mut queue: VecDeque<_> = (1..=3).collect();
+
+        move_behind_front(&mut queue, 0);
+        move_behind_front(&mut queue, 1);
+        move_behind_front(&mut queue, 5);
+
+        assert_eq!(queue, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context as _, ContextCompat, Result};
+
+use crate::core::BotConfig;
+
+use super::{visual_mods::skip_string, VisualMods};
+
+/// Copy of a replay that's handed to danser instead of the original, removed on drop
+pub struct PatchedReplay {
+    path: PathBuf,
+}
+
+impl PatchedReplay {
+    /// Copy the replay with overridden visual mods and map hash.
+    ///
+    /// Returns `None` if there is nothing to override.
+    pub fn create(
+        id: u32,
+        replay: &Path,
+        mods: Option<VisualMods>,
+        map_hash: Option<&str>,
+    ) -> Result<Option<Self>> {
+        if mods.is_none() && map_hash.is_none() {
+            return Ok(None);
+        }
+
+        let mut bytes = fs::read(replay).with_context(|| format!("failed to read {replay:?}"))?;
+
+        // Patched first since it may change the offset of the mods
+        if let Some(hash) = map_hash {
+            patch_map_hash(&mut bytes, hash)?;
+        }
+
+        if let Some(mods) = mods {
+            mods.patch(&mut bytes)?;
+        }
+
+        let mut path = BotConfig::get().paths.downloads();
+        path.push(format!("patched-{id}.osr"));
+
+        fs::write(&path, bytes).with_context(|| format!("failed to write {path:?}"))?;
+
+        Ok(Some(Self { path }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PatchedReplay {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!("failed to remove patched replay {:?}: {err}", self.path);
+        }
+    }
+}
+
+/// Overwrite the map hash within the bytes of a .osr file.
+///
+/// danser looks up the map through this hash so it
+/// must match the one of the map that's rendered.
+fn patch_map_hash(bytes: &mut Vec<u8>, hash: &str) -> Result<()> {
+    // mode and game version
+    const OFFSET: usize = 5;
+
+    let end = skip_string(bytes, OFFSET)
+        .filter(|&end| end <= bytes.len())
+        .context("replay ends before its map hash")?;
+
+    // A hash is 32 characters long so its length fits into a single byte
+    let mut string = Vec::with_capacity(2 + hash.len());
+    string.extend_from_slice(&[0x0b, hash.len() as u8]);
+    string.extend_from_slice(hash.as_bytes());
+
+    bytes.splice(OFFSET..end, string);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_v2::prelude::GameMods;
+
+    use crate::core::replay_queue::{test_util::OsrBuilder, visual_mods::mods_offset};
+
+    use super::*;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef";
+
+    fn replay_bytes(map_hash: &str) -> Vec<u8> {
+        OsrBuilder::new()
+            .map_hash(map_hash)
+            .mods(GameMods::HardRock)
+            .build()
+    }
+
+    #[test]
+    fn replaces_map_hash() {
+        let mut bytes = replay_bytes("d41d8cd98f00b204e9800998ecf8427e");
+        patch_map_hash(&mut bytes, HASH).unwrap();
+
+        assert_eq!(bytes, replay_bytes(HASH));
+    }
+
+    #[test]
+    fn inserts_missing_map_hash() {
+        let mut bytes = replay_bytes("");
+        patch_map_hash(&mut bytes, HASH).unwrap();
+
+        assert_eq!(bytes, replay_bytes(HASH));
+    }
+
+    #[test]
+    fn mods_stay_in_place() {
+        let mut bytes = replay_bytes("d41d8cd98f00b204e9800998ecf8427e");
+        patch_map_hash(&mut bytes, HASH).unwrap();
+
+        let offset = mods_offset(&bytes).unwrap();
+        let mods = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+
+        assert_eq!(mods, GameMods::HardRock.bits());
+    }
+
+    #[test]
+    fn truncated_map_hash() {
+        let mut bytes = replay_bytes(HASH);
+        bytes.truncate(20);
+
+        assert!(patch_map_hash(&mut bytes, HASH).is_err());
+    }
+}
@@ -1,31 +1,59 @@
 use std::{
     error::Error as StdError,
     ffi::OsStr,
-    fmt::{Display, Formatter, Result as FmtResult},
+    fmt::{Display, Formatter, Result as FmtResult, Write},
     fs,
     io::Cursor,
-    path::PathBuf,
+    path::Path,
     process::Stdio,
+    slice,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use bytes::Bytes;
 use eyre::{Context as _, ContextCompat, Report, Result};
 use futures::future;
 use rosu_pp::{Beatmap, BeatmapExt};
-use rosu_v2::prelude::{Beatmap as Map, GameMods};
+use rosu_v2::prelude::Beatmap as Map;
 use tokio::{
     io::{AsyncBufReadExt, AsyncReadExt, BufReader},
     process::{ChildStdout, Command},
+    time,
+};
+use tracing::Instrument;
+use twilight_http::{
+    api_error::ApiError,
+    error::{Error as HttpError, ErrorType},
+    response::ResponseFuture,
+};
+use twilight_model::{
+    channel::{embed::EmbedField, message::MessageFlags, Message},
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
 };
 use zip::ZipArchive;
 
 use crate::{
-    core::{BotConfig, Context, ReplayStatus},
-    util::{builder::MessageBuilder, levenshtein_similarity, ChannelExt},
+    core::{
+        locale::Msg, settings::DanserSettings, stats::FailureReason, BotConfig, Context,
+        ErrorReport, PreparedSettings, RenderOutput, RenderRecord, ReplayStatus, VideoEncoder,
+    },
+    custom_client::{is_not_found, DownloadFailure},
+    util::{
+        builder::{AuthorBuilder, EmbedBuilder, MessageBuilder},
+        mods_string, ChannelExt, ExponentialBackoff, MessageExt,
+    },
 };
 
-use super::{ReplayData, ReplayQueue, ReplaySlim};
+use super::{
+    comparison::{comparison_label, KnockoutDir},
+    patched_replay::PatchedReplay,
+    JobLog, RenderOptions, ReplayData, ReplayQueue, ReplaySlim,
+};
 
 impl ReplayQueue {
     pub fn process(ctx: Arc<Context>) {
@@ -33,126 +61,372 @@ impl ReplayQueue {
     }
 
     async fn async_process(ctx: Arc<Context>) {
+        loop {
+            let data = ctx.replay_queue.peek().await;
+            let span = info_span!("render", user = %data.user, replay = %data.replay_name());
+            let local_map = data.local_map.clone();
+
+            Self::process_entry(Arc::clone(&ctx), data)
+                .instrument(span)
+                .await;
+
+            // The clips of a highlight share their uploaded map
+            if let Some(local_map) = local_map {
+                if ctx.replay_queue.local_map_count(&local_map).await == 0 {
+                    local_map.remove();
+                }
+            }
+
+            if ctx.replay_queue.queue.lock().await.is_empty() {
+                ctx.announce_idle().await;
+            }
+        }
+    }
+
+    /// Render and upload the front entry of the queue, then pop it
+    async fn process_entry(ctx: Arc<Context>, data: ReplayData) {
         let config = BotConfig::get();
 
         let mut danser_path = config.paths.danser().to_owned();
         danser_path.push("danser");
 
-        loop {
-            let ReplayData {
-                input_channel,
-                output_channel,
-                path,
-                replay,
-                time_points,
-                user,
-            } = ctx.replay_queue.peek().await;
-
-            let mapset_id = match replay.beatmap_hash.as_deref() {
-                Some(hash) => match ctx.osu().beatmap().checksum(hash).await {
+        let ReplayData {
+            id,
+            comparison,
+            guild: _,
+            input_channel,
+            interaction_token,
+            local_map,
+            output_channel,
+            options,
+            path,
+            queued_at,
+            render_for,
+            replay,
+            skin_ini,
+            time_points,
+            title: note,
+            user,
+        } = data;
+
+        let start = Instant::now();
+        let job_log = JobLog::create(id);
+
+        job_log.line(format_args!("Job {id} of user {user}, replay {path:?}"));
+        job_log.line(format_args!("{options:?}, {time_points:?}"));
+
+        // See `RenderFor` for who the render is attributed to
+        let recipient = render_for
+            .as_ref()
+            .map_or(user, |render_for| render_for.user);
+
+        if recipient != user {
+            job_log.line(format_args!("Rendering for user {recipient}"));
+        }
+
+        if replay.lazer {
+            job_log.line("Replay was set on lazer");
+        }
+
+        let guild = ctx
+            .cache
+            .channel(output_channel, |c| c.guild_id)
+            .ok()
+            .flatten();
+
+        // Only needed to download the map; replays without it must come with a map
+        let hash = replay.beatmap_hash.as_deref();
+
+        // Maps that were uploaded alongside the replay are already stored
+        let (map_path, mapset_id) = match local_map {
+            Some(ref local_map) => (local_map.osu_file.clone(), None),
+            None => {
+                let hash = match hash {
+                    Some(hash) => hash,
+                    None => {
+                        warn!("missing hash in replay requested by user {user}");
+
+                        let content = "The replay does not contain the hash of its map \
+                            so the map could not be downloaded";
+                        let _ = input_channel.error(&ctx, content).await;
+
+                        ctx.stats.renders.failed(FailureReason::Map);
+                        ctx.replay_queue.reset_peek().await;
+                        return;
+                    }
+                };
+
+                let mapset_id = match ctx.osu().beatmap().checksum(hash).await {
                     Ok(Map { mapset, .. }) => match mapset {
                         Some(mapset) => mapset.mapset_id,
                         None => {
                             warn!("map without mapset");
 
-                            let content = "The mapset was not received when requesting the map from the osu!api";
+                            let content =
+                                "The mapset was not received when requesting the map from the osu!api";
                             let _ = input_channel.error(&ctx, content).await;
 
+                            ctx.stats.renders.failed(FailureReason::Map);
                             ctx.replay_queue.reset_peek().await;
-                            continue;
+                            return;
                         }
                     },
                     Err(err) => {
                         let context = format!("failed to request map with hash `{hash}`");
                         let err = Report::from(err).wrap_err(context);
                         warn!("{err:?}");
+                        report_failure(&ctx, &job_log, user, guild, &err, None).await;
 
                         let content = "Failed to retrieve map. Maybe it's not submitted?";
                         let _ = input_channel.error(&ctx, content).await;
 
+                        ctx.stats.renders.failed(FailureReason::Map);
                         ctx.replay_queue.reset_peek().await;
-                        continue;
+                        return;
                     }
-                },
-                None => {
-                    warn!("missing hash in replay requested by user {user}");
+                };
 
-                    let content = "Missing the beatmap hash in the replay file";
-                    let _ = input_channel.error(&ctx, content).await;
+                info!("Started map download");
+                job_log.line(format_args!("Started download of mapset {mapset_id}"));
+                ctx.replay_queue.set_status(ReplayStatus::Downloading).await;
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
+                let map_osu_file = match prepare_mapset(&ctx, mapset_id, hash).await {
+                    Ok(Some(osu_file)) => osu_file,
+                    Ok(None) => {
+                        warn!("mapset {mapset_id} does not contain a map with hash `{hash}`");
+
+                        let content =
+                            "The downloaded mapset does not contain the replay's difficulty.\n\
+                            The map was likely updated after the replay was set.";
+                        let _ = input_channel.error(&ctx, content).await;
+
+                        ctx.stats.renders.failed(FailureReason::Map);
+                        ctx.replay_queue.reset_peek().await;
+                        return;
+                    }
+                    Err(err) => {
+                        warn!("{err:?}");
+                        report_failure(&ctx, &job_log, user, guild, &err, None).await;
+
+                        let content = err
+                            .downcast_ref::<MapsetDownloadError>()
+                            .map_or(MapsetDownloadError::GENERIC, MapsetDownloadError::message);
+                        let _ = input_channel.error(&ctx, content).await;
+
+                        ctx.stats.renders.failed(FailureReason::Map);
+                        ctx.replay_queue.reset_peek().await;
+                        return;
+                    }
+                };
+
+                info!("Finished map download");
+                job_log.line("Finished map download");
+
+                let mut map_path = config.paths.songs();
+                map_path.push(format!("{mapset_id}/{map_osu_file}"));
+
+                (map_path, Some(mapset_id))
+            }
+        };
+
+        // The map download can't be interrupted without leaving a partial mapset behind
+        if ctx.replay_queue.is_cancelled() {
+            abort_render(&ctx, &job_log, input_channel, user, None).await;
+
+            return;
+        }
+
+        // Deletes the skin copy and the comparison's replays once processing is done
+        let mut _temp_skin = None;
+        let mut _knockout_dir = None;
+        let mut patched_replay = None;
+        let mut broken_skin = None;
+        let mut encoder = ctx.video_encoder();
+
+        let settings_res = ctx
+            .prepare_render_settings(user, guild, &options, skin_ini.as_ref(), id)
+            .and_then(|prepared| {
+                let PreparedSettings {
+                    mut settings,
+                    broken_skin: broken,
+                    temp_skin,
+                } = prepared;
+
+                broken_skin = broken;
+                _temp_skin = temp_skin;
+
+                if let Some(ref compared) = comparison {
+                    let knockout_dir = KnockoutDir::create(id, [&path, &compared.path])
+                        .context("failed to prepare comparison replays")?;
+
+                    knockout_dir.apply(&mut settings);
+                    _knockout_dir = Some(knockout_dir);
                 }
-            };
 
-            info!("Started map download");
-            ctx.replay_queue.set_status(ReplayStatus::Downloading).await;
+                // danser looks up the map through the replay's hash so it must
+                // be the one of the map that overrides it, hashless replays included
+                let map_hash = local_map
+                    .as_ref()
+                    .map(|local_map| local_map.hash.as_str())
+                    .filter(|local_hash| {
+                        hash.map_or(true, |hash| !hash.eq_ignore_ascii_case(local_hash))
+                    });
+
+                patched_replay = PatchedReplay::create(id, &path, options.visual_mods, map_hash)
+                    .context("failed to patch replay")?;
 
-            if let Err(err) = download_mapset(&ctx, mapset_id).await {
+                store_render_settings(&settings).map(|name| (name, settings))
+            });
+
+        let (settings_name, mut settings) = match settings_res {
+            Ok((name, settings)) => {
+                job_log.settings(&settings);
+
+                (name, settings)
+            }
+            Err(err) => {
+                let err = err.wrap_err("failed to store render settings");
                 warn!("{err:?}");
+                report_failure(&ctx, &job_log, user, guild, &err, None).await;
 
-                let content = "Failed to download map. Mirrors are likely down, try again later.";
+                let content = "Failed to apply the render options";
                 let _ = input_channel.error(&ctx, content).await;
 
+                ctx.stats.renders.failed(FailureReason::Settings);
                 ctx.replay_queue.reset_peek().await;
-                continue;
+                return;
             }
+        };
 
-            info!("Finished map download");
-
-            let mut settings_path = config.paths.danser().to_owned();
-            settings_path.push(format!("settings/{user}.json"));
+        if let Some((ref skin, ref reason)) = broken_skin {
+            let err = eyre!("skin `{skin}` can't be used because {reason}");
+            warn!("{err}, falling back to the default skin");
 
-            let settings = if settings_path.exists() {
-                user.to_string()
-            } else {
-                "default".to_owned()
+            let report = ErrorReport {
+                origin: "render skin",
+                user: Some(user),
+                guild,
+                err: &err,
             };
 
-            let filename_opt = path
-                .file_name()
-                .and_then(OsStr::to_str)
-                .and_then(|name| name.split('.').next());
+            ctx.report_error(report).await;
+        }
 
-            let filename = match filename_opt {
-                Some(name) => name,
-                None => {
-                    warn!("replay path `{path:?}` has an unexpected form");
+        let filename_opt = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .and_then(|name| name.split('.').next());
 
-                    let content = "There was an error resolving the beatmap path";
-                    let _ = input_channel.error(&ctx, content).await;
+        let filename = match filename_opt {
+            Some(name) => name,
+            None => {
+                warn!("replay path `{path:?}` has an unexpected form");
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
-                }
-            };
+                let content = "There was an error resolving the beatmap path";
+                let _ = input_channel.error(&ctx, content).await;
 
-            let mut command = Command::new(&danser_path);
-
-            command
-                .arg("-noupdatecheck")
-                .arg("-replay")
-                .arg(&path)
-                .arg("-record")
-                .arg("-settings")
-                .arg(settings)
-                .arg("-quickstart")
-                .arg("-out")
-                .arg(filename)
-                .arg("-preciseprogress")
-                .stderr(Stdio::piped())
-                .stdout(Stdio::piped());
-
-            if time_points.start != 0 {
-                command.args(["-start", &time_points.start.to_string()]);
+                ctx.stats.renders.failed(FailureReason::Other);
+                ctx.replay_queue.reset_peek().await;
+                return;
             }
+        };
+
+        let mut command = Command::new(&danser_path);
+
+        command.arg("-noupdatecheck");
+
+        // Knockout mode picks up both replays from the replays folder;
+        // comparisons are only accepted for replays that contain their map's hash
+        match hash.filter(|_| comparison.is_some()) {
+            Some(hash) => {
+                command.args(["-md5", hash, "-knockout"]);
+            }
+            None => {
+                let replay_path = patched_replay
+                    .as_ref()
+                    .map_or(path.as_path(), PatchedReplay::path);
+
+                command.arg("-replay").arg(replay_path);
+            }
+        }
 
-            if time_points.end != 0 {
-                command.args(["-end", &time_points.end.to_string()]);
+        command
+            .arg("-record")
+            .arg("-settings")
+            .arg(settings_name)
+            .arg("-quickstart")
+            .arg("-out")
+            .arg(filename)
+            .arg("-preciseprogress")
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped());
+
+        if time_points.start != 0 {
+            command.args(["-start", &time_points.start.to_string()]);
+        }
+
+        if time_points.end != 0 {
+            command.args(["-end", &time_points.end.to_string()]);
+        }
+
+        if let Some(speed) = options.speed {
+            command.args(speed.danser_args());
+        }
+
+        info!("Started replay processing");
+        job_log.line(format_args!("Running {command:?}"));
+        let players = match comparison {
+            Some(ref compared) => Some(comparison_label(&replay, &compared.replay)),
+            None => replay.player_name.clone(),
+        };
+
+        ctx.announce_render(players.as_deref(), &map_path).await;
+
+        let announce = guild
+            .and_then(|guild| ctx.guild_settings(guild, |s| s.announce_renders))
+            .unwrap_or(false);
+
+        let status_msg = if announce {
+            let status = Msg::NowRendering;
+            let content =
+                render_status_message(&ctx, guild, status, players.as_deref(), &map_path, user);
+            let builder = MessageBuilder::new().embed(content);
+
+            let res = match output_channel.create_message(&ctx, &builder).await {
+                Ok(response) => response.model().await.map_err(Report::from),
+                Err(err) => Err(Report::from(err)),
+            };
+
+            match res {
+                Ok(msg) => Some(StatusMessage {
+                    msg,
+                    guild,
+                    players: players.as_deref(),
+                    map_path: &map_path,
+                    user,
+                }),
+                Err(err) => {
+                    warn!("{:?}", err.wrap_err("failed to announce render start"));
+
+                    None
+                }
             }
+        } else {
+            None
+        };
+
+        let status_msg = status_msg.as_ref();
 
-            info!("Started replay processing");
+        let mut file_path = config.paths.replays();
+        file_path.push(format!("{filename}.mp4"));
 
+        // A leftover video would hide that the encoder failed
+        if file_path.exists() {
+            let _ = fs::remove_file(&file_path);
+        }
+
+        // Retried once with the CPU if the hardware encoder fails
+        loop {
             ctx.replay_queue
                 .set_status(ReplayStatus::Rendering(0))
                 .await;
@@ -163,141 +437,689 @@ impl ReplayQueue {
                     let reader = BufReader::new(stdout);
 
                     tokio::select! {
-                        _ = read_danser_progress(&ctx, reader) => unreachable!(),
+                        _ = read_danser_progress(&ctx, reader, &job_log) => unreachable!(),
+                        _ = ctx.replay_queue.cancelled() => {
+                            if let Err(err) = child.kill().await {
+                                warn!("{:?}", Report::from(err).wrap_err("failed to kill danser"));
+                            }
+
+                            abort_render(&ctx, &job_log, input_channel, user, status_msg).await;
+
+                            return;
+                        }
                         child_res = child.wait() => {
                             trace!("Danser finished, stopped checking its logs");
 
                             if let Err(err) = child_res {
-                                let err = Report::from(err).wrap_err("failed to run danser command");
+                                let err =
+                                    Report::from(err).wrap_err("failed to run danser command");
                                 warn!("{err:?}");
+                                report_failure(&ctx, &job_log, user, guild, &err, status_msg).await;
 
                                 let content = "Failed to run danser on the replay";
                                 let _ = input_channel.error(&ctx, content).await;
 
+                                ctx.stats.renders.failed(FailureReason::Danser);
                                 ctx.replay_queue.reset_peek().await;
-                                continue;
+                                return;
                             }
 
-                            if let Some(mut stderr) = child.stderr {
-                                let mut res = String::new();
+                            let mut danser_stderr = String::new();
 
+                            if let Some(mut stderr) = child.stderr {
                                 trace!("Reading danser stderr...");
 
-                                if stderr.read_to_string(&mut res).await.is_ok() {
-                                    warn!("danser stderr: {res}");
+                                if stderr.read_to_string(&mut danser_stderr).await.is_ok() {
+                                    warn!("danser stderr: {danser_stderr}");
+                                    job_log.line(format_args!("danser stderr: {danser_stderr}"));
                                 }
 
                                 trace!("Finished danser stderr");
                             }
+
+                            // Other failures, e.g. a broken replay, would fail with libx264 too
+                            if !video_written(&file_path) && encoder.init_failed(&danser_stderr) {
+                                warn!(
+                                    "Initialising {} failed, falling back to libx264",
+                                    encoder.ffmpeg_name()
+                                );
+
+                                job_log.line(format_args!(
+                                    "Initialising {} failed, retrying with libx264",
+                                    encoder.ffmpeg_name()
+                                ));
+
+                                ctx.disable_hardware_encoding();
+                                encoder = VideoEncoder::Cpu;
+                                encoder.apply(&mut settings.recording);
+
+                                if let Err(err) = store_render_settings(&settings) {
+                                    let err = err.wrap_err("failed to store render settings");
+                                    warn!("{err:?}");
+                                    let status = status_msg;
+                                    report_failure(&ctx, &job_log, user, guild, &err, status).await;
+
+                                    let content = "Failed to apply the render options";
+                                    let _ = input_channel.error(&ctx, content).await;
+
+                                    ctx.stats.renders.failed(FailureReason::Settings);
+                                    ctx.replay_queue.reset_peek().await;
+                                    return;
+                                }
+
+                                continue;
+                            }
                         },
                     }
                 }
                 Err(err) => {
                     let err = Report::from(err).wrap_err("failed to start danser command");
                     warn!("{err:?}");
+                    report_failure(&ctx, &job_log, user, guild, &err, status_msg).await;
 
                     let content = "Failed to run danser on the replay";
                     let _ = input_channel.error(&ctx, content).await;
 
+                    ctx.stats.renders.failed(FailureReason::Danser);
                     ctx.replay_queue.reset_peek().await;
-                    continue;
+                    return;
                 }
             }
 
-            info!("Finished replay processing");
+            break;
+        }
 
-            let title = match get_title() {
-                Ok(title) => title,
-                Err(err) => {
-                    warn!("{err:?}");
+        info!("Finished replay processing");
+        job_log.line("Finished danser");
 
-                    let content = "Failed to read danser logs";
-                    let _ = input_channel.error(&ctx, content).await;
+        if ctx.replay_queue.is_cancelled() {
+            abort_render(&ctx, &job_log, input_channel, user, status_msg).await;
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
-                }
-            };
+            return;
+        }
 
-            let map_osu_file = match get_beatmap_osu_file(mapset_id, &title).await {
-                Ok(osu_file) => osu_file,
-                Err(err) => {
-                    let err = err.wrap_err("failed to get map_osu_file");
-                    warn!("{err:?}");
+        let title = match get_title() {
+            Ok(title) => title,
+            Err(err) => {
+                warn!("{err:?}");
+                report_failure(&ctx, &job_log, user, guild, &err, status_msg).await;
 
-                    let content = "danser did not like the replay file";
-                    let _ = input_channel.error(&ctx, content).await;
+                let content = "Failed to read danser logs";
+                let _ = input_channel.error(&ctx, content).await;
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
-                }
-            };
+                ctx.stats.renders.failed(FailureReason::Danser);
+                ctx.replay_queue.reset_peek().await;
+                return;
+            }
+        };
 
-            let mut map_path = config.paths.songs();
-            map_path.push(format!("{mapset_id}/{map_osu_file}"));
+        let compared = comparison.as_ref().map(|compared| &compared.replay);
 
-            let video_title = match create_title(&replay, map_path, &title).await {
-                Ok(title) => title,
-                Err(err) => {
-                    let err = err.wrap_err("failed to create title");
-                    warn!("{err:?}");
+        let video_title = match create_title(&replay, compared, &options, &map_path, &title).await {
+            Ok(title) => title,
+            Err(err) => {
+                let err = err.wrap_err("failed to create title");
+                warn!("{err:?}");
+                report_failure(&ctx, &job_log, user, guild, &err, status_msg).await;
 
-                    let content = "There was an error while trying to create the video title";
-                    let _ = input_channel.error(&ctx, content).await;
+                let content = "There was an error while trying to create the video title";
+                let _ = input_channel.error(&ctx, content).await;
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
+                ctx.stats.renders.failed(FailureReason::Other);
+                ctx.replay_queue.reset_peek().await;
+                return;
+            }
+        };
+
+        let mut replays = vec![path];
+        replays.extend(comparison.as_ref().map(|compared| compared.path.clone()));
+
+        // Replays that other entries still render are kept regardless
+        if !config.keep_replays {
+            let mut kept = Vec::new();
+
+            for replay_path in replays.drain(..) {
+                if ctx.replay_queue.replay_count(&replay_path).await > 1 {
+                    kept.push(replay_path);
+                } else if let Err(err) = fs::remove_file(&replay_path) {
+                    warn!("failed to remove replay file {replay_path:?}: {err}");
                 }
-            };
+            }
+
+            replays = kept;
+        }
 
-            let mut file_path = config.paths.replays();
-            file_path.push(format!("{filename}.mp4"));
+        // Stored before uploading so that a failed upload can be retried
+        // through `/reupload` instead of wasting the render
+        let output = RenderOutput::new(
+            user,
+            Some(recipient).filter(|&recipient| recipient != user),
+            file_path.clone(),
+            video_title.clone(),
+            replays,
+        );
+        ctx.render_outputs.insert(id, output);
+
+        info!("Started upload to shisha.mezo.xyz");
+        job_log.line("Started upload");
+        ctx.replay_queue.set_status(ReplayStatus::Uploading).await;
+
+        let upload_fut = ctx.client().upload_video(&video_title, user, &file_path);
+
+        // The video is kept for `/reupload` if the upload is aborted
+        let upload_res = tokio::select! {
+            res = upload_fut => res,
+            _ = ctx.replay_queue.cancelled() => {
+                info!("Kept video of job {id} at {file_path:?} for a re-upload");
+                abort_render(&ctx, &job_log, input_channel, user, status_msg).await;
+
+                return;
+            }
+        };
 
-            info!("Started upload to shisha.mezo.xyz");
-            ctx.replay_queue.set_status(ReplayStatus::Uploading).await;
+        let upload_res = match upload_res {
+            Ok(res) if res.error == 1 => Err(eyre!("failed to upload: `{}`", res.text)),
+            Ok(res) => Ok(res.text),
+            Err(err) => Err(err.wrap_err("failed to upload file")),
+        };
 
-            let upload_fut = ctx.client().upload_video(&video_title, user, file_path);
+        let link = match upload_res {
+            Ok(link) => link,
+            Err(err) => {
+                warn!("{err:?}");
+                report_failure(&ctx, &job_log, user, guild, &err, status_msg).await;
 
-            let link = match upload_fut.await {
-                Ok(res) if res.error == 1 => {
-                    let err = format!("failed to upload: `{}`", res.text);
-                    warn!("{err}");
+                info!("Kept video of job {id} at {file_path:?} for a re-upload");
+                job_log.line(format_args!("Kept video at {file_path:?}"));
 
-                    let _ = input_channel.error(&ctx, err).await;
+                let content = format!(
+                    "Failed to upload the video.\n\
+                    It was kept so you can try again later with `/reupload id:{id}`"
+                );
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
-                }
-                Ok(res) => res.text,
-                Err(err) => {
-                    let err = err.wrap_err("failed to upload file");
-                    warn!("{err:?}");
+                let _ = input_channel.error(&ctx, content).await;
 
-                    let content = "Failed to upload file";
-                    let _ = input_channel.error(&ctx, content).await;
+                ctx.stats.renders.failed(FailureReason::Upload);
+                ctx.replay_queue.reset_peek().await;
+                return;
+            }
+        };
 
-                    ctx.replay_queue.reset_peek().await;
-                    continue;
+        info!("Finished upload to shisha.mezo.xyz");
+        job_log.line(format_args!("Finished upload: {link}"));
+
+        let skin_warning = broken_skin.map(|(skin, _)| {
+            format!(
+                "\n:warning: The skin `{skin}` could not be loaded so the default skin was used"
+            )
+        });
+
+        let mut content = format!("<@{recipient}> your replay is ready! {link}");
+
+        if recipient != user {
+            let _ = write!(content, "\nSubmitted by <@{user}>");
+        }
+
+        if let Some(ref warning) = skin_warning {
+            content.push_str(warning);
+        }
+
+        let compact = ctx.compact_embeds(recipient, guild);
+        let mut embed = result_embed(&replay, compared, &options, &title, &link, compact);
+
+        if let Some(note) = note {
+            embed = embed.description(note);
+        }
+
+        if let Some(ref render_for) = render_for {
+            let author = AuthorBuilder::new(format!("Rendered for {}", render_for.name))
+                .icon_url(&render_for.avatar_url);
+
+            embed = embed.author(author);
+        }
+
+        // Compact embeds go without the image
+        let cover = match mapset_id.filter(|_| !embed.is_compact()) {
+            Some(mapset_id) => mapset_cover(&ctx, mapset_id).await,
+            None => None,
+        };
+
+        if cover.is_some() {
+            embed = embed.image(format!("attachment://{COVER_FILENAME}"));
+        }
+
+        let mut builder = MessageBuilder::new().content(content).embed(embed);
+
+        if let Some(cover) = cover {
+            builder = builder.attachment(COVER_FILENAME, cover);
+        }
+
+        // Only renders that were requested through an interaction can be ephemeral
+        let ephemeral = interaction_token.is_some()
+            && guild
+                .and_then(|guild| ctx.guild_settings(guild, |s| s.ephemeral_results))
+                .unwrap_or(false);
+
+        // Long renders may outlast the interaction token
+        let token =
+            interaction_token.filter(|_| ephemeral && queued_at.elapsed() < TOKEN_USABLE_FOR);
+
+        let ephemeral_res = match token {
+            Some(ref token) => {
+                Some(send_result(&ctx, ResultTarget::Ephemeral(token), &builder).await)
+            }
+            None => None,
+        };
+
+        // Webhooks don't support ephemeral messages so they only apply to public results
+        let webhook = guild
+            .filter(|_| !ephemeral)
+            .and_then(|guild| ctx.guild_settings(guild, |s| s.result_webhook.clone()))
+            .flatten();
+
+        let webhook_res = match webhook {
+            Some(ref url) => Some(send_webhook_result(&ctx, url, &builder).await),
+            None => None,
+        };
+
+        let res = match webhook_res {
+            Some(Ok(())) => Ok(()),
+            Some(Err(err)) => {
+                warn!(
+                    "{:?}",
+                    err.wrap_err("failed to send result through webhook")
+                );
+                info!("Posting result in the output channel instead");
+
+                send_result(&ctx, ResultTarget::Channel(output_channel), &builder).await
+            }
+            None => match ephemeral_res {
+                Some(Ok(())) => Ok(()),
+                fallback => {
+                    if ephemeral {
+                        match fallback {
+                            Some(Err(err)) => warn!("Failed to send ephemeral result: {err}"),
+                            _ => info!("Interaction token is about to expire"),
+                        }
+
+                        info!("Posting result in the output channel instead");
+
+                        // The interaction's response can't be referred to
+                        // so the job id has to do
+                        let mut content =
+                            format!("<@{recipient}> your replay from job `{id}` is ready! {link}");
+
+                        if let Some(ref warning) = skin_warning {
+                            content.push_str(warning);
+                        }
+
+                        builder = builder.content(content);
+                    }
+
+                    send_result(&ctx, ResultTarget::Channel(output_channel), &builder).await
                 }
-            };
+            },
+        };
 
-            info!("Finished upload to shisha.mezo.xyz");
+        if let Err(err) = res {
+            let err = err.wrap_err("failed to send video link");
+            warn!("{err:?}");
+            report_failure(&ctx, &job_log, user, guild, &err, None).await;
 
-            let content = format!("<@{user}> your replay is ready! {link}");
+            // The video is uploaded already so at least let the user know where
+            let content = format!("Failed to post your render: {link}");
+
+            if let Err(err) = dm_user(&ctx, recipient, &content).await {
+                warn!("{:?}", err.wrap_err("failed to DM video link"));
+            }
+        }
+
+        let (showcase_channels, mut failed_channels) = if ephemeral {
+            (Vec::new(), Vec::new())
+        } else {
+            showcase_channels(&ctx, output_channel)
+        };
+
+        if !showcase_channels.is_empty() {
+            let content = format!("{video_title}\n{link}");
             let builder = MessageBuilder::new().content(content);
 
-            if let Err(err) = output_channel.create_message(&ctx, &builder).await {
-                let err = Report::from(err).wrap_err("failed to send video link");
-                warn!("{err:?}");
+            for channel in showcase_channels {
+                if let Err(err) = channel.create_message(&ctx, &builder).await {
+                    let err = Report::from(err)
+                        .wrap_err(format!("failed to send video link to showcase {channel}"));
+                    warn!("{err:?}");
+
+                    failed_channels.push(channel);
+                }
+            }
+        }
+
+        if !failed_channels.is_empty() {
+            let mut content = "Failed to send the video to showcase channel".to_owned();
+
+            if failed_channels.len() > 1 {
+                content.push('s');
+            }
+
+            for (i, channel) in failed_channels.iter().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                let _ = write!(content, "{sep}<#{channel}>");
             }
 
-            ctx.replay_queue.reset_peek().await;
+            let _ = input_channel.error(&ctx, content).await;
+        }
+
+        // Ephemeral results are not meant to be found by others
+        if let Some(guild) = guild.filter(|_| !ephemeral) {
+            let record = RenderRecord::new(recipient, title, mapset_id, link);
+
+            if let Err(err) = ctx.render_history.push(guild, record) {
+                warn!("{:?}", err.wrap_err("failed to store render record"));
+            }
+        }
+
+        if let Some(status_msg) = status_msg {
+            status_msg.update(&ctx, Msg::RenderFinished).await;
+        }
+
+        job_log.line("Render completed");
+
+        ctx.stats.renders.completed(start.elapsed(), user, guild);
+        ctx.stats
+            .render_duration
+            .observe(start.elapsed().as_secs_f64());
+
+        ctx.replay_queue.reset_peek().await;
+    }
+}
+
+/// Let the user know that the bot owner aborted their render and pop it
+async fn abort_render(
+    ctx: &Context,
+    job_log: &JobLog,
+    input_channel: Id<ChannelMarker>,
+    user: Id<UserMarker>,
+    status_msg: Option<&StatusMessage<'_>>,
+) {
+    info!("Render was aborted");
+    job_log.line("Render was aborted");
+
+    if let Some(status_msg) = status_msg {
+        status_msg.update(ctx, Msg::RenderAborted).await;
+    }
+
+    let content = format!(
+        "<@{user}> your render was cancelled by the bot owner.\n\
+        Feel free to submit it again later."
+    );
+    let _ = input_channel.error(ctx, content).await;
+
+    ctx.replay_queue.reset_peek().await;
+}
+
+/// Report the failure in the error channel and the job's log,
+/// and mark the render as failed in its announcement
+async fn report_failure(
+    ctx: &Context,
+    job_log: &JobLog,
+    user: Id<UserMarker>,
+    guild: Option<Id<GuildMarker>>,
+    err: &Report,
+    status_msg: Option<&StatusMessage<'_>>,
+) {
+    job_log.line(format_args!("Failed: {err:?}"));
+
+    if let Some(status_msg) = status_msg {
+        status_msg.update(ctx, Msg::RenderFailed).await;
+    }
+
+    let report = ErrorReport {
+        origin: "render",
+        user: Some(user),
+        guild,
+        err,
+    };
+
+    ctx.report_error(report).await;
+}
+
+#[derive(Copy, Clone)]
+enum ResultTarget<'t> {
+    Channel(Id<ChannelMarker>),
+    /// Follow-up to the interaction with the given token that only the requester can see
+    Ephemeral(&'t str),
+}
+
+/// Send the result message, retrying on ratelimits and transient errors
+async fn send_result(
+    ctx: &Context,
+    target: ResultTarget<'_>,
+    builder: &MessageBuilder<'_>,
+) -> Result<()> {
+    let mut backoff = ExponentialBackoff::new(2).factor(1000).max_delay(30_000);
+    let mut attempt = 1;
+
+    loop {
+        let res = match target {
+            ResultTarget::Channel(channel) => channel.create_message(ctx, builder).await,
+            ResultTarget::Ephemeral(token) => ephemeral_followup(ctx, token, builder)?.await,
+        };
+
+        let err = match res {
+            Ok(_) => return Ok(()),
+            Err(err) => err,
+        };
+
+        let delay = match retry_delay(&err) {
+            Some(_) if attempt >= SEND_ATTEMPTS => return Err(err.into()),
+            Some(RetryDelay::After(delay)) => delay,
+            Some(RetryDelay::Backoff) => backoff.next().unwrap_or_default(),
+            None => return Err(err.into()),
+        };
+
+        warn!(
+            "Failed to send result (attempt {attempt}/{SEND_ATTEMPTS}), \
+            retrying in {delay:?}: {err}"
+        );
+        attempt += 1;
+        time::sleep(delay).await;
+    }
+}
+
+async fn send_webhook_result(ctx: &Context, url: &str, builder: &MessageBuilder<'_>) -> Result<()> {
+    let content = builder.content.as_deref();
+    let embed = builder.embed.as_ref();
+    let file = builder.attachment.as_ref();
+
+    ctx.client()
+        .execute_webhook(url, content, embed, file)
+        .await
+}
+
+/// Fails if the message exceeds discord's limits, e.g. due to a long title
+fn ephemeral_followup(
+    ctx: &Context,
+    token: &str,
+    builder: &MessageBuilder<'_>,
+) -> Result<ResponseFuture<Message>> {
+    let client = ctx.interaction();
+    let mut req = client.create_followup(token).flags(MessageFlags::EPHEMERAL);
+
+    if let Some(ref content) = builder.content {
+        req = req.content(content.as_ref()).wrap_err("invalid content")?;
+    }
+
+    if let Some(ref embed) = builder.embed {
+        req = req
+            .embeds(slice::from_ref(embed))
+            .wrap_err("invalid embed")?;
+    }
+
+    if let Some(ref attachment) = builder.attachment {
+        req = req
+            .attachments(slice::from_ref(attachment))
+            .wrap_err("invalid attachment")?;
+    }
+
+    Ok(req.exec())
+}
+
+enum RetryDelay {
+    /// Discord specified how long to wait
+    After(Duration),
+    Backoff,
+}
+
+/// Returns `None` if the error is not worth retrying
+fn retry_delay(err: &HttpError) -> Option<RetryDelay> {
+    match err.kind() {
+        ErrorType::Response {
+            error: ApiError::Ratelimited(ratelimited),
+            ..
+        } => {
+            let delay = Duration::from_secs_f64(ratelimited.retry_after);
+
+            Some(RetryDelay::After(delay))
+        }
+        ErrorType::Response { status, .. } if status.is_server_error() => Some(RetryDelay::Backoff),
+        ErrorType::RequestError | ErrorType::RequestTimedOut => Some(RetryDelay::Backoff),
+        _ => None,
+    }
+}
+
+async fn dm_user(ctx: &Context, user: Id<UserMarker>, content: &str) -> Result<()> {
+    let channel = ctx
+        .http
+        .create_private_channel(user)
+        .exec()
+        .await
+        .context("failed to create private channel")?
+        .model()
+        .await
+        .context("failed to deserialize private channel")?;
+
+    channel
+        .id
+        .plain_message(ctx, content)
+        .await
+        .context("failed to send DM")?;
+
+    Ok(())
+}
+
+/// Returns the showcase channels of the output channel's server in which
+/// the bot can send messages, and those in which it can't.
+fn showcase_channels(
+    ctx: &Context,
+    output_channel: Id<ChannelMarker>,
+) -> (Vec<Id<ChannelMarker>>, Vec<Id<ChannelMarker>>) {
+    let guild = match ctx.cache.channel(output_channel, |c| c.guild_id) {
+        Ok(Some(guild)) => guild,
+        Ok(None) | Err(_) => return (Vec::new(), Vec::new()),
+    };
+
+    let channels = ctx
+        .guild_settings(guild, |server| {
+            server
+                .showcase_channels
+                .iter()
+                .copied()
+                .filter(|&channel| channel != output_channel)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let current_user = match ctx.cache.current_user(|user| user.id) {
+        Ok(user) => user,
+        Err(err) => {
+            warn!("{err:?}");
+
+            return (Vec::new(), channels);
+        }
+    };
+
+    channels.into_iter().partition(|&channel| {
+        ctx.cache
+            .get_channel_permissions(current_user, channel, Some(guild))
+            .contains(Permissions::SEND_MESSAGES)
+    })
+}
+
+/// Message posted in the output channel when a render starts,
+/// edited with the new status as the render progresses
+/// Announcement of a render in the output channel that's kept up to date
+struct StatusMessage<'a> {
+    msg: Message,
+    guild: Option<Id<GuildMarker>>,
+    players: Option<&'a str>,
+    map_path: &'a Path,
+    user: Id<UserMarker>,
+}
+
+impl StatusMessage<'_> {
+    async fn update(&self, ctx: &Context, status: Msg) {
+        let content = render_status_message(
+            ctx,
+            self.guild,
+            status,
+            self.players,
+            self.map_path,
+            self.user,
+        );
+        let builder = MessageBuilder::new().embed(content);
+
+        if let Err(err) = self.msg.update(ctx, &builder).await {
+            let err = Report::from(err).wrap_err("failed to update render status message");
+            warn!("{err:?}");
         }
     }
 }
 
-async fn read_danser_progress(ctx: &Context, reader: BufReader<ChildStdout>) {
-    async fn inner(ctx: &Context, reader: BufReader<ChildStdout>) -> Result<()> {
+fn render_status_message(
+    ctx: &Context,
+    guild: Option<Id<GuildMarker>>,
+    status: Msg,
+    players: Option<&str>,
+    map_path: &Path,
+    user: Id<UserMarker>,
+) -> String {
+    let map = map_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let status = ctx.guild_locale(guild).get(status);
+
+    match players {
+        Some(players) => format!("{status}: **{players}** - {map}\nRequested by <@{user}>"),
+        None => format!("{status}: {map}\nRequested by <@{user}>"),
+    }
+}
+
+/// Whether danser produced a non-empty video file
+fn video_written(path: &Path) -> bool {
+    fs::metadata(path).map_or(false, |metadata| metadata.len() > 0)
+}
+
+/// Write the resolved settings of a render into
+/// a separate settings file and return its name
+fn store_render_settings(settings: &DanserSettings) -> Result<String> {
+    const NAME: &str = "render_override";
+
+    let mut path = BotConfig::get().paths.danser().to_owned();
+    path.push(format!("settings/{NAME}.json"));
+
+    let file = fs::File::create(&path).with_context(|| format!("failed to create {path:?}"))?;
+
+    serde_json::to_writer(file, settings)
+        .with_context(|| format!("failed to serialize settings into {path:?}"))?;
+
+    Ok(NAME.to_owned())
+}
+
+async fn read_danser_progress(ctx: &Context, reader: BufReader<ChildStdout>, job_log: &JobLog) {
+    async fn inner(ctx: &Context, reader: BufReader<ChildStdout>, job_log: &JobLog) -> Result<()> {
         let mut lines = reader.lines();
         let mut started_encoding = false;
 
@@ -318,6 +1140,7 @@ async fn read_danser_progress(ctx: &Context, reader: BufReader<ChildStdout>) {
 
             if let Some(line) = trimmed_line {
                 debug!("[DANSER]: {line}");
+                job_log.line(format_args!("[DANSER]: {line}"));
             }
 
             let line = match line_opt {
@@ -347,7 +1170,7 @@ async fn read_danser_progress(ctx: &Context, reader: BufReader<ChildStdout>) {
         }
     }
 
-    if let Err(err) = inner(ctx, reader).await {
+    if let Err(err) = inner(ctx, reader, job_log).await {
         error!("{err:?}");
     }
 
@@ -377,6 +1200,31 @@ impl Display for MapsetDownloadError {
     }
 }
 
+impl MapsetDownloadError {
+    const GENERIC: &'static str =
+        "Failed to download map. Mirrors are likely down, try again later.";
+
+    /// Explanation for the user based on why the mirrors failed
+    fn message(&self) -> &'static str {
+        let kitsu = DownloadFailure::from_report(&self.kitsu);
+        let chimu = DownloadFailure::from_report(&self.chimu);
+
+        match (kitsu, chimu) {
+            (DownloadFailure::NotFound, DownloadFailure::NotFound) => {
+                "None of the mirrors have the mapset, it might be unsubmitted or deleted"
+            }
+            (DownloadFailure::Ratelimited, _) | (_, DownloadFailure::Ratelimited) => {
+                "The mirrors are receiving too many requests right now, try again in a minute"
+            }
+            (DownloadFailure::ErrorPage, DownloadFailure::ErrorPage) => {
+                "The mirrors kept responding with an error page instead of the mapset, \
+                they're likely busy so try again later"
+            }
+            _ => Self::GENERIC,
+        }
+    }
+}
+
 impl StdError for MapsetDownloadError {
     #[inline]
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
@@ -384,8 +1232,51 @@ impl StdError for MapsetDownloadError {
     }
 }
 
-async fn download_mapset(ctx: &Context, mapset_id: u32) -> Result<()> {
-    let bytes = request_mapset(ctx, mapset_id).await?;
+/// Makes sure the mapset's folder contains the difficulty with the given hash,
+/// downloading the mapset from a mirror if necessary.
+///
+/// Returns the file name of the matching .osu file or `None` if
+/// no mirror provided a mapset containing the difficulty.
+async fn prepare_mapset(ctx: &Context, mapset_id: u32, hash: &str) -> Result<Option<String>> {
+    if let Some(osu_file) = find_osu_file(mapset_id, hash)? {
+        debug!("Mapset {mapset_id} is already stored, skipping download");
+
+        return Ok(Some(osu_file));
+    }
+
+    let kitsu = match ctx.client().download_kitsu_mapset(mapset_id).await {
+        Ok(bytes) => match extract_mapset(bytes, mapset_id, hash) {
+            Ok(Some(osu_file)) => return Ok(Some(osu_file)),
+            Ok(None) => {
+                warn!("mapset {mapset_id} from kitsu does not contain a map with hash `{hash}`");
+
+                None
+            }
+            Err(err) => Some(err),
+        },
+        Err(err) => Some(err),
+    };
+
+    let chimu = match ctx.client().download_chimu_mapset(mapset_id).await {
+        Ok(bytes) => match extract_mapset(bytes, mapset_id, hash) {
+            Ok(Some(osu_file)) => return Ok(Some(osu_file)),
+            Ok(None) => {
+                warn!("mapset {mapset_id} from chimu does not contain a map with hash `{hash}`");
+
+                None
+            }
+            Err(err) => Some(err),
+        },
+        Err(err) => Some(err),
+    };
+
+    match (kitsu, chimu) {
+        (Some(kitsu), Some(chimu)) => Err(Report::from(MapsetDownloadError { kitsu, chimu })),
+        _ => Ok(None),
+    }
+}
+
+fn extract_mapset(bytes: Bytes, mapset_id: u32, hash: &str) -> Result<Option<String>> {
     let cursor = Cursor::new(bytes);
 
     let mut archive = ZipArchive::new(cursor).context("failed to create zip archive")?;
@@ -395,25 +1286,207 @@ async fn download_mapset(ctx: &Context, mapset_id: u32) -> Result<()> {
 
     archive
         .extract(&out_path)
-        .with_context(|| format!("failed to extract zip archive at `{out_path:?}`"))
+        .with_context(|| format!("failed to extract zip archive at `{out_path:?}`"))?;
+
+    find_osu_file(mapset_id, hash)
 }
 
-async fn request_mapset(ctx: &Context, mapset_id: u32) -> Result<Bytes> {
-    let kitsu = match ctx.client().download_kitsu_mapset(mapset_id).await {
-        Ok(bytes) => return Ok(bytes),
-        Err(err) => err,
+/// Search the mapset's folder for a .osu file whose MD5 hash matches the given one.
+fn find_osu_file(mapset_id: u32, hash: &str) -> Result<Option<String>> {
+    let mut mapset_dir = BotConfig::get().paths.songs();
+    mapset_dir.push(mapset_id.to_string());
+
+    if !mapset_dir.exists() {
+        return Ok(None);
+    }
+
+    let entries = fs::read_dir(&mapset_dir)
+        .with_context(|| format!("failed to read mapset dir at {mapset_dir:?}"))?;
+
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed to read entry of {mapset_dir:?}"))?;
+
+        let file_name = entry.file_name();
+
+        let file_name = match file_name.to_str().filter(|name| name.ends_with(".osu")) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let path = entry.path();
+        let bytes = fs::read(&path).with_context(|| format!("failed to read file at {path:?}"))?;
+        let file_hash = format!("{:x}", md5::compute(bytes));
+
+        if file_hash.eq_ignore_ascii_case(hash) {
+            debug!("Found map with hash `{hash}` at {path:?}");
+
+            return Ok(Some(file_name.to_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+const COVER_FILENAME: &str = "cover.jpg";
+
+/// Name of the cover within the mapset's folder of the map cache
+const CACHED_COVER: &str = "shishabot-cover.jpg";
+
+/// The mapset's cover from the map cache or downloaded, `None` if it's unavailable
+async fn mapset_cover(ctx: &Context, mapset_id: u32) -> Option<Vec<u8>> {
+    let mut path = BotConfig::get().paths.songs();
+    path.push(mapset_id.to_string());
+    path.push(CACHED_COVER);
+
+    if let Ok(bytes) = fs::read(&path) {
+        return Some(bytes);
+    }
+
+    let bytes = match ctx.client().get_mapset_cover(mapset_id).await {
+        Ok(bytes) => bytes.to_vec(),
+        // Not every mapset has a cover
+        Err(err) if is_not_found(&err) => {
+            debug!("Mapset {mapset_id} has no cover");
+
+            return None;
+        }
+        Err(err) => {
+            warn!("{:?}", err.wrap_err("failed to get mapset cover"));
+
+            return None;
+        }
     };
 
-    let chimu = match ctx.client().download_chimu_mapset(mapset_id).await {
-        Ok(bytes) => return Ok(bytes),
-        Err(err) => err,
+    // The folder only exists if the mapset itself is cached
+    if path.parent().map_or(false, Path::exists) {
+        if let Err(err) = fs::write(&path, &bytes) {
+            warn!("failed to cache cover at {path:?}: {err}");
+        }
+    }
+
+    Some(bytes)
+}
+
+/// How often sending the result message is attempted
+const SEND_ATTEMPTS: usize = 4;
+
+/// Interaction tokens are valid for 15 minutes. The margin accounts for the
+/// time between receiving the interaction and queueing the replay.
+const TOKEN_USABLE_FOR: Duration = Duration::from_secs(12 * 60);
+
+fn result_embed(
+    replay: &ReplaySlim,
+    compared: Option<&ReplaySlim>,
+    options: &RenderOptions,
+    map_title: &str,
+    link: &str,
+    compact: bool,
+) -> EmbedBuilder {
+    let embed = EmbedBuilder::new().compact(compact);
+
+    let speed_field = options.speed.map(|speed| EmbedField {
+        inline: true,
+        name: "Speed".to_owned(),
+        value: speed.label(),
+    });
+
+    if let Some(compared) = compared {
+        let field = |replay: &ReplaySlim, i: usize| EmbedField {
+            inline: false,
+            name: format!(
+                "{i}. {}",
+                replay.player_name.as_deref().unwrap_or("<unknown player>")
+            ),
+            value: format!(
+                "`+{mods}` • {acc}% • {combo}x • {misses} misses",
+                mods = mods_string(replay.mods),
+                acc = replay.accuracy(),
+                combo = replay.max_combo,
+                misses = replay.count_miss,
+            ),
+        };
+
+        return embed
+            .title(format!(
+                "{} on {map_title}",
+                comparison_label(replay, compared)
+            ))
+            .url(link)
+            .fields(
+                [field(replay, 1), field(compared, 2)]
+                    .into_iter()
+                    .chain(speed_field)
+                    .collect(),
+            );
+    }
+
+    let player = replay.player_name.as_deref().unwrap_or("<unknown player>");
+
+    let compact_fields = || {
+        let value = format!(
+            "`+{mods}` • {acc}% • {combo}x • {misses} misses",
+            mods = mods_string(replay.mods),
+            acc = replay.accuracy(),
+            combo = replay.max_combo,
+            misses = replay.count_miss,
+        );
+
+        vec![EmbedField {
+            inline: false,
+            name: "Play".to_owned(),
+            value,
+        }]
+    };
+
+    let full_fields = || {
+        vec![
+            EmbedField {
+                inline: true,
+                name: "Mods".to_owned(),
+                value: mods_string(replay.mods),
+            },
+            EmbedField {
+                inline: true,
+                name: "Accuracy".to_owned(),
+                value: format!("{}%", replay.accuracy()),
+            },
+            EmbedField {
+                inline: true,
+                name: "Combo".to_owned(),
+                value: format!("{}x", replay.max_combo),
+            },
+            EmbedField {
+                inline: true,
+                name: "Misses".to_owned(),
+                value: replay.count_miss.to_string(),
+            },
+        ]
     };
 
-    Err(Report::from(MapsetDownloadError { kitsu, chimu }))
+    let mut fields = embed.detail(full_fields, compact_fields);
+    fields.extend(speed_field);
+
+    // The stats above stay those of the actual play
+    fields.extend(options.visual_mods.map(|mods| EmbedField {
+        inline: true,
+        name: "Visual mods".to_owned(),
+        value: format!("{} (overridden)", mods.label()),
+    }));
+
+    embed
+        .title(format!("{player} on {map_title}"))
+        .url(link)
+        .fields(fields)
 }
 
-async fn create_title(replay: &ReplaySlim, map_path: PathBuf, map_title: &str) -> Result<String> {
-    let stars = Beatmap::from_path(&map_path)
+async fn create_title(
+    replay: &ReplaySlim,
+    compared: Option<&ReplaySlim>,
+    options: &RenderOptions,
+    map_path: &Path,
+    map_title: &str,
+) -> Result<String> {
+    let stars = Beatmap::from_path(map_path)
         .await
         .with_context(|| format!("failed to parse map at {map_path:?}"))?
         .stars()
@@ -423,54 +1496,31 @@ async fn create_title(replay: &ReplaySlim, map_path: PathBuf, map_title: &str) -
 
     // let map_title = get_title()?;
     let stars = (stars * 100.0).round() / 100.0;
-    let player = replay.player_name.as_deref().unwrap_or("<unknown player>");
-    let acc = replay.accuracy();
 
-    let mods = match GameMods::from_bits(replay.mods) {
-        Some(GameMods::NoMod) | None => String::new(),
-        Some(mods) => format!("+{mods} "),
+    let mut map_title = match options.speed {
+        Some(speed) => format!("{map_title} ({}x)", speed.rate),
+        None => map_title.to_owned(),
     };
 
-    Ok(format!("[{stars}⭐] {player} | {map_title} {mods}{acc}%"))
-}
-
-async fn get_beatmap_osu_file(mapset_id: u32, map_without_artist: &str) -> Result<String> {
-    let mut items_dir = BotConfig::get().paths.songs();
-    items_dir.push(mapset_id.to_string());
-
-    let items = fs::read_dir(&items_dir)
-        .with_context(|| format!("failed to read items dir at {items_dir:?}"))?;
-
-    let mut max_similarity = 0.0;
-    let mut final_file_name = String::new();
-
-    for entry in items {
-        match entry {
-            Ok(entry) => {
-                let file_name = entry.file_name();
-
-                if let Some(file_name) = file_name.to_str().filter(|name| name.ends_with(".osu")) {
-                    debug!("COMPARING: {map_without_artist} WITH: {file_name}");
-
-                    let similarity = levenshtein_similarity(map_without_artist, file_name);
+    if let Some(mods) = options.visual_mods {
+        let _ = write!(map_title, " [{} visuals]", mods.label());
+    }
 
-                    if similarity > max_similarity {
-                        max_similarity = similarity;
-                        final_file_name = file_name.to_owned();
-                    }
-                }
-            }
-            Err(err) => {
-                let context = format!("there was an error while reading files in {items_dir:?}");
+    if let Some(compared) = compared {
+        let players = comparison_label(replay, compared);
 
-                return Err(Report::from(err).wrap_err(context));
-            }
-        }
+        return Ok(format!("[{stars}⭐] {players} | {map_title}"));
     }
 
-    debug!("FINAL TITLE: {final_file_name} SIMILARITY: {max_similarity}");
+    let player = replay.player_name.as_deref().unwrap_or("<unknown player>");
+    let acc = replay.accuracy();
 
-    Ok(final_file_name)
+    let mods = match replay.mods {
+        0 => String::new(),
+        mods => format!("+{} ", mods_string(mods)),
+    };
+
+    Ok(format!("[{stars}⭐] {player} | {map_title} {mods}{acc}%"))
 }
 
 fn get_title() -> Result<String> {
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+use crate::core::settings::Recording;
+
+/// Encoding quality of a render.
+///
+/// Higher quality results in larger files and longer encoding times.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, CommandOption, CreateOption, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityPreset {
+    #[option(name = "Fast: small file, quick render", value = "fast")]
+    Fast,
+    #[default]
+    #[option(name = "Balanced", value = "balanced")]
+    Balanced,
+    #[option(name = "High: large file, slow render", value = "high")]
+    High,
+}
+
+impl QualityPreset {
+    pub fn name(self) -> &'static str {
+        match self {
+            QualityPreset::Fast => "Fast",
+            QualityPreset::Balanced => "Balanced",
+            QualityPreset::High => "High",
+        }
+    }
+
+    /// Constant rate factor of the encoder; lower values mean higher quality
+    fn rate_factor(self) -> i64 {
+        match self {
+            QualityPreset::Fast => 28,
+            QualityPreset::Balanced => 21,
+            QualityPreset::High => 15,
+        }
+    }
+
+    /// Speed preset of the software encoders; slower presets compress better
+    fn speed(self) -> &'static str {
+        match self {
+            QualityPreset::Fast => "veryfast",
+            QualityPreset::Balanced => "faster",
+            QualityPreset::High => "medium",
+        }
+    }
+
+    /// Apply the preset onto the settings of all encoders so
+    /// that it takes effect regardless of the configured one.
+    ///
+    /// Hardware encoders keep their own speed presets since
+    /// their names differ from those of the software encoders.
+    pub fn apply(self, recording: &mut Recording) {
+        let rate_factor = self.rate_factor();
+        let speed = self.speed();
+
+        recording.libx264.rate_control = "crf".to_owned();
+        recording.libx264.crf = rate_factor;
+        recording.libx264.preset = speed.to_owned();
+
+        recording.libx265.rate_control = "crf".to_owned();
+        recording.libx265.crf = rate_factor;
+        recording.libx265.preset = speed.to_owned();
+
+        recording.h264_nvenc.cq = rate_factor;
+        recording.hevc_nvenc.cq = rate_factor;
+        recording.h264_qsv.quality = rate_factor;
+        recording.hevc_qsv.quality = rate_factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_quality_lower_rate_factor() {
+        let fast = QualityPreset::Fast.rate_factor();
+        let balanced = QualityPreset::Balanced.rate_factor();
+        let high = QualityPreset::High.rate_factor();
+
+        assert!(fast > balanced && balanced > high);
+    }
+
+    #[test]
+    fn apply_sets_software_encoders() {
+        let mut recording = Recording::default();
+        QualityPreset::High.apply(&mut recording);
+
+        assert_eq!(recording.libx264.crf, 15);
+        assert_eq!(recording.libx264.preset, "medium");
+        assert_eq!(recording.libx265.rate_control, "crf");
+        assert_eq!(recording.h264_nvenc.cq, 15);
+    }
+}
@@ -0,0 +1,173 @@
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::settings::Recording;
+
+/// Frame size of a render that overrides the one of the danser settings
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    /// Bounds of both the width and the height
+    pub const SIDE: RangeInclusive<u32> = 240..=3840;
+
+    /// Frames larger than 4K take too long to render and encode
+    pub const MAX_PIXELS: u32 = 3840 * 2160;
+
+    /// The longer side may be at most this many times as long as the shorter one,
+    /// otherwise the playfield shrinks to a sliver
+    pub const MAX_RATIO: u32 = 4;
+
+    /// Common resolutions that can be chosen by name
+    pub const PRESETS: [(&'static str, Self); 5] = [
+        ("720p", Self::new(1280, 720)),
+        ("1080p", Self::new(1920, 1080)),
+        ("1440p", Self::new(2560, 1440)),
+        ("vertical", Self::new(1080, 1920)),
+        ("square", Self::new(1080, 1080)),
+    ];
+
+    const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// Parse the name of a preset or a custom resolution such as `1080x1920`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+
+        let preset = Self::PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s));
+
+        if let Some((_, resolution)) = preset {
+            return Ok(*resolution);
+        }
+
+        let invalid = || {
+            format!(
+                "Invalid resolution `{s}`, must be one of {} or of the form `1080x1920`",
+                Self::preset_label()
+            )
+        };
+
+        let (width, height) = s.split_once(['x', 'X', '×']).ok_or_else(invalid)?;
+
+        let width = width.trim().parse().map_err(|_| invalid())?;
+        let height = height.trim().parse().map_err(|_| invalid())?;
+
+        let resolution = Self { width, height };
+        resolution.validate()?;
+
+        Ok(resolution)
+    }
+
+    /// Returns an error message if the resolution can't be rendered
+    pub fn validate(self) -> Result<(), String> {
+        let Self { width, height } = self;
+
+        if !Self::SIDE.contains(&width) || !Self::SIDE.contains(&height) {
+            return Err(format!(
+                "Invalid resolution `{}`, width and height must be between {} and {}",
+                self.label(),
+                Self::SIDE.start(),
+                Self::SIDE.end()
+            ));
+        }
+
+        // Most encoders only support even dimensions due to chroma subsampling
+        if width % 2 != 0 || height % 2 != 0 {
+            return Err(format!(
+                "Invalid resolution `{}`, width and height must be even",
+                self.label()
+            ));
+        }
+
+        if width * height > Self::MAX_PIXELS {
+            return Err(format!(
+                "Resolution `{}` is too large, it may have at most as many pixels as 3840x2160",
+                self.label()
+            ));
+        }
+
+        if width.max(height) > width.min(height) * Self::MAX_RATIO {
+            return Err(format!(
+                "Resolution `{}` is too stretched, one side may be at most {} times the other",
+                self.label(),
+                Self::MAX_RATIO
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Names of the presets
+    pub fn preset_label() -> String {
+        Self::PRESETS
+            .iter()
+            .map(|(name, _)| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn apply(self, recording: &mut Recording) {
+        recording.frame_width = self.width as i32;
+        recording.frame_height = self.height as i32;
+    }
+
+    pub fn label(self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolution() {
+        let expected = Resolution::new(1080, 1920);
+
+        assert_eq!(Resolution::parse("1080x1920"), Ok(expected));
+        assert_eq!(Resolution::parse(" 1080 X 1920 "), Ok(expected));
+        assert_eq!(Resolution::parse("1080×1920"), Ok(expected));
+        assert_eq!(Resolution::parse("Vertical"), Ok(expected));
+
+        assert!(Resolution::parse("1080").is_err());
+        assert!(Resolution::parse("1080x").is_err());
+        assert!(Resolution::parse("-1080x1920").is_err());
+    }
+
+    #[test]
+    fn rejects_unrenderable() {
+        // odd
+        assert!(Resolution::parse("1081x1920").is_err());
+        // too small
+        assert!(Resolution::parse("200x200").is_err());
+        // too many pixels
+        assert!(Resolution::parse("3840x3840").is_err());
+        // too stretched
+        assert!(Resolution::parse("3840x480").is_err());
+
+        assert!(Resolution::parse("3840x960").is_ok());
+    }
+
+    #[test]
+    fn presets_are_valid() {
+        for (_, resolution) in Resolution::PRESETS {
+            assert_eq!(resolution.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn apply_sets_frame_size() {
+        let mut recording = Recording::default();
+        Resolution::new(1080, 1920).apply(&mut recording);
+
+        assert_eq!(recording.frame_width, 1080);
+        assert_eq!(recording.frame_height, 1920);
+    }
+}
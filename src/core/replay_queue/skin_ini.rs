@@ -0,0 +1,382 @@
+use std::{
+    ffi::OsStr,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context as _, Result};
+
+use crate::core::BotConfig;
+
+/// Maximum size in bytes of an uploaded overrides file
+pub const MAX_SIZE: u64 = 16 * 1024;
+
+/// Skins with this prefix only exist for the duration of a single render
+const TEMP_SKIN_PREFIX: &str = ".render-override-";
+
+/// Keys of a skin.ini that override those of a skin for a single render
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SkinIniOverrides {
+    sections: Vec<Section>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Section {
+    name: String,
+    entries: Vec<(String, String)>,
+}
+
+impl SkinIniOverrides {
+    /// Parse the overrides, returning an error message if they're malformed
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut sections: Vec<Section> = Vec::new();
+
+        for (line, i) in content.lines().zip(1..) {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = match name.strip_suffix(']').map(str::trim) {
+                    Some(name) if !name.is_empty() => name,
+                    _ => return Err(format!("Invalid section header `{line}` in line {i}")),
+                };
+
+                let idx = sections
+                    .iter()
+                    .position(|section| section.name.eq_ignore_ascii_case(name));
+
+                // Sections may be repeated, their entries are merged
+                if let Some(idx) = idx {
+                    let section = sections.remove(idx);
+                    sections.push(section);
+                } else {
+                    sections.push(Section {
+                        name: name.to_owned(),
+                        entries: Vec::new(),
+                    });
+                }
+
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) if !key.trim().is_empty() => (key.trim(), value.trim()),
+                _ => return Err(format!("Expected `Key: Value` in line {i}, got `{line}`")),
+            };
+
+            let section = match sections.last_mut() {
+                Some(section) => section,
+                None => return Err(format!("Key `{key}` in line {i} is not within a section")),
+            };
+
+            let entry = section
+                .entries
+                .iter_mut()
+                .find(|(existing, _)| existing.eq_ignore_ascii_case(key));
+
+            match entry {
+                Some((_, existing)) => *existing = value.to_owned(),
+                None => section.entries.push((key.to_owned(), value.to_owned())),
+            }
+        }
+
+        if sections.iter().all(|section| section.entries.is_empty()) {
+            return Err("The overrides don't contain any keys".to_owned());
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Apply the overrides onto the content of a skin.ini.
+    ///
+    /// Overridden keys are replaced in place, new keys are appended to
+    /// their section, and missing sections are appended at the end.
+    pub fn apply(&self, skin_ini: &str) -> String {
+        let mut applied: Vec<Vec<bool>> = self
+            .sections
+            .iter()
+            .map(|section| vec![false; section.entries.len()])
+            .collect();
+
+        let mut output = String::with_capacity(skin_ini.len());
+        let mut current: Option<usize> = None;
+
+        for line in skin_ini.lines() {
+            let trimmed = line.trim();
+
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+                self.append_missing(current, &mut applied, &mut output);
+
+                current = self
+                    .sections
+                    .iter()
+                    .position(|section| section.name.eq_ignore_ascii_case(name.trim()));
+
+                output.push_str(line);
+                output.push('\n');
+
+                continue;
+            }
+
+            let overridden = current
+                .zip(trimmed.split_once(':'))
+                .and_then(|(idx, (key, _))| {
+                    let key = key.trim();
+
+                    self.sections[idx]
+                        .entries
+                        .iter()
+                        .position(|(existing, _)| existing.eq_ignore_ascii_case(key))
+                        .map(|entry_idx| (idx, entry_idx, key))
+                });
+
+            match overridden {
+                Some((idx, entry_idx, key)) => {
+                    let (_, value) = &self.sections[idx].entries[entry_idx];
+                    let _ = writeln!(output, "{key}: {value}");
+                    applied[idx][entry_idx] = true;
+                }
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+
+        self.append_missing(current, &mut applied, &mut output);
+
+        for (idx, section) in self.sections.iter().enumerate() {
+            // Sections of the skin.ini had all their entries applied already
+            if section.entries.is_empty() || applied[idx].iter().any(|&applied| applied) {
+                continue;
+            }
+
+            if !output.is_empty() {
+                output.push('\n');
+            }
+
+            let _ = writeln!(output, "[{}]", section.name);
+            self.append_missing(Some(idx), &mut applied, &mut output);
+        }
+
+        output
+    }
+
+    /// Add the section's entries that were not applied yet to
+    /// the output, in front of its trailing empty lines.
+    fn append_missing(
+        &self,
+        section: Option<usize>,
+        applied: &mut [Vec<bool>],
+        output: &mut String,
+    ) {
+        let idx = match section {
+            Some(idx) => idx,
+            None => return,
+        };
+
+        if applied[idx].iter().all(|&applied| applied) {
+            return;
+        }
+
+        let content_len = output.trim_end().len();
+        let trailing = output.split_off(content_len);
+
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        let entries = self.sections[idx]
+            .entries
+            .iter()
+            .zip(applied[idx].iter_mut());
+
+        for ((key, value), applied) in entries {
+            if !*applied {
+                let _ = writeln!(output, "{key}: {value}");
+                *applied = true;
+            }
+        }
+
+        // The first newline ended the last line of content which was re-added already
+        output.push_str(trailing.strip_prefix('\n').unwrap_or(&trailing));
+    }
+}
+
+/// Whether the skin only exists for the duration of a single render
+pub fn is_temp_skin(name: &OsStr) -> bool {
+    name.to_string_lossy().starts_with(TEMP_SKIN_PREFIX)
+}
+
+/// Copy of a skin with overrides applied to its skin.ini.
+///
+/// The copy is deleted on drop.
+pub struct TempSkin {
+    dir: PathBuf,
+}
+
+impl TempSkin {
+    /// Copy the skin into a new directory inside the skins folder
+    /// and apply the overrides onto the copy's skin.ini.
+    pub fn create(skin: &str, overrides: &SkinIniOverrides, id: u32) -> Result<Self> {
+        let skins_dir = BotConfig::get().paths.skins();
+        let source = skins_dir.join(skin);
+        let dir = skins_dir.join(format!("{TEMP_SKIN_PREFIX}{id}"));
+
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("failed to remove leftover temp skin {dir:?}"))?;
+        }
+
+        let temp_skin = Self { dir };
+
+        // Skins that are built into danser have no folder so
+        // only the overrides are written in that case
+        if source.is_dir() {
+            copy_dir(&source, &temp_skin.dir)?;
+        } else {
+            fs::create_dir_all(&temp_skin.dir)
+                .with_context(|| format!("failed to create directory {:?}", temp_skin.dir))?;
+        }
+
+        let ini_path = fs::read_dir(&temp_skin.dir)
+            .with_context(|| format!("failed to read directory {:?}", temp_skin.dir))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.file_name()
+                    .map_or(false, |name| name.eq_ignore_ascii_case("skin.ini"))
+            })
+            .unwrap_or_else(|| temp_skin.dir.join("skin.ini"));
+
+        let original = match fs::read(&ini_path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => String::new(),
+        };
+
+        fs::write(&ini_path, overrides.apply(&original))
+            .with_context(|| format!("failed to write skin.ini at {ini_path:?}"))?;
+
+        Ok(temp_skin)
+    }
+
+    /// Name of the skin directory
+    pub fn name(&self) -> String {
+        self.dir
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+    }
+}
+
+impl Drop for TempSkin {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_dir_all(&self.dir) {
+            warn!("failed to remove temp skin {:?}: {err}", self.dir);
+        }
+    }
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    fs::create_dir_all(target).with_context(|| format!("failed to create directory {target:?}"))?;
+
+    let entries =
+        fs::read_dir(source).with_context(|| format!("failed to read directory {source:?}"))?;
+
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed to read entry of {source:?}"))?;
+        let path = entry.path();
+        let target = target.join(entry.file_name());
+
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            fs::copy(&path, &target)
+                .with_context(|| format!("failed to copy {path:?} to {target:?}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sections_and_keys() {
+        let overrides = SkinIniOverrides::parse(
+            "// comment\n\
+            [General]\n\
+            CursorRotate: 0\n\
+            \n\
+            [Colours]\n\
+            Combo1 : 255,0,0\n",
+        )
+        .unwrap();
+
+        assert_eq!(overrides.sections.len(), 2);
+        assert_eq!(overrides.sections[0].name, "General");
+        assert_eq!(
+            overrides.sections[1].entries,
+            [("Combo1".to_owned(), "255,0,0".to_owned())]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        assert!(SkinIniOverrides::parse("CursorRotate: 0").is_err());
+        assert!(SkinIniOverrides::parse("[General\nCursorRotate: 0").is_err());
+        assert!(SkinIniOverrides::parse("[General]\nCursorRotate").is_err());
+        assert!(SkinIniOverrides::parse("[General]\n: 0").is_err());
+        assert!(SkinIniOverrides::parse("[General]").is_err());
+    }
+
+    #[test]
+    fn apply_replaces_and_appends() {
+        let overrides = SkinIniOverrides::parse(
+            "[general]\n\
+            cursorrotate: 0\n\
+            CursorExpand: 1\n\
+            [Fonts]\n\
+            HitCircleOverlap: 3\n",
+        )
+        .unwrap();
+
+        let skin_ini = "[General]\n\
+            Name: Test\n\
+            CursorRotate: 1\n\
+            \n\
+            [Colours]\n\
+            Combo1: 0,0,0\n";
+
+        let expected = "[General]\n\
+            Name: Test\n\
+            CursorRotate: 0\n\
+            CursorExpand: 1\n\
+            \n\
+            [Colours]\n\
+            Combo1: 0,0,0\n\
+            \n\
+            [Fonts]\n\
+            HitCircleOverlap: 3\n";
+
+        assert_eq!(overrides.apply(skin_ini), expected);
+    }
+
+    #[test]
+    fn apply_to_empty() {
+        let overrides = SkinIniOverrides::parse("[General]\nCursorRotate: 0").unwrap();
+
+        assert_eq!(overrides.apply(""), "[General]\nCursorRotate: 0\n");
+    }
+
+    #[test]
+    fn temp_skins_are_recognized() {
+        assert!(is_temp_skin(OsStr::new(".render-override-12")));
+        assert!(!is_temp_skin(OsStr::new("render-override")));
+    }
+}
@@ -0,0 +1,79 @@
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+use twilight_interactions::command::{CommandOption, CreateOption};
+
+/// Custom playback speed of a render on top of the replay's mods
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PlaybackSpeed {
+    pub rate: f64,
+    pub pitch: PitchMode,
+}
+
+/// How the music's pitch reacts to a custom playback speed
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, CommandOption, CreateOption, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum PitchMode {
+    #[default]
+    #[option(name = "Corrected: keep the original pitch", value = "corrected")]
+    Corrected,
+    #[option(name = "Nightcore: pitch changes with the speed", value = "nightcore")]
+    Nightcore,
+}
+
+impl PlaybackSpeed {
+    pub const RATE: RangeInclusive<f64> = 0.5..=2.0;
+
+    /// Arguments that make danser play the render at this speed
+    pub fn danser_args(self) -> [String; 4] {
+        let pitch = match self.pitch {
+            PitchMode::Corrected => 1.0,
+            PitchMode::Nightcore => self.rate,
+        };
+
+        [
+            "-speed".to_owned(),
+            self.rate.to_string(),
+            "-pitch".to_owned(),
+            pitch.to_string(),
+        ]
+    }
+
+    pub fn label(self) -> String {
+        let pitch = match self.pitch {
+            PitchMode::Corrected => "pitch corrected",
+            PitchMode::Nightcore => "nightcore",
+        };
+
+        format!("{}x ({pitch})", self.rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nightcore_pitch_follows_rate() {
+        let speed = PlaybackSpeed {
+            rate: 1.25,
+            pitch: PitchMode::Nightcore,
+        };
+
+        assert_eq!(speed.danser_args(), ["-speed", "1.25", "-pitch", "1.25"]);
+        assert_eq!(speed.label(), "1.25x (nightcore)");
+    }
+
+    #[test]
+    fn corrected_pitch_stays() {
+        let speed = PlaybackSpeed {
+            rate: 0.75,
+            pitch: PitchMode::Corrected,
+        };
+
+        assert_eq!(speed.danser_args(), ["-speed", "0.75", "-pitch", "1"]);
+        assert_eq!(speed.label(), "0.75x (pitch corrected)");
+    }
+}
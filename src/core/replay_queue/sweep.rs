@@ -0,0 +1,96 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::{
+    core::{BotConfig, Context},
+    util::ChannelExt,
+};
+
+use super::{job_log, ReplayData, ReplayQueue};
+
+/// How often the queue is checked for stale entries
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+enum StaleReason {
+    Expired,
+    MissingFile,
+}
+
+impl ReplayQueue {
+    /// Periodically drop waiting entries that are too old or whose replay file is gone
+    /// and delete the files of renders that are past their retention period
+    pub fn sweep(ctx: Arc<Context>) {
+        tokio::spawn(Self::async_sweep(ctx));
+    }
+
+    async fn async_sweep(ctx: Arc<Context>) {
+        let max_age = Duration::from_secs(BotConfig::get().queue_max_age);
+
+        let mut interval = interval(SWEEP_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let stale = ctx.replay_queue.remove_stale(max_age).await;
+
+            for (data, reason) in stale {
+                info!(
+                    "Removed stale replay `{}` of user {} from the queue",
+                    data.replay_name(),
+                    data.user
+                );
+
+                let reason = match reason {
+                    StaleReason::Expired => "it has been waiting for too long",
+                    StaleReason::MissingFile => "its replay file could not be found anymore",
+                };
+
+                let content = format!(
+                    "<@{user}> your replay `{name}` was removed from the queue because {reason}.\n\
+                    Feel free to submit it again.",
+                    user = data.user,
+                    name = data.display_title(),
+                );
+
+                let _ = data.input_channel.error(&ctx, content).await;
+            }
+
+            for output in ctx.render_outputs.remove_expired() {
+                output.remove_files();
+            }
+
+            job_log::remove_old_logs(Duration::from_secs(BotConfig::get().output_retention));
+        }
+    }
+
+    async fn remove_stale(&self, max_age: Duration) -> Vec<(ReplayData, StaleReason)> {
+        let mut queue = self.queue.lock().await;
+        let now = Instant::now();
+        let mut stale = Vec::new();
+
+        // The first entry is currently being processed so it's skipped
+        let mut i = 1;
+
+        while i < queue.len() {
+            let reason = if now.duration_since(queue[i].queued_at) > max_age {
+                Some(StaleReason::Expired)
+            } else if !queue[i].path.exists() {
+                Some(StaleReason::MissingFile)
+            } else {
+                None
+            };
+
+            match reason.and_then(|reason| queue.remove(i).map(|data| (data, reason))) {
+                Some(entry) => stale.push(entry),
+                None => i += 1,
+            }
+        }
+
+        stale
+    }
+}
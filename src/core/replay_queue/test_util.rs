@@ -0,0 +1,86 @@
+use rosu_v2::prelude::GameMods;
+
+/// Builds a minimal .osr file for tests
+pub struct OsrBuilder {
+    version: u32,
+    map_hash: String,
+    mods: GameMods,
+    lazer_info: Option<String>,
+}
+
+impl Default for OsrBuilder {
+    fn default() -> Self {
+        Self {
+            version: 20_139_812,
+            map_hash: "d41d8cd98f00b204e9800998ecf8427e".to_owned(),
+            mods: GameMods::NoMod,
+            lazer_info: None,
+        }
+    }
+}
+
+impl OsrBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+
+        self
+    }
+
+    /// An empty hash is written as absent string
+    pub fn map_hash(mut self, map_hash: &str) -> Self {
+        self.map_hash = map_hash.to_owned();
+
+        self
+    }
+
+    pub fn mods(mut self, mods: GameMods) -> Self {
+        self.mods = mods;
+
+        self
+    }
+
+    /// Append a lazer block that contains the given json as score info
+    pub fn lazer_info(mut self, json: &str) -> Self {
+        self.lazer_info = Some(json.to_owned());
+
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+
+        for s in [self.map_hash.as_str(), "mezo", ""] {
+            if s.is_empty() {
+                bytes.push(0x00);
+            } else {
+                bytes.push(0x0b);
+                bytes.push(s.len() as u8);
+                bytes.extend_from_slice(s.as_bytes());
+            }
+        }
+
+        // hit counts, score, max combo, and perfect flag
+        bytes.extend_from_slice(&[0; 6 * 2 + 4 + 2 + 1]);
+        bytes.extend_from_slice(&self.mods.bits().to_le_bytes());
+
+        // empty life bar graph, timestamp, empty replay data, and score id
+        bytes.push(0x00);
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&0_i32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+
+        if let Some(json) = self.lazer_info {
+            let mut compressed = Vec::new();
+            lzma_rs::lzma_compress(&mut json.as_bytes(), &mut compressed).unwrap();
+            bytes.extend_from_slice(&(compressed.len() as i32).to_le_bytes());
+            bytes.extend_from_slice(&compressed);
+        }
+
+        bytes
+    }
+}
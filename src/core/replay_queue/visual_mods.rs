@@ -0,0 +1,147 @@
+use eyre::{ContextCompat, Result};
+use rosu_v2::prelude::GameMods;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{mods_string, parse_mods};
+
+/// Mods that only change what's shown, not where or when objects must be hit
+const VISUAL: GameMods =
+    GameMods::from_bits_truncate(GameMods::Hidden.bits() | GameMods::Flashlight.bits());
+
+/// Visual mods that danser renders instead of those of the replay.
+///
+/// Only the replay file that's handed to danser is modified,
+/// the displayed stats keep using the actual mods of the play.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct VisualMods {
+    bits: u32,
+}
+
+impl VisualMods {
+    /// Parse acronyms such as `HDFL`, `NM` to strip all visual mods
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let bits = parse_mods(s).map_err(|acronym| format!("Unknown mod `{acronym}`"))?;
+        let mods = GameMods::from_bits_truncate(bits);
+
+        if !VISUAL.contains(mods) {
+            return Err(format!(
+                "Only {} can be overridden, other mods change the map \
+                so the replay would no longer line up",
+                Self::allowed_label()
+            ));
+        }
+
+        Ok(Self { bits })
+    }
+
+    /// Acronyms of the mods that can be overridden
+    pub fn allowed_label() -> String {
+        mods_string(VISUAL.bits())
+    }
+
+    /// The replay's mods with its visual mods replaced
+    pub fn apply(self, replay_mods: u32) -> u32 {
+        (replay_mods & !VISUAL.bits()) | self.bits
+    }
+
+    pub fn label(self) -> String {
+        mods_string(self.bits)
+    }
+
+    /// Overwrite the mods within the bytes of a .osr file
+    pub(super) fn patch(self, bytes: &mut [u8]) -> Result<()> {
+        let offset = mods_offset(bytes).context("replay ends before its mods")?;
+        let slice = &mut bytes[offset..offset + 4];
+
+        let mods = u32::from_le_bytes(slice.try_into().unwrap());
+        slice.copy_from_slice(&self.apply(mods).to_le_bytes());
+
+        Ok(())
+    }
+}
+
+// https://osu.ppy.sh/wiki/en/Client/File_formats/Osr_%28file_format%29
+pub(super) fn mods_offset(bytes: &[u8]) -> Option<usize> {
+    // mode and game version
+    let mut offset = 5;
+
+    // map hash, player name, and replay hash
+    for _ in 0..3 {
+        offset = skip_string(bytes, offset)?;
+    }
+
+    // hit counts, score, max combo, and perfect flag
+    offset += 6 * 2 + 4 + 2 + 1;
+
+    (offset + 4 <= bytes.len()).then_some(offset)
+}
+
+pub(super) fn skip_string(bytes: &[u8], mut offset: usize) -> Option<usize> {
+    match *bytes.get(offset)? {
+        0x00 => Some(offset + 1),
+        0x0b => {
+            offset += 1;
+            let mut len = 0;
+            let mut shift = 0;
+
+            loop {
+                let byte = *bytes.get(offset)?;
+                offset += 1;
+                len |= ((byte & 0x7f) as usize) << shift;
+
+                if byte & 0x80 == 0 {
+                    break;
+                }
+
+                shift += 7;
+            }
+
+            Some(offset + len)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::replay_queue::test_util::OsrBuilder;
+
+    use super::*;
+
+    fn replay_bytes(mods: GameMods) -> Vec<u8> {
+        OsrBuilder::new().mods(mods).build()
+    }
+
+    #[test]
+    fn only_visual_mods() {
+        assert!(VisualMods::parse("HDFL").is_ok());
+        assert!(VisualMods::parse("NM").is_ok());
+        assert!(VisualMods::parse("HR").is_err());
+        assert!(VisualMods::parse("HDXY").is_err());
+    }
+
+    #[test]
+    fn replaces_visual_mods_only() {
+        let mods = VisualMods::parse("FL").unwrap();
+        let replay = GameMods::Hidden | GameMods::DoubleTime;
+        let expected = GameMods::Flashlight | GameMods::DoubleTime;
+
+        assert_eq!(mods.apply(replay.bits()), expected.bits());
+    }
+
+    #[test]
+    fn patches_replay_bytes() {
+        let mut bytes = replay_bytes(GameMods::HardRock);
+        VisualMods::parse("HD").unwrap().patch(&mut bytes).unwrap();
+
+        assert_eq!(bytes, replay_bytes(GameMods::HardRock | GameMods::Hidden));
+    }
+
+    #[test]
+    fn truncated_replay() {
+        let mut bytes = replay_bytes(GameMods::HardRock);
+        bytes.truncate(40);
+
+        assert!(VisualMods::parse("HD").unwrap().patch(&mut bytes).is_err());
+    }
+}
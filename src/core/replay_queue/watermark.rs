@@ -0,0 +1,91 @@
+use crate::core::settings::Recording;
+
+/// Maximum amount of characters of a watermark
+pub const MAX_LEN: usize = 64;
+
+/// Returns an error message if the text can't be used as watermark
+pub fn validate(text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("The watermark must not be empty".to_owned());
+    }
+
+    if text.chars().count() > MAX_LEN {
+        return Err(format!(
+            "The watermark must not be longer than {MAX_LEN} characters"
+        ));
+    }
+
+    if text.chars().any(char::is_control) {
+        return Err("The watermark must consist of a single line".to_owned());
+    }
+
+    Ok(())
+}
+
+/// Overlay the text in the bottom right corner of the video
+/// by adding a filter to the ones that danser passes to ffmpeg.
+pub fn apply(text: &str, recording: &mut Recording) {
+    let filter = filter(text);
+
+    if recording.filters.trim().is_empty() {
+        recording.filters = filter;
+    } else {
+        recording.filters = format!("{},{filter}", recording.filters);
+    }
+}
+
+/// The drawtext filter that shows the text.
+///
+/// Sizes are relative to the video's height so that it looks
+/// the same regardless of the resolution.
+fn filter(text: &str) -> String {
+    // The text is escaped for the filter's options and
+    // the whole filter again for the filtergraph
+    let filter = format!(
+        "drawtext=text={}:expansion=none:fontcolor=white@0.7:fontsize=h/30:\
+        borderw=2:bordercolor=black@0.5:x=w-tw-h/50:y=h-th-h/50",
+        escape(text.trim(), &['\\', '\'', ':']),
+    );
+
+    escape(&filter, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// Prefix each of the special characters with a backslash
+fn escape(s: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_text() {
+        let filter = filter("a'b:c,d");
+
+        assert!(filter.starts_with(r"drawtext=text=a\\\'b\\:c\,d:expansion=none:"));
+    }
+
+    #[test]
+    fn appends_to_existing_filters() {
+        let mut recording = Recording::default();
+        apply("mezo", &mut recording);
+        assert!(recording.filters.starts_with("drawtext=text=mezo:"));
+
+        recording.filters = "eq=gamma=1.2".to_owned();
+        apply("mezo", &mut recording);
+        assert!(recording
+            .filters
+            .starts_with("eq=gamma=1.2,drawtext=text=mezo:"));
+    }
+}
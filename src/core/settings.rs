@@ -1,26 +1,133 @@
-use std::collections::HashSet;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    ffi::OsStr,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
 
+use eyre::{Context as _, Result};
 use flurry::HashMap as FlurryMap;
 use serde::{Deserialize, Serialize};
 use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker},
+    marker::{ChannelMarker, GuildMarker, UserMarker},
     Id,
 };
 
-use crate::util::hasher::IntBuildHasher;
+use crate::{
+    core::{locale::Locale, BotConfig, RenderOptions},
+    util::hasher::IntBuildHasher,
+};
 
 type Servers = FlurryMap<Id<GuildMarker>, Server, IntBuildHasher>;
+type Users = FlurryMap<Id<UserMarker>, UserSettings, IntBuildHasher>;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RootSettings {
     #[serde(rename = "Servers", with = "servers")]
     pub servers: Servers,
+    #[serde(rename = "Users", default, with = "users")]
+    pub users: Users,
+}
+
+/// Preferences of a user that apply in every server
+#[derive(Clone, Debug, Default)]
+pub struct UserSettings {
+    /// Whether embeds for the user show fewer fields, `None` to follow the server
+    pub compact_embeds: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct Server {
     pub input_channels: HashSet<Id<ChannelMarker>, IntBuildHasher>,
     pub output_channel: Option<Id<ChannelMarker>>,
+    /// Additional channels in which rendered replays are sent
+    pub showcase_channels: HashSet<Id<ChannelMarker>, IntBuildHasher>,
+    pub locale: Locale,
+    /// Names of commands that can't be used in the server
+    pub disabled_commands: HashSet<String>,
+    /// Text that is overlaid on rendered videos
+    pub watermark: Option<String>,
+    /// Whether replays posted in input channels are rendered without command
+    pub auto_render: bool,
+    /// Options applied to renders of users without their own danser settings
+    pub render_defaults: RenderOptions,
+    /// Whether render results are only shown to the requester
+    pub ephemeral_results: bool,
+    /// Amount of the server's replays that may be queued at once,
+    /// capped by `GUILD_QUEUE_MAX`
+    pub queue_limit: Option<usize>,
+    /// Webhook through which results are posted instead of the output channel.
+    ///
+    /// The url contains the webhook's token so it's neither displayed nor exported.
+    pub result_webhook: Option<String>,
+    /// Whether the setup wizard was posted after the bot joined the server
+    pub setup_prompted: bool,
+    /// Whether a message is posted in the output channel when a render starts
+    pub announce_renders: bool,
+    /// Whether embeds show fewer fields for better readability on mobile
+    pub compact_embeds: bool,
+    /// Whether minor issues of parsed replays are shown when they're queued
+    pub parse_warnings: bool,
+}
+
+/// The portable part of a server's settings that can be exported and
+/// imported into any server, i.e. everything except channel ids.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerExport {
+    pub locale: Locale,
+    pub disabled_commands: HashSet<String>,
+    #[serde(default)]
+    pub watermark: Option<String>,
+    #[serde(default)]
+    pub auto_render: bool,
+    #[serde(default)]
+    pub render_defaults: RenderOptions,
+    #[serde(default)]
+    pub ephemeral_results: bool,
+    #[serde(default)]
+    pub queue_limit: Option<usize>,
+    #[serde(default)]
+    pub announce_renders: bool,
+    #[serde(default)]
+    pub compact_embeds: bool,
+    #[serde(default)]
+    pub parse_warnings: bool,
+}
+
+impl ServerExport {
+    /// Overwrite the server's portable settings, keeping its channels
+    pub fn apply(self, server: &mut Server) {
+        server.locale = self.locale;
+        server.disabled_commands = self.disabled_commands;
+        server.watermark = self.watermark;
+        server.auto_render = self.auto_render;
+        server.render_defaults = self.render_defaults;
+        server.ephemeral_results = self.ephemeral_results;
+        server.queue_limit = self.queue_limit;
+        server.announce_renders = self.announce_renders;
+        server.compact_embeds = self.compact_embeds;
+        server.parse_warnings = self.parse_warnings;
+    }
+}
+
+impl From<&Server> for ServerExport {
+    #[inline]
+    fn from(server: &Server) -> Self {
+        Self {
+            locale: server.locale,
+            disabled_commands: server.disabled_commands.clone(),
+            watermark: server.watermark.clone(),
+            auto_render: server.auto_render,
+            render_defaults: server.render_defaults.clone(),
+            ephemeral_results: server.ephemeral_results,
+            queue_limit: server.queue_limit,
+            announce_renders: server.announce_renders,
+            compact_embeds: server.compact_embeds,
+            parse_warnings: server.parse_warnings,
+        }
+    }
 }
 
 mod servers {
@@ -39,7 +146,10 @@ mod servers {
         Id,
     };
 
-    use crate::util::hasher::IntBuildHasher;
+    use crate::{
+        core::{locale::Locale, RenderOptions},
+        util::hasher::IntBuildHasher,
+    };
 
     use super::{FlurryMap, Server, Servers};
 
@@ -48,6 +158,32 @@ mod servers {
         server_id: Id<GuildMarker>,
         input_channels: HashSet<Id<ChannelMarker>, IntBuildHasher>,
         output_channel: Option<Id<ChannelMarker>>,
+        #[serde(default)]
+        showcase_channels: HashSet<Id<ChannelMarker>, IntBuildHasher>,
+        #[serde(default)]
+        locale: Locale,
+        #[serde(default)]
+        disabled_commands: HashSet<String>,
+        #[serde(default)]
+        watermark: Option<String>,
+        #[serde(default)]
+        auto_render: bool,
+        #[serde(default)]
+        render_defaults: RenderOptions,
+        #[serde(default)]
+        ephemeral_results: bool,
+        #[serde(default)]
+        queue_limit: Option<usize>,
+        #[serde(default)]
+        result_webhook: Option<String>,
+        #[serde(default)]
+        setup_prompted: bool,
+        #[serde(default)]
+        announce_renders: bool,
+        #[serde(default)]
+        compact_embeds: bool,
+        #[serde(default)]
+        parse_warnings: bool,
     }
 
     struct ServersVisitor;
@@ -71,11 +207,37 @@ mod servers {
                         server_id,
                         input_channels,
                         output_channel,
+                        showcase_channels,
+                        locale,
+                        disabled_commands,
+                        watermark,
+                        auto_render,
+                        render_defaults,
+                        ephemeral_results,
+                        queue_limit,
+                        result_webhook,
+                        setup_prompted,
+                        announce_renders,
+                        compact_embeds,
+                        parse_warnings,
                     } = raw;
 
                     let server = Server {
                         input_channels,
                         output_channel,
+                        showcase_channels,
+                        locale,
+                        disabled_commands,
+                        watermark,
+                        auto_render,
+                        render_defaults,
+                        ephemeral_results,
+                        queue_limit,
+                        result_webhook,
+                        setup_prompted,
+                        announce_renders,
+                        compact_embeds,
+                        parse_warnings,
                     };
 
                     guard.insert(server_id, server);
@@ -97,11 +259,24 @@ mod servers {
 
     impl Serialize for BorrowedRawServer<'_> {
         fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-            let mut raw = s.serialize_struct("RawServer", 3)?;
+            let mut raw = s.serialize_struct("RawServer", 16)?;
 
             raw.serialize_field("server_id", &self.server_id)?;
             raw.serialize_field("input_channels", &self.server.input_channels)?;
             raw.serialize_field("output_channel", &self.server.output_channel)?;
+            raw.serialize_field("showcase_channels", &self.server.showcase_channels)?;
+            raw.serialize_field("locale", &self.server.locale)?;
+            raw.serialize_field("disabled_commands", &self.server.disabled_commands)?;
+            raw.serialize_field("watermark", &self.server.watermark)?;
+            raw.serialize_field("auto_render", &self.server.auto_render)?;
+            raw.serialize_field("render_defaults", &self.server.render_defaults)?;
+            raw.serialize_field("ephemeral_results", &self.server.ephemeral_results)?;
+            raw.serialize_field("queue_limit", &self.server.queue_limit)?;
+            raw.serialize_field("result_webhook", &self.server.result_webhook)?;
+            raw.serialize_field("setup_prompted", &self.server.setup_prompted)?;
+            raw.serialize_field("announce_renders", &self.server.announce_renders)?;
+            raw.serialize_field("compact_embeds", &self.server.compact_embeds)?;
+            raw.serialize_field("parse_warnings", &self.server.parse_warnings)?;
 
             raw.end()
         }
@@ -119,6 +294,56 @@ mod servers {
     }
 }
 
+mod users {
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+    use twilight_model::id::{marker::UserMarker, Id};
+
+    use crate::util::hasher::IntBuildHasher;
+
+    use super::{FlurryMap, UserSettings, Users};
+
+    #[derive(Deserialize, Serialize)]
+    struct RawUser {
+        user_id: Id<UserMarker>,
+        #[serde(default)]
+        compact_embeds: Option<bool>,
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Users, D::Error> {
+        let raw_users = Vec::<RawUser>::deserialize(d)?;
+        let users = FlurryMap::with_capacity_and_hasher(raw_users.len(), IntBuildHasher);
+
+        {
+            let guard = users.pin();
+
+            for RawUser {
+                user_id,
+                compact_embeds,
+            } in raw_users
+            {
+                guard.insert(user_id, UserSettings { compact_embeds });
+            }
+        }
+
+        Ok(users)
+    }
+
+    pub(super) fn serialize<S: Serializer>(users: &Users, s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(users.len()))?;
+
+        for (&user_id, user) in users.pin().iter() {
+            let user = RawUser {
+                user_id,
+                compact_embeds: user.compact_embeds,
+            };
+
+            seq.serialize_element(&user)?;
+        }
+
+        seq.end()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DanserSettings {
@@ -136,6 +361,65 @@ pub struct DanserSettings {
     pub recording: Recording,
 }
 
+impl DanserSettings {
+    /// Read the settings of the given user if they have their own
+    pub fn load_user(user: Id<UserMarker>) -> Result<Option<Self>> {
+        let mut path = BotConfig::get().paths.danser().to_owned();
+        path.push(format!("settings/{user}.json"));
+
+        if path.exists() {
+            Self::read(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Delete the settings of the given user; returns whether they had their own
+    pub fn delete_user(user: Id<UserMarker>) -> Result<bool> {
+        let mut path = BotConfig::get().paths.danser().to_owned();
+        path.push(format!("settings/{user}.json"));
+
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(&path).with_context(|| format!("failed to remove {path:?}"))?;
+
+        Ok(true)
+    }
+
+    /// Read the global default settings
+    pub fn load_default() -> Result<Self> {
+        let mut path = BotConfig::get().paths.danser().to_owned();
+        path.push("settings/default.json");
+
+        Self::read(&path)
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("failed to open {path:?}"))?;
+
+        serde_json::from_reader(file)
+            .with_context(|| format!("failed to deserialize settings at {path:?}"))
+    }
+
+    /// Name of the skin that is currently selected
+    pub fn skin_name(&self) -> Cow<'_, str> {
+        Path::new(&self.skin.current_skin).file_name().map_or(
+            Cow::Borrowed(self.skin.current_skin.as_str()),
+            OsStr::to_string_lossy,
+        )
+    }
+
+    /// Select the skin with the given name while keeping the path to the skins folder
+    pub fn set_skin(&mut self, name: &str) {
+        let mut skin_path = PathBuf::from(&self.skin.current_skin);
+        skin_path.pop();
+        skin_path.push(name);
+        self.skin.current_skin = skin_path.to_string_lossy().into_owned();
+    }
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct General {
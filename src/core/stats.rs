@@ -1,14 +1,333 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    mem,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use prometheus::{Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry};
 use time::OffsetDateTime;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::util::hasher::IntBuildHasher;
 
 pub struct BotStats {
+    pub registry: Registry,
     pub start_time: OffsetDateTime,
-    // TODO: pub replays_rendered: IntCounter (shisha.mezo.xyz endpoint or maybe local counter)
+    pub queue_depth: IntGauge,
+    pub renders: RenderCounters,
+    pub render_duration: Histogram,
+    pub site_requests: HistogramVec,
+}
+
+/// Renders older than this are not considered for the throughput
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub struct RenderCounters {
+    counters: IntCounterVec,
+    /// Failed renders labeled by their [`FailureReason`]
+    failures: IntCounterVec,
+    /// Processed renders within the [`THROUGHPUT_WINDOW`], oldest first
+    recent: Mutex<VecDeque<RecentRender>>,
+    /// Completed renders per user and server since the activity was last taken
+    activity: Mutex<RenderActivity>,
+}
+
+/// Stage of the render at which it failed
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FailureReason {
+    /// The map could not be retrieved or downloaded
+    Map,
+    /// The render options could not be applied
+    Settings,
+    /// danser failed to run or to finish the render
+    Danser,
+    Upload,
+    Other,
+}
+
+impl FailureReason {
+    pub const ALL: [Self; 5] = [
+        Self::Map,
+        Self::Settings,
+        Self::Danser,
+        Self::Upload,
+        Self::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Map => "map",
+            Self::Settings => "settings",
+            Self::Danser => "danser",
+            Self::Upload => "upload",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RenderActivity {
+    pub users: HashMap<Id<UserMarker>, usize, IntBuildHasher>,
+    pub guilds: HashMap<Id<GuildMarker>, usize, IntBuildHasher>,
+}
+
+struct RecentRender {
+    finished_at: Instant,
+    /// `None` if the render failed
+    duration: Option<Duration>,
+}
+
+/// Render stats of the last day
+pub struct Throughput {
+    pub completed_hour: usize,
+    pub completed_day: usize,
+    pub failed_day: usize,
+    /// Average duration of the day's completed renders
+    pub average_duration: Option<Duration>,
+}
+
+impl Throughput {
+    /// Percentage of the day's renders that failed
+    pub fn failure_rate(&self) -> Option<f64> {
+        let total = self.completed_day + self.failed_day;
+
+        (total > 0).then(|| 100.0 * self.failed_day as f64 / total as f64)
+    }
+}
+
+impl RenderCounters {
+    fn new(counters: IntCounterVec, failures: IntCounterVec) -> Self {
+        Self {
+            counters,
+            failures,
+            recent: Mutex::new(VecDeque::new()),
+            activity: Mutex::new(RenderActivity::default()),
+        }
+    }
+
+    pub fn completed(
+        &self,
+        duration: Duration,
+        user: Id<UserMarker>,
+        guild: Option<Id<GuildMarker>>,
+    ) {
+        self.counters.with_label_values(&["completed"]).inc();
+        self.push_recent(Instant::now(), Some(duration));
+
+        let mut activity = self.activity.lock().unwrap();
+        *activity.users.entry(user).or_default() += 1;
+
+        if let Some(guild) = guild {
+            *activity.guilds.entry(guild).or_default() += 1;
+        }
+    }
+
+    pub fn failed(&self, reason: FailureReason) {
+        self.counters.with_label_values(&["failed"]).inc();
+        self.failures.with_label_values(&[reason.as_str()]).inc();
+        self.push_recent(Instant::now(), None);
+    }
+
+    /// Total amount of completed renders since the start
+    pub fn completed_total(&self) -> u64 {
+        self.counters.with_label_values(&["completed"]).get()
+    }
+
+    /// Total amount of renders that failed for the reason since the start
+    pub fn failed_total(&self, reason: FailureReason) -> u64 {
+        self.failures.with_label_values(&[reason.as_str()]).get()
+    }
+
+    /// Completed renders per user and server since the previous call
+    pub fn take_activity(&self) -> RenderActivity {
+        mem::take(&mut *self.activity.lock().unwrap())
+    }
+
+    fn push_recent(&self, finished_at: Instant, duration: Option<Duration>) {
+        let mut recent = self.recent.lock().unwrap();
+        Self::evict_old(&mut recent, finished_at);
+
+        recent.push_back(RecentRender {
+            finished_at,
+            duration,
+        });
+    }
+
+    fn evict_old(recent: &mut VecDeque<RecentRender>, now: Instant) {
+        while recent.front().map_or(false, |render| {
+            now.duration_since(render.finished_at) > THROUGHPUT_WINDOW
+        }) {
+            recent.pop_front();
+        }
+    }
+
+    pub fn throughput(&self, now: Instant) -> Throughput {
+        let mut recent = self.recent.lock().unwrap();
+        Self::evict_old(&mut recent, now);
+
+        let hour = Duration::from_secs(60 * 60);
+
+        let mut throughput = Throughput {
+            completed_hour: 0,
+            completed_day: 0,
+            failed_day: 0,
+            average_duration: None,
+        };
+
+        let mut total_duration = Duration::ZERO;
+
+        for render in recent.iter() {
+            match render.duration {
+                Some(duration) => {
+                    throughput.completed_day += 1;
+                    total_duration += duration;
+
+                    if now.duration_since(render.finished_at) <= hour {
+                        throughput.completed_hour += 1;
+                    }
+                }
+                None => throughput.failed_day += 1,
+            }
+        }
+
+        if throughput.completed_day > 0 {
+            throughput.average_duration = Some(total_duration / throughput.completed_day as u32);
+        }
+
+        throughput
+    }
 }
 
 impl BotStats {
     pub fn new() -> Self {
+        let queue_depth = IntGauge::new("queue_depth", "Amount of replays in the queue")
+            .expect("failed to create queue gauge");
+
+        let renders = IntCounterVec::new(Opts::new("renders", "Processed replays"), &["status"])
+            .expect("failed to create render counter");
+
+        let failures_opts = Opts::new("render_failures", "Failed replays by reason");
+        let render_failures = IntCounterVec::new(failures_opts, &["reason"])
+            .expect("failed to create render failure counter");
+
+        let render_duration_opts = HistogramOpts::new("render_duration", "Seconds per render")
+            .buckets(vec![15.0, 30.0, 60.0, 120.0, 180.0, 300.0, 600.0, 1200.0]);
+
+        let render_duration =
+            Histogram::with_opts(render_duration_opts).expect("failed to create render histogram");
+
+        let site_requests_opts =
+            HistogramOpts::new("site_requests", "Seconds per request to a site");
+
+        let site_requests = HistogramVec::new(site_requests_opts, &["site"])
+            .expect("failed to create site histogram");
+
+        let registry = Registry::new_custom(Some(String::from("shishabot")), None)
+            .expect("failed to create prometheus registry");
+
+        registry.register(Box::new(queue_depth.clone())).unwrap();
+        registry.register(Box::new(renders.clone())).unwrap();
+        registry
+            .register(Box::new(render_failures.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(render_duration.clone()))
+            .unwrap();
+        registry.register(Box::new(site_requests.clone())).unwrap();
+
         Self {
+            registry,
             start_time: OffsetDateTime::now_utc(),
+            queue_depth,
+            renders: RenderCounters::new(renders, render_failures),
+            render_duration,
+            site_requests,
+        }
+    }
+
+    /// Estimate how long it takes until `ahead` many renders are done
+    /// based on the average render duration.
+    ///
+    /// Returns `None` if there were no renders yet.
+    pub fn estimated_wait(&self, ahead: usize) -> Option<Duration> {
+        let count = self.render_duration.get_sample_count();
+
+        if count == 0 {
+            return None;
         }
+
+        let average = self.render_duration.get_sample_sum() / count as f64;
+
+        Some(Duration::from_secs_f64(average * ahead as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters() -> RenderCounters {
+        let counters = IntCounterVec::new(Opts::new("test", "test"), &["status"]).unwrap();
+        let failures = IntCounterVec::new(Opts::new("test", "test"), &["reason"]).unwrap();
+
+        RenderCounters::new(counters, failures)
+    }
+
+    #[test]
+    fn throughput_counts_windows() {
+        let counters = counters();
+        let now = Instant::now();
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+
+        counters.push_recent(now, Some(minutes(1)));
+        counters.push_recent(now + minutes(90), Some(minutes(3)));
+        counters.push_recent(now + minutes(100), None);
+
+        let throughput = counters.throughput(now + minutes(120));
+
+        assert_eq!(throughput.completed_hour, 1);
+        assert_eq!(throughput.completed_day, 2);
+        assert_eq!(throughput.failed_day, 1);
+        assert_eq!(throughput.average_duration, Some(minutes(2)));
+        assert_eq!(throughput.failure_rate().map(f64::round), Some(33.0));
+    }
+
+    #[test]
+    fn activity_is_taken() {
+        let counters = counters();
+        let user = Id::new(1);
+        let guild = Id::new(2);
+
+        counters.completed(Duration::from_secs(60), user, Some(guild));
+        counters.completed(Duration::from_secs(60), user, None);
+        counters.failed(FailureReason::Danser);
+
+        assert_eq!(counters.completed_total(), 2);
+        assert_eq!(counters.failed_total(FailureReason::Danser), 1);
+        assert_eq!(counters.failed_total(FailureReason::Map), 0);
+
+        let activity = counters.take_activity();
+
+        assert_eq!(activity.users[&user], 2);
+        assert_eq!(activity.guilds[&guild], 1);
+        assert!(counters.take_activity().users.is_empty());
+    }
+
+    #[test]
+    fn throughput_forgets_old_renders() {
+        let counters = counters();
+        let now = Instant::now();
+
+        counters.push_recent(now, Some(Duration::from_secs(60)));
+
+        let throughput = counters.throughput(now + THROUGHPUT_WINDOW + Duration::from_secs(1));
+
+        assert_eq!(throughput.completed_day, 0);
+        assert_eq!(throughput.average_duration, None);
+        assert_eq!(throughput.failure_rate(), None);
     }
 }
@@ -0,0 +1,214 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Consecutive failures after which the breaker opens
+const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open breaker rejects requests before letting a probe through
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Stops requests to a site that keeps failing so that they fail fast
+/// instead of waiting on timeouts and using up ratelimit tokens.
+///
+/// After [`FAILURE_THRESHOLD`] consecutive failures the breaker opens and
+/// rejects all requests. Once [`COOLDOWN`] passed, it's half-open and lets
+/// a single request through. If that one succeeds, the breaker closes
+/// again, otherwise it re-opens for another cooldown.
+#[derive(Default)]
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Default)]
+struct BreakerInner {
+    failures: u32,
+    opened_at: Option<Instant>,
+    /// Start of the request that tests whether the site recovered
+    probe_started: Option<Instant>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl Display for BreakerState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BreakerState::Closed => f.write_str("closed"),
+            BreakerState::Open => f.write_str("open"),
+            BreakerState::HalfOpen => f.write_str("half-open"),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn state(&self, now: Instant) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) < COOLDOWN => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Whether a request may be sent right now
+    pub fn try_acquire(&self, now: Instant) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        let opened_at = match inner.opened_at {
+            Some(opened_at) => opened_at,
+            None => return true,
+        };
+
+        if now.duration_since(opened_at) < COOLDOWN {
+            return false;
+        }
+
+        // Only one probe at a time, unless the previous one never finished
+        let probing = inner
+            .probe_started
+            .map_or(false, |started| now.duration_since(started) < COOLDOWN);
+
+        if probing {
+            return false;
+        }
+
+        inner.probe_started = Some(now);
+
+        true
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = 0;
+        inner.opened_at = None;
+        inner.probe_started = None;
+    }
+
+    pub fn record_failure(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.probe_started.take().is_some() {
+            inner.opened_at = Some(now);
+
+            return;
+        }
+
+        inner.failures += 1;
+
+        if inner.failures >= FAILURE_THRESHOLD && inner.opened_at.is_none() {
+            inner.opened_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opened(now: Instant) -> CircuitBreaker {
+        let breaker = CircuitBreaker::default();
+
+        for _ in 0..FAILURE_THRESHOLD {
+            assert!(breaker.try_acquire(now));
+            breaker.record_failure(now);
+        }
+
+        breaker
+    }
+
+    #[test]
+    fn closed_below_threshold() {
+        let breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure(now);
+        }
+
+        assert_eq!(breaker.state(now), BreakerState::Closed);
+        assert!(breaker.try_acquire(now));
+    }
+
+    #[test]
+    fn success_resets_failures() {
+        let breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure(now);
+        }
+
+        breaker.record_success();
+        breaker.record_failure(now);
+
+        assert_eq!(breaker.state(now), BreakerState::Closed);
+    }
+
+    #[test]
+    fn opens_at_threshold() {
+        let now = Instant::now();
+        let breaker = opened(now);
+
+        assert_eq!(breaker.state(now), BreakerState::Open);
+        assert!(!breaker.try_acquire(now + COOLDOWN / 2));
+    }
+
+    #[test]
+    fn half_open_after_cooldown() {
+        let now = Instant::now();
+        let breaker = opened(now);
+        let later = now + COOLDOWN;
+
+        assert_eq!(breaker.state(later), BreakerState::HalfOpen);
+
+        // Only a single probe is let through
+        assert!(breaker.try_acquire(later));
+        assert!(!breaker.try_acquire(later));
+    }
+
+    #[test]
+    fn successful_probe_closes() {
+        let now = Instant::now();
+        let breaker = opened(now);
+        let later = now + COOLDOWN;
+
+        assert!(breaker.try_acquire(later));
+        breaker.record_success();
+
+        assert_eq!(breaker.state(later), BreakerState::Closed);
+        assert!(breaker.try_acquire(later));
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let now = Instant::now();
+        let breaker = opened(now);
+        let later = now + COOLDOWN;
+
+        assert!(breaker.try_acquire(later));
+        breaker.record_failure(later);
+
+        assert_eq!(breaker.state(later), BreakerState::Open);
+        assert!(!breaker.try_acquire(later + COOLDOWN / 2));
+        assert_eq!(breaker.state(later + COOLDOWN), BreakerState::HalfOpen);
+    }
+
+    #[test]
+    fn stale_probe_is_replaced() {
+        let now = Instant::now();
+        let breaker = opened(now);
+        let later = now + COOLDOWN;
+
+        // The first probe never reports back, e.g. because it was cancelled
+        assert!(breaker.try_acquire(later));
+        assert!(breaker.try_acquire(later + COOLDOWN));
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+
+/// Upper bound of cached responses per site; the least recently used one is evicted beyond that
+const MAX_ENTRIES: usize = 256;
+
+/// Short-lived cache of a site's GET responses keyed by url.
+///
+/// A TTL of zero disables the cache.
+pub(super) struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+struct CacheEntry {
+    bytes: Bytes,
+    inserted_at: Instant,
+    used_at: Instant,
+}
+
+impl ResponseCache {
+    pub(super) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(super) fn get(&self, url: &str) -> Option<Bytes> {
+        self.get_at(url, Instant::now())
+    }
+
+    pub(super) fn insert(&self, url: &str, bytes: Bytes) {
+        self.insert_at(url, bytes, Instant::now())
+    }
+
+    fn get_at(&self, url: &str, now: Instant) -> Option<Bytes> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get_mut(url) {
+            Some(entry) if now.saturating_duration_since(entry.inserted_at) < self.ttl => {
+                entry.used_at = now;
+
+                Some(entry.bytes.clone())
+            }
+            Some(_) => {
+                entries.remove(url);
+
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert_at(&self, url: &str, bytes: Bytes, now: Instant) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = self.ttl;
+        entries.retain(|_, entry| now.saturating_duration_since(entry.inserted_at) < ttl);
+
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(url) {
+            let lru = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used_at)
+                .map(|(key, _)| key.to_owned());
+
+            if let Some(lru) = lru {
+                entries.remove(&lru);
+            }
+        }
+
+        let entry = CacheEntry {
+            bytes,
+            inserted_at: now,
+            used_at: now,
+        };
+
+        entries.insert(url.to_owned(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn expires_after_ttl() {
+        let cache = ResponseCache::new(TTL);
+        let now = Instant::now();
+
+        cache.insert_at("a", Bytes::from_static(b"a"), now);
+
+        assert!(cache.get_at("a", now + TTL / 2).is_some());
+        assert!(cache.get_at("a", now + TTL).is_none());
+        assert!(cache.entries.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn zero_ttl_disables() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        let now = Instant::now();
+
+        cache.insert_at("a", Bytes::from_static(b"a"), now);
+
+        assert!(cache.get_at("a", now).is_none());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ResponseCache::new(TTL);
+        let now = Instant::now();
+
+        for i in 0..MAX_ENTRIES {
+            let at = now + Duration::from_millis(i as u64);
+            cache.insert_at(&i.to_string(), Bytes::new(), at);
+        }
+
+        // Using the oldest entry keeps it around
+        let later = now + Duration::from_secs(1);
+        assert!(cache.get_at("0", later).is_some());
+
+        cache.insert_at("new", Bytes::new(), later);
+
+        assert_eq!(cache.entries.lock().unwrap().len(), MAX_ENTRIES);
+        assert!(cache.get_at("0", later).is_some());
+        assert!(cache.get_at("1", later).is_none());
+        assert!(cache.get_at("new", later).is_some());
+    }
+}
@@ -1,45 +1,138 @@
-use std::{hash::Hash, path::Path};
+use std::{
+    borrow::Cow,
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+    future::Future,
+    hash::Hash,
+    path::Path,
+    pin::Pin,
+    slice,
+    time::Instant,
+};
 
 use crate::core::BotConfig;
 use bytes::Bytes;
-use eyre::{Context as _, Result};
-use http::{header::CONTENT_LENGTH, Response};
+use eyre::{Context as _, Report, Result};
+use http::{header::CONTENT_LENGTH, Response, StatusCode};
 use hyper::{
+    body::HttpBody,
     client::{connect::dns::GaiResolver, Client as HyperClient, HttpConnector},
     header::{CONTENT_TYPE, USER_AGENT},
     Body, Method, Request,
 };
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use leaky_bucket_lite::LeakyBucket;
+use prometheus::HistogramVec;
 use serde::Deserialize;
-use tokio::time::Duration;
+use tokio::{
+    sync::Semaphore,
+    time::{self, Duration},
+};
 use twilight_model::{
-    channel::Attachment,
+    channel::{embed::Embed, Attachment},
+    http::attachment::Attachment as AttachmentFile,
     id::{marker::UserMarker, Id},
 };
 
-use self::multipart::Multipart;
+use self::{
+    breaker::CircuitBreaker,
+    cache::ResponseCache,
+    multipart::Multipart,
+    ratelimit::SiteRatelimiter,
+    webhook::{PayloadAttachment, WebhookPayload},
+};
+
+pub use self::{
+    breaker::BreakerState,
+    ratelimit::RatelimitInfo,
+    webhook::{is_webhook_url, WebhookInfo},
+};
 
+mod breaker;
+mod cache;
 mod multipart;
+mod ratelimit;
+mod webhook;
 
 static MY_USER_AGENT: &str = env!("CARGO_PKG_NAME");
 
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 #[repr(u8)]
 enum Site {
     DiscordAttachment,
+    DiscordWebhook,
     DownloadChimu,
     DownloadKitsu,
+    OsuMapFile,
+    OsuMapsetCover,
     OsuReplay,
     ShishaMezo,
 }
 
+impl Site {
+    const ALL: [Self; 8] = [
+        Site::DiscordAttachment,
+        Site::DiscordWebhook,
+        Site::DownloadChimu,
+        Site::DownloadKitsu,
+        Site::OsuMapFile,
+        Site::OsuMapsetCover,
+        Site::OsuReplay,
+        Site::ShishaMezo,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Site::DiscordAttachment => "DiscordAttachment",
+            Site::DiscordWebhook => "DiscordWebhook",
+            Site::DownloadChimu => "DownloadChimu",
+            Site::DownloadKitsu => "DownloadKitsu",
+            Site::OsuMapFile => "OsuMapFile",
+            Site::OsuMapsetCover => "OsuMapsetCover",
+            Site::OsuReplay => "OsuReplay",
+            Site::ShishaMezo => "ShishaMezo",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|site| site.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// How often a mapset download is retried after a server error
+const MAPSET_RETRIES: u32 = 2;
+
+/// How often a map file download is retried after a server error or an error page
+const MAP_FILE_RETRIES: u32 = 2;
+
 type Client = HyperClient<HttpsConnector<HttpConnector<GaiResolver>>, Body>;
 
+type TransportFuture<'t> = Pin<Box<dyn Future<Output = Result<Response<Body>>> + Send + 't>>;
+
+/// The layer that actually performs requests, swappable for tests
+trait Transport: Send + Sync {
+    fn request(&self, req: Request<Body>) -> TransportFuture<'_>;
+}
+
+impl Transport for Client {
+    fn request(&self, req: Request<Body>) -> TransportFuture<'_> {
+        let fut = HyperClient::request(self, req);
+
+        Box::pin(async move { fut.await.map_err(Report::from) })
+    }
+}
+
 pub struct CustomClient {
-    client: Client,
-    ratelimiters: [LeakyBucket; 5],
+    client: Box<dyn Transport>,
+    ratelimiters: [SiteRatelimiter; 8],
+    breakers: [CircuitBreaker; 8],
+    caches: [ResponseCache; 8],
+    /// Caps the amount of requests that are in flight across all sites
+    in_flight: Semaphore,
     upload: UploadData,
+    osu_api_key: &'static str,
+    metrics: HistogramVec,
 }
 
 struct UploadData {
@@ -57,46 +150,175 @@ impl From<&'static BotConfig> for UploadData {
     }
 }
 
+/// Seconds for which responses of rarely changing sites are cached
+struct CacheTtls {
+    map_file: u64,
+    mapset_cover: u64,
+}
+
+impl CacheTtls {
+    fn get(&self, site: Site) -> Duration {
+        let secs = match site {
+            Site::OsuMapFile => self.map_file,
+            Site::OsuMapsetCover => self.mapset_cover,
+            _ => 0,
+        };
+
+        Duration::from_secs(secs)
+    }
+}
+
+impl From<&'static BotConfig> for CacheTtls {
+    #[inline]
+    fn from(config: &'static BotConfig) -> Self {
+        Self {
+            map_file: config.map_file_cache_ttl,
+            mapset_cover: config.mapset_cover_cache_ttl,
+        }
+    }
+}
+
 impl CustomClient {
-    pub fn new() -> Self {
+    pub fn new(metrics: HistogramVec) -> Self {
         let connector = HttpsConnectorBuilder::new()
             .with_webpki_roots()
             .https_or_http()
             .enable_http1()
             .build();
 
-        let client = HyperClient::builder().build(connector);
+        let client: Client = HyperClient::builder().build(connector);
+        let config = BotConfig::get();
 
-        let ratelimiter = |per_second| {
-            LeakyBucket::builder()
-                .max(per_second)
-                .tokens(per_second)
-                .refill_interval(Duration::from_millis(1000 / per_second as u64))
-                .refill_amount(1)
-                .build()
-        };
+        Self::with_transport(
+            Box::new(client),
+            UploadData::from(config),
+            &config.tokens.osu_api_key,
+            CacheTtls::from(config),
+            config.max_concurrent_requests,
+            metrics,
+        )
+    }
 
+    fn with_transport(
+        client: Box<dyn Transport>,
+        upload: UploadData,
+        osu_api_key: &'static str,
+        cache_ttls: CacheTtls,
+        max_concurrent_requests: usize,
+        metrics: HistogramVec,
+    ) -> Self {
         let ratelimiters = [
-            ratelimiter(2), // DiscordAttachment
-            ratelimiter(1), // DownloadChimu
-            ratelimiter(1), // DownloadKitsu
-            ratelimiter(1), // OsuReplay
-            ratelimiter(1), // ShishaMezo
+            SiteRatelimiter::new(2), // DiscordAttachment
+            SiteRatelimiter::new(2), // DiscordWebhook
+            SiteRatelimiter::new(1), // DownloadChimu
+            SiteRatelimiter::new(1), // DownloadKitsu
+            SiteRatelimiter::new(2), // OsuMapFile
+            SiteRatelimiter::new(2), // OsuMapsetCover
+            SiteRatelimiter::new(1), // OsuReplay
+            SiteRatelimiter::new(1), // ShishaMezo
         ];
 
         Self {
             client,
             ratelimiters,
-            upload: UploadData::from(BotConfig::get()),
+            breakers: Default::default(),
+            caches: Site::ALL.map(|site| ResponseCache::new(cache_ttls.get(site))),
+            in_flight: Semaphore::new(max_concurrent_requests),
+            upload,
+            osu_api_key,
+            metrics,
         }
     }
 
     async fn ratelimit(&self, site: Site) {
-        self.ratelimiters[site as usize].acquire_one().await
+        self.ratelimiters[site as usize].acquire().await
+    }
+
+    /// Current state of each site's ratelimiter
+    pub fn ratelimits(&self) -> Vec<RatelimitInfo> {
+        Site::ALL
+            .iter()
+            .map(|&site| self.ratelimiters[site as usize].info(site.as_str()))
+            .collect()
+    }
+
+    /// Allow only a single request per `interval` to the given site until
+    /// the limit is reset or the bot restarts.
+    ///
+    /// The interval may not be shorter than the site's regular one.
+    pub fn tighten_ratelimit(
+        &self,
+        site: &str,
+        interval: Duration,
+    ) -> Result<RatelimitInfo, RatelimitError> {
+        let site = Site::from_name(site).ok_or(RatelimitError::UnknownSite)?;
+        let ratelimiter = &self.ratelimiters[site as usize];
+        let default = ratelimiter.default_interval();
+
+        if interval < default {
+            return Err(RatelimitError::NotTighter { default });
+        }
+
+        ratelimiter.tighten(interval);
+
+        Ok(ratelimiter.info(site.as_str()))
+    }
+
+    /// Restore the site's regular ratelimit
+    pub fn reset_ratelimit(&self, site: &str) -> Result<RatelimitInfo, RatelimitError> {
+        let site = Site::from_name(site).ok_or(RatelimitError::UnknownSite)?;
+        let ratelimiter = &self.ratelimiters[site as usize];
+        ratelimiter.reset();
+
+        Ok(ratelimiter.info(site.as_str()))
+    }
+
+    /// Fail fast if the site's circuit breaker is open
+    fn check_breaker(&self, site: Site) -> Result<()> {
+        if self.breakers[site as usize].try_acquire(Instant::now()) {
+            Ok(())
+        } else {
+            Err(Report::from(CircuitOpenError { site }))
+        }
+    }
+
+    /// Feed the outcome of a request into the site's circuit breaker
+    fn record_outcome(&self, site: Site, res: &Result<Response<Body>>) {
+        let breaker = &self.breakers[site as usize];
+
+        match res {
+            Ok(response) if !response.status().is_server_error() => breaker.record_success(),
+            _ => breaker.record_failure(Instant::now()),
+        }
+    }
+
+    /// Current state of each site's circuit breaker
+    pub fn breaker_states(&self) -> Vec<(&'static str, BreakerState)> {
+        let now = Instant::now();
+
+        Site::ALL
+            .iter()
+            .map(|&site| (site.as_str(), self.breakers[site as usize].state(now)))
+            .collect()
+    }
+
+    fn observe(&self, site: Site, start: Instant) {
+        self.metrics
+            .with_label_values(&[site.as_str()])
+            .observe(start.elapsed().as_secs_f64());
     }
 
     async fn make_get_request(&self, url: impl AsRef<str>, site: Site) -> Result<Bytes> {
         let url = url.as_ref();
+        let cache = &self.caches[site as usize];
+
+        // Cache hits neither use up the site's tokens nor count towards its breaker
+        if let Some(bytes) = cache.get(url) {
+            trace!("Cached GET response for url {url}");
+
+            return Ok(bytes);
+        }
+
         trace!("GET request to url {url}");
 
         let req = Request::builder()
@@ -106,15 +328,32 @@ impl CustomClient {
             .body(Body::empty())
             .context("failed to build GET request")?;
 
-        self.ratelimit(site).await;
+        self.check_breaker(site)?;
 
-        let response = self
-            .client
-            .request(req)
+        // Queue up for a slot before the ratelimiter so that waiting
+        // requests don't use up the site's tokens
+        let _permit = self
+            .in_flight
+            .acquire()
             .await
-            .context("failed to receive GET response")?;
+            .context("request semaphore was closed")?;
+        self.ratelimit(site).await;
+        let start = Instant::now();
 
-        Self::error_for_status(response, url).await
+        let res = self.client.request(req).await;
+        self.record_outcome(site, &res);
+        let response = res.context("failed to receive GET response")?;
+
+        self.observe(site, start);
+
+        let bytes = Self::error_for_status(response, url).await?;
+
+        // Error pages are served with a success status at times
+        if !is_html(&bytes) {
+            cache.insert(url, bytes.clone());
+        }
+
+        Ok(bytes)
     }
 
     async fn make_post_request(
@@ -138,13 +377,21 @@ impl CustomClient {
             .body(Body::from(form))
             .context("failed to build POST request")?;
 
-        self.ratelimit(site).await;
+        self.check_breaker(site)?;
 
-        let response = self
-            .client
-            .request(req)
+        let _permit = self
+            .in_flight
+            .acquire()
             .await
-            .context("failed to receive POST response")?;
+            .context("request semaphore was closed")?;
+        self.ratelimit(site).await;
+        let start = Instant::now();
+
+        let res = self.client.request(req).await;
+        self.record_outcome(site, &res);
+        let response = res.context("failed to receive POST response")?;
+
+        self.observe(site, start);
 
         Self::error_for_status(response, url).await
     }
@@ -153,7 +400,13 @@ impl CustomClient {
         let status = response.status();
 
         if status.is_client_error() || status.is_server_error() {
-            bail!("failed with status code {status} when requesting {url}")
+            let err = StatusError {
+                status,
+                url: url.to_owned(),
+                body: body_snippet(response.into_body()).await,
+            };
+
+            Err(Report::from(err))
         } else {
             let bytes = hyper::body::to_bytes(response.into_body())
                 .await
@@ -166,7 +419,7 @@ impl CustomClient {
     pub async fn get_raw_replay(&self, score_id: u64) -> Result<Vec<u8>> {
         let url = format!(
             "https://osu.ppy.sh/api/get_replay?k={api_key}&s={score_id}",
-            api_key = BotConfig::get().tokens.osu_api_key,
+            api_key = self.osu_api_key,
         );
 
         #[derive(Deserialize)]
@@ -185,6 +438,8 @@ impl CustomClient {
         base64::decode(content.into_bytes()).context("failed to decode through base64")
     }
 
+    /// Attachment urls expire so the bytes must be fetched right away
+    /// rather than when the attachment is needed later on.
     pub async fn get_discord_attachment(&self, attachment: &Attachment) -> Result<Bytes> {
         self.make_get_request(&attachment.url, Site::DiscordAttachment)
             .await
@@ -193,13 +448,112 @@ impl CustomClient {
     pub async fn download_chimu_mapset(&self, mapset_id: u32) -> Result<Bytes> {
         let url = format!("https://chimu.moe/d/{mapset_id}");
 
-        self.make_get_request(url, Site::DownloadChimu).await
+        self.download_mapset(url, Site::DownloadChimu).await
     }
 
     pub async fn download_kitsu_mapset(&self, mapset_id: u32) -> Result<Bytes> {
         let url = format!("https://kitsu.moe/api/d/{mapset_id}");
 
-        self.make_get_request(url, Site::DownloadKitsu).await
+        self.download_mapset(url, Site::DownloadKitsu).await
+    }
+
+    /// Mirrors are flaky so server errors are retried with a backoff.
+    /// Also, some mirrors respond with an html page instead of a proper
+    /// status code so that's checked for too.
+    async fn download_mapset(&self, url: String, site: Site) -> Result<Bytes> {
+        let mut attempt = 0;
+
+        let bytes = loop {
+            match self.make_get_request(&url, site).await {
+                Ok(bytes) => break bytes,
+                Err(err) if attempt < MAPSET_RETRIES && is_server_error(&err) => {
+                    attempt += 1;
+                    debug!("Retrying mapset download from {url} (attempt {attempt})");
+                    time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        if is_html(&bytes) {
+            return Err(Report::from(ErrorPageError { url }));
+        }
+
+        Ok(bytes)
+    }
+
+    /// The content of the map's .osu file, empty if the map does not exist.
+    ///
+    /// osu! occasionally responds with an error page when it's busy
+    /// so those are retried alongside server errors.
+    pub async fn get_map_file(&self, map_id: u32) -> Result<Bytes> {
+        let url = format!("https://osu.ppy.sh/osu/{map_id}");
+        let mut attempt = 0;
+
+        loop {
+            match self.make_get_request(&url, Site::OsuMapFile).await {
+                Ok(bytes) if !is_html(&bytes) => return Ok(bytes),
+                Ok(_) if attempt == MAP_FILE_RETRIES => {
+                    return Err(Report::from(ErrorPageError { url }))
+                }
+                Ok(_) => {}
+                Err(err) if attempt < MAP_FILE_RETRIES && is_server_error(&err) => {}
+                Err(err) => return Err(err),
+            }
+
+            attempt += 1;
+            debug!("Retrying map file download from {url} (attempt {attempt})");
+            time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+        }
+    }
+
+    /// Wide banner of the mapset that fits as an embed image
+    pub async fn get_mapset_cover(&self, mapset_id: u32) -> Result<Bytes> {
+        let url = format!("https://assets.ppy.sh/beatmaps/{mapset_id}/covers/cover.jpg");
+
+        self.make_get_request(url, Site::OsuMapsetCover).await
+    }
+
+    /// Request the webhook to check that it exists; does not post anything
+    pub async fn get_webhook(&self, url: &str) -> Result<WebhookInfo> {
+        ensure!(is_webhook_url(url), "not a webhook url");
+
+        let bytes = self.make_get_request(url, Site::DiscordWebhook).await?;
+
+        serde_json::from_slice(&bytes).with_context(|| {
+            let text = String::from_utf8_lossy(&bytes);
+
+            format!("failed to deserialize webhook: {text}")
+        })
+    }
+
+    pub async fn execute_webhook(
+        &self,
+        url: &str,
+        content: Option<&str>,
+        embed: Option<&Embed>,
+        file: Option<&AttachmentFile>,
+    ) -> Result<()> {
+        ensure!(is_webhook_url(url), "not a webhook url");
+
+        let payload = WebhookPayload {
+            content,
+            embeds: embed.map(slice::from_ref).unwrap_or_default(),
+            attachments: file.into_iter().map(PayloadAttachment::from).collect(),
+        };
+
+        let payload = serde_json::to_string(&payload).context("failed to serialize payload")?;
+        let mut form = Multipart::new().push_text("payload_json", payload);
+
+        if let Some(file) = file {
+            let key = format!("files[{}]", file.id);
+            form = form.push_bytes(key, &file.filename, &file.file);
+        }
+
+        self.make_post_request(url, Site::DiscordWebhook, form)
+            .await?;
+
+        Ok(())
     }
 
     pub async fn upload_video(
@@ -228,6 +582,229 @@ impl CustomClient {
     }
 }
 
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    url: String,
+    /// Start of the response body, often explains what went wrong
+    body: Option<String>,
+}
+
+impl Display for StatusError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        // Error reports are posted to discord so the url must not contain secrets
+        write!(
+            f,
+            "failed with status code {} when requesting {}",
+            self.status,
+            redact_url(&self.url)
+        )?;
+
+        if let Some(ref body) = self.body {
+            write!(f, ": {body}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The url without its query, which may contain an api key, and
+/// with the token of webhook urls replaced
+fn redact_url(url: &str) -> Cow<'_, str> {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+
+    if !is_webhook_url(url) {
+        return Cow::Borrowed(url);
+    }
+
+    // The token is the last segment of webhook urls
+    match url.trim_end_matches('/').rsplit_once('/') {
+        Some((prefix, _)) => Cow::Owned(format!("{prefix}/<token>")),
+        None => Cow::Borrowed(url),
+    }
+}
+
+/// Bytes of an error response's body that are kept for debugging
+const MAX_ERROR_BODY_LEN: usize = 512;
+
+/// Read up to [`MAX_ERROR_BODY_LEN`] bytes of the body.
+///
+/// Returns `None` if the body is empty or could not be read.
+async fn body_snippet(mut body: Body) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.ok()?;
+        let remaining = MAX_ERROR_BODY_LEN - bytes.len();
+
+        if chunk.len() > remaining {
+            bytes.extend_from_slice(&chunk[..remaining]);
+            truncated = true;
+
+            break;
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let mut snippet = String::from_utf8_lossy(&bytes).trim().to_owned();
+
+    if snippet.is_empty() {
+        return None;
+    }
+
+    if truncated {
+        snippet.push_str("...");
+    }
+
+    Some(snippet)
+}
+
+impl StdError for StatusError {}
+
+/// The site responded with an html page instead of the requested file
+#[derive(Debug)]
+pub struct ErrorPageError {
+    url: String,
+}
+
+impl Display for ErrorPageError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "received an html page instead of a file from {}",
+            self.url
+        )
+    }
+}
+
+impl StdError for ErrorPageError {}
+
+/// Requests to the site are paused because it kept failing
+#[derive(Debug)]
+pub struct CircuitOpenError {
+    site: Site,
+}
+
+impl Display for CircuitOpenError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "requests to {} are paused after repeated failures",
+            self.site.as_str()
+        )
+    }
+}
+
+impl StdError for CircuitOpenError {}
+
+#[derive(Debug)]
+pub enum RatelimitError {
+    UnknownSite,
+    NotTighter { default: Duration },
+}
+
+impl Display for RatelimitError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnknownSite => {
+                f.write_str("unknown site, must be one of: ")?;
+
+                for (i, site) in Site::ALL.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    f.write_str(site.as_str())?;
+                }
+
+                Ok(())
+            }
+            Self::NotTighter { default } => write!(
+                f,
+                "the interval must be at least the regular {}ms",
+                default.as_millis()
+            ),
+        }
+    }
+}
+
+impl StdError for RatelimitError {}
+
+/// Cause of a failed download as far as users are concerned
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DownloadFailure {
+    /// The file does not exist, e.g. because the map is private or deleted
+    NotFound,
+    /// The site rejected the request because of too many requests
+    Ratelimited,
+    /// The site kept responding with an html page instead of the file
+    ErrorPage,
+    Other,
+}
+
+impl DownloadFailure {
+    pub fn from_report(err: &Report) -> Self {
+        if let Some(err) = err.downcast_ref::<StatusError>() {
+            match err.status {
+                StatusCode::NOT_FOUND => Self::NotFound,
+                StatusCode::TOO_MANY_REQUESTS => Self::Ratelimited,
+                _ => Self::Other,
+            }
+        } else if err.downcast_ref::<ErrorPageError>().is_some() {
+            Self::ErrorPage
+        } else {
+            Self::Other
+        }
+    }
+}
+
+pub fn is_not_found(err: &Report) -> bool {
+    err.downcast_ref::<StatusError>()
+        .map_or(false, |err| err.status == StatusCode::NOT_FOUND)
+}
+
+/// Discord responds with 403 or 404 once the signed url of an attachment expired
+pub fn is_expired_attachment(err: &Report) -> bool {
+    err.downcast_ref::<StatusError>().map_or(false, |err| {
+        matches!(err.status, StatusCode::FORBIDDEN | StatusCode::NOT_FOUND)
+    })
+}
+
+/// Response content for a failed attachment download, `fallback` unless the url expired
+pub fn attachment_error(err: &Report, fallback: &'static str) -> &'static str {
+    if is_expired_attachment(err) {
+        "The attachment's link expired, please upload the file again"
+    } else {
+        fallback
+    }
+}
+
+fn is_server_error(err: &Report) -> bool {
+    err.downcast_ref::<StatusError>()
+        .map_or(false, |err| err.status.is_server_error())
+}
+
+fn is_html(bytes: &[u8]) -> bool {
+    const TAGS: [&[u8]; 2] = [b"<html", b"<!doctype html"];
+
+    let trimmed = match bytes.iter().position(|byte| !byte.is_ascii_whitespace()) {
+        Some(idx) => &bytes[idx..],
+        None => return false,
+    };
+
+    TAGS.iter().any(|tag| {
+        trimmed
+            .get(..tag.len())
+            .map_or(false, |start| start.eq_ignore_ascii_case(tag))
+    })
+}
+
 #[derive(Deserialize)]
 pub struct UploadResponse {
     pub error: u16,
@@ -239,3 +816,460 @@ pub struct OsuReplayResponse {
     pub content: String,
     pub encoding: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use http::StatusCode;
+    use hyper::{Body, Request, Response};
+    use once_cell::sync::Lazy;
+    use prometheus::{HistogramOpts, HistogramVec};
+    use tokio::time::{self, Duration};
+
+    use super::{
+        attachment_error, is_expired_attachment, BreakerState, CacheTtls, CircuitOpenError,
+        CustomClient, DownloadFailure, RatelimitError, Site, StatusError, Transport,
+        TransportFuture, UploadData, MAX_ERROR_BODY_LEN,
+    };
+
+    struct MockTransport {
+        responses: Mutex<VecDeque<(StatusCode, &'static str)>>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Transport for MockTransport {
+        fn request(&self, _: Request<Body>) -> TransportFuture<'_> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            let (status, body) = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("no response left");
+
+            let response = Response::builder()
+                .status(status)
+                .body(Body::from(body))
+                .unwrap();
+
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    /// Keeps track of the highest amount of simultaneous requests
+    #[derive(Default)]
+    struct SlowTransport {
+        current: Arc<AtomicUsize>,
+        max: Arc<AtomicUsize>,
+    }
+
+    impl Transport for SlowTransport {
+        fn request(&self, _: Request<Body>) -> TransportFuture<'_> {
+            Box::pin(async move {
+                let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max.fetch_max(current, Ordering::SeqCst);
+                time::sleep(Duration::from_millis(50)).await;
+                self.current.fetch_sub(1, Ordering::SeqCst);
+
+                Ok(Response::new(Body::empty()))
+            })
+        }
+    }
+
+    const OSU_API_KEY: &str = "secret-api-key";
+
+    /// Nothing is cached so that every request reaches the transport
+    const NO_CACHE: CacheTtls = CacheTtls {
+        map_file: 0,
+        mapset_cover: 0,
+    };
+
+    fn client_with_transport(
+        transport: impl Transport + 'static,
+        cache_ttls: CacheTtls,
+        max_concurrent_requests: usize,
+    ) -> CustomClient {
+        let upload = UploadData {
+            secret: "",
+            url: "",
+        };
+        let metrics = HistogramVec::new(HistogramOpts::new("test", "test"), &["site"]).unwrap();
+
+        CustomClient::with_transport(
+            Box::new(transport),
+            upload,
+            OSU_API_KEY,
+            cache_ttls,
+            max_concurrent_requests,
+            metrics,
+        )
+    }
+
+    fn client(responses: &[(StatusCode, &'static str)]) -> (CustomClient, Arc<AtomicUsize>) {
+        cached_client(responses, NO_CACHE)
+    }
+
+    fn cached_client(
+        responses: &[(StatusCode, &'static str)],
+        cache_ttls: CacheTtls,
+    ) -> (CustomClient, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let transport = MockTransport {
+            responses: Mutex::new(responses.iter().copied().collect()),
+            calls: Arc::clone(&calls),
+        };
+
+        (client_with_transport(transport, cache_ttls, 4), calls)
+    }
+
+    #[tokio::test]
+    async fn expired_attachment() {
+        let responses = [(StatusCode::FORBIDDEN, ""), (StatusCode::BAD_GATEWAY, "")];
+        let (client, _) = client(&responses);
+        let url = "https://cdn.discordapp.com/attachments/1/2/replay.osr";
+
+        let err = client
+            .make_get_request(url, Site::DiscordAttachment)
+            .await
+            .unwrap_err();
+
+        assert!(is_expired_attachment(&err));
+        assert_ne!(attachment_error(&err, "fallback"), "fallback");
+
+        let err = client
+            .make_get_request(url, Site::DiscordAttachment)
+            .await
+            .unwrap_err();
+
+        assert!(!is_expired_attachment(&err));
+        assert_eq!(attachment_error(&err, "fallback"), "fallback");
+    }
+
+    #[tokio::test]
+    async fn mapset_success() {
+        let (client, calls) = client(&[(StatusCode::OK, "PK")]);
+
+        let bytes = client.download_kitsu_mapset(1).await.unwrap();
+
+        assert_eq!(&bytes[..], b"PK");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn mapset_retries_server_error() {
+        let responses = [
+            (StatusCode::BAD_GATEWAY, ""),
+            (StatusCode::SERVICE_UNAVAILABLE, ""),
+            (StatusCode::OK, "PK"),
+        ];
+
+        let (client, calls) = client(&responses);
+
+        assert!(client.download_chimu_mapset(1).await.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn mapset_gives_up_after_retries() {
+        let responses = [(StatusCode::INTERNAL_SERVER_ERROR, ""); 3];
+        let (client, calls) = client(&responses);
+
+        let err = client.download_kitsu_mapset(1).await.unwrap_err();
+        let status = err.downcast_ref::<StatusError>().unwrap().status;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn status_error_contains_body() {
+        let body = r#"{"error": "Not found"}"#;
+        let (client, _) = client(&[(StatusCode::NOT_FOUND, body)]);
+
+        let err = client.get_mapset_cover(1).await.unwrap_err();
+        let err = err.downcast_ref::<StatusError>().unwrap();
+
+        assert_eq!(err.body.as_deref(), Some(body));
+        assert!(err.to_string().ends_with(body));
+    }
+
+    #[tokio::test]
+    async fn status_error_hides_api_key() {
+        let (client, _) = client(&[(StatusCode::UNAUTHORIZED, "")]);
+
+        let err = client.get_raw_replay(1).await.unwrap_err();
+
+        // Error reports show each cause of the chain
+        for cause in err.chain() {
+            let cause = cause.to_string();
+
+            assert!(!cause.contains("k="), "{cause}");
+            assert!(!cause.contains(OSU_API_KEY), "{cause}");
+        }
+
+        assert!(err
+            .to_string()
+            .ends_with("https://osu.ppy.sh/api/get_replay"));
+    }
+
+    #[test]
+    fn status_error_hides_webhook_token() {
+        let err = StatusError {
+            status: StatusCode::NOT_FOUND,
+            url: "https://discord.com/api/webhooks/1018154809515323412/aBc-dEf_123?wait=true"
+                .to_owned(),
+            body: None,
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "failed with status code 404 Not Found when requesting \
+            https://discord.com/api/webhooks/1018154809515323412/<token>"
+        );
+    }
+
+    #[tokio::test]
+    async fn status_error_body_is_truncated() {
+        static BODY: Lazy<String> = Lazy::new(|| "a".repeat(2 * MAX_ERROR_BODY_LEN));
+
+        let (client, _) = client(&[(StatusCode::INTERNAL_SERVER_ERROR, BODY.as_str())]);
+
+        let err = client.get_mapset_cover(1).await.unwrap_err();
+        let snippet = err.downcast_ref::<StatusError>().unwrap().body.clone();
+
+        assert_eq!(snippet.map(|body| body.len()), Some(MAX_ERROR_BODY_LEN + 3));
+    }
+
+    #[tokio::test]
+    async fn mapset_no_retry_on_client_error() {
+        let (client, calls) = client(&[(StatusCode::NOT_FOUND, "")]);
+
+        assert!(client.download_kitsu_mapset(1).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn mapset_rejects_html() {
+        let body = "\n  <!DOCTYPE html><html><body>Not found</body></html>";
+        let (client, calls) = client(&[(StatusCode::OK, body)]);
+
+        assert!(client.download_chimu_mapset(1).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn map_file_retries_error_page() {
+        let responses = [
+            (StatusCode::OK, "<html>Too busy</html>"),
+            (StatusCode::OK, "osu file format v14"),
+        ];
+
+        let (client, calls) = client(&responses);
+
+        assert_eq!(
+            &client.get_map_file(1).await.unwrap()[..],
+            b"osu file format v14"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn map_file_keeps_returning_error_page() {
+        let responses = [(StatusCode::OK, "<html>Too busy</html>"); 3];
+        let (client, calls) = client(&responses);
+
+        let err = client.get_map_file(1).await.unwrap_err();
+
+        assert_eq!(
+            DownloadFailure::from_report(&err),
+            DownloadFailure::ErrorPage
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn download_failure_causes() {
+        let responses = [
+            (StatusCode::NOT_FOUND, ""),
+            (StatusCode::TOO_MANY_REQUESTS, ""),
+            (StatusCode::FORBIDDEN, ""),
+        ];
+
+        let (client, calls) = client(&responses);
+
+        for expected in [
+            DownloadFailure::NotFound,
+            DownloadFailure::Ratelimited,
+            DownloadFailure::Other,
+        ] {
+            let err = client
+                .get_map_file(1)
+                .await
+                .unwrap_err()
+                .wrap_err("failed to download map");
+
+            assert_eq!(DownloadFailure::from_report(&err), expected);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn breaker_short_circuits_failing_site() {
+        let responses = [(StatusCode::BAD_GATEWAY, ""); 5];
+        let (client, calls) = client(&responses);
+
+        for _ in 0..5 {
+            assert!(client.get_mapset_cover(1).await.is_err());
+        }
+
+        let err = client.get_mapset_cover(1).await.unwrap_err();
+
+        assert!(err.downcast_ref::<CircuitOpenError>().is_some());
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+        let state = client
+            .breaker_states()
+            .into_iter()
+            .find(|(site, _)| *site == Site::OsuMapsetCover.as_str())
+            .map(|(_, state)| state);
+
+        assert_eq!(state, Some(BreakerState::Open));
+    }
+
+    #[tokio::test]
+    async fn breaker_ignores_client_errors() {
+        let responses = [(StatusCode::NOT_FOUND, ""); 6];
+        let (client, calls) = client(&responses);
+
+        for _ in 0..6 {
+            let err = client.get_mapset_cover(1).await.unwrap_err();
+            assert!(err.downcast_ref::<StatusError>().is_some());
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn concurrency_cap_is_honored() {
+        let transport = SlowTransport::default();
+        let max = Arc::clone(&transport.max);
+        let client = client_with_transport(transport, NO_CACHE, 1);
+
+        // The site's ratelimiter alone would let both requests through at once
+        let url = "https://assets.ppy.sh";
+        let (a, b) = tokio::join!(
+            client.make_get_request(url, Site::OsuMapsetCover),
+            client.make_get_request(url, Site::OsuMapsetCover),
+        );
+
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(max.load(Ordering::SeqCst), 1);
+    }
+
+    const COVER_CACHE: CacheTtls = CacheTtls {
+        map_file: 0,
+        mapset_cover: 60,
+    };
+
+    #[tokio::test]
+    async fn cache_hits_skip_ratelimiter() {
+        let responses = [(StatusCode::OK, "cover"), (StatusCode::OK, "other")];
+        let (client, calls) = cached_client(&responses, COVER_CACHE);
+
+        let tokens = |client: &CustomClient| {
+            client
+                .ratelimits()
+                .into_iter()
+                .find(|info| info.site == Site::OsuMapsetCover.as_str())
+                .map(|info| (info.tokens, info.max))
+                .unwrap()
+        };
+
+        assert_eq!(&client.get_mapset_cover(1).await.unwrap()[..], b"cover");
+        assert_eq!(&client.get_mapset_cover(1).await.unwrap()[..], b"cover");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (tokens_left, max) = tokens(&client);
+        assert_eq!(tokens_left, max - 1);
+
+        // Other urls are not served from the cache
+        assert_eq!(&client.get_mapset_cover(2).await.unwrap()[..], b"other");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let responses = [(StatusCode::NOT_FOUND, ""), (StatusCode::OK, "cover")];
+        let (client, calls) = cached_client(&responses, COVER_CACHE);
+
+        assert!(client.get_mapset_cover(1).await.is_err());
+        assert!(client.get_mapset_cover(1).await.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn error_pages_are_not_cached() {
+        let responses = [
+            (StatusCode::OK, "<html>busy</html>"),
+            (StatusCode::OK, "osu file format v14"),
+        ];
+
+        let cache_ttls = CacheTtls {
+            map_file: 60,
+            mapset_cover: 0,
+        };
+
+        let (client, calls) = cached_client(&responses, cache_ttls);
+
+        assert!(client.get_map_file(1).await.is_ok());
+        assert_eq!(
+            &client.get_map_file(1).await.unwrap()[..],
+            b"osu file format v14"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn ratelimit_can_be_tightened_and_reset() {
+        let (client, _) = client(&[]);
+        let interval = Duration::from_secs(5);
+
+        let info = client.tighten_ratelimit("osureplay", interval).unwrap();
+
+        assert_eq!(info.site, Site::OsuReplay.as_str());
+        assert_eq!(info.refill_interval, interval);
+        assert!(info.tightened);
+
+        let info = client.reset_ratelimit("OsuReplay").unwrap();
+
+        assert_eq!(info.refill_interval, info.default_interval);
+        assert!(!info.tightened);
+    }
+
+    #[tokio::test]
+    async fn ratelimit_cannot_be_loosened() {
+        let (client, _) = client(&[]);
+
+        let err = client
+            .tighten_ratelimit("OsuMapFile", Duration::from_millis(100))
+            .unwrap_err();
+
+        assert!(matches!(err, RatelimitError::NotTighter { .. }));
+
+        let err = client
+            .tighten_ratelimit("osu", Duration::from_secs(1))
+            .unwrap_err();
+
+        assert!(matches!(err, RatelimitError::UnknownSite));
+    }
+}
@@ -36,6 +36,13 @@ impl Multipart {
         self
     }
 
+    pub fn push_bytes<K: Display>(mut self, key: K, filename: &str, bytes: &[u8]) -> Self {
+        self.write_field_headers(key, Some(filename), Some("application/octet-stream"));
+        self.bytes.extend_from_slice(bytes);
+
+        self
+    }
+
     pub async fn push_file<K, P>(mut self, key: K, path: P) -> Result<Self>
     where
         K: Display,
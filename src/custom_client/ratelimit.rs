@@ -0,0 +1,95 @@
+use std::sync::{Arc, RwLock};
+
+use leaky_bucket_lite::LeakyBucket;
+use tokio::time::Duration;
+
+/// A site's ratelimiter whose bucket can be swapped out at runtime
+/// e.g. to react to the site tightening its limits.
+///
+/// Swapped buckets are not persisted so a restart resets them.
+pub(super) struct SiteRatelimiter {
+    per_second: u32,
+    inner: RwLock<RatelimiterInner>,
+}
+
+struct RatelimiterInner {
+    bucket: Arc<LeakyBucket>,
+    tightened: bool,
+}
+
+/// Snapshot of a site's ratelimiter
+pub struct RatelimitInfo {
+    pub site: &'static str,
+    pub tokens: u32,
+    pub max: u32,
+    pub refill_interval: Duration,
+    /// Refill interval of the site's regular ratelimiter
+    pub default_interval: Duration,
+    pub tightened: bool,
+}
+
+impl SiteRatelimiter {
+    pub(super) fn new(per_second: u32) -> Self {
+        let bucket = LeakyBucket::builder()
+            .max(per_second)
+            .tokens(per_second)
+            .refill_interval(Duration::from_millis(1000 / per_second as u64))
+            .refill_amount(1)
+            .build();
+
+        let inner = RatelimiterInner {
+            bucket: Arc::new(bucket),
+            tightened: false,
+        };
+
+        Self {
+            per_second,
+            inner: RwLock::new(inner),
+        }
+    }
+
+    pub(super) async fn acquire(&self) {
+        // Don't hold the lock while waiting so that the bucket can be swapped meanwhile
+        let bucket = Arc::clone(&self.inner.read().unwrap().bucket);
+
+        bucket.acquire_one().await
+    }
+
+    pub(super) fn default_interval(&self) -> Duration {
+        Duration::from_millis(1000 / self.per_second as u64)
+    }
+
+    /// Only allow a single request per `interval`
+    pub(super) fn tighten(&self, interval: Duration) {
+        let bucket = LeakyBucket::builder()
+            .max(1)
+            .tokens(1)
+            .refill_interval(interval)
+            .refill_amount(1)
+            .build();
+
+        let mut inner = self.inner.write().unwrap();
+        inner.bucket = Arc::new(bucket);
+        inner.tightened = true;
+    }
+
+    /// Swap back to the regular bucket
+    pub(super) fn reset(&self) {
+        let SiteRatelimiter { inner, .. } = Self::new(self.per_second);
+
+        *self.inner.write().unwrap() = inner.into_inner().unwrap();
+    }
+
+    pub(super) fn info(&self, site: &'static str) -> RatelimitInfo {
+        let inner = self.inner.read().unwrap();
+
+        RatelimitInfo {
+            site,
+            tokens: inner.bucket.tokens(),
+            max: inner.bucket.max(),
+            refill_interval: inner.bucket.refill_interval(),
+            default_interval: self.default_interval(),
+            tightened: inner.tightened,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use http::Uri;
+use serde::{Deserialize, Serialize};
+use twilight_model::{
+    channel::embed::Embed,
+    http::attachment::Attachment,
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+const WEBHOOK_HOSTS: [&str; 4] = [
+    "discord.com",
+    "discordapp.com",
+    "ptb.discord.com",
+    "canary.discord.com",
+];
+
+/// Whether the url is that of a Discord webhook including its token.
+///
+/// Only those are requested so that guilds can't make the bot send
+/// requests to arbitrary urls.
+pub fn is_webhook_url(url: &str) -> bool {
+    let uri = match url.parse::<Uri>() {
+        Ok(uri) => uri,
+        Err(_) => return false,
+    };
+
+    let valid_host = uri
+        .host()
+        .map_or(false, |host| WEBHOOK_HOSTS.contains(&host));
+
+    if uri.scheme_str() != Some("https") || !valid_host || uri.port().is_some() {
+        return false;
+    }
+
+    if uri.query().is_some() {
+        return false;
+    }
+
+    let mut segments = uri.path().trim_end_matches('/').split('/').skip(1);
+
+    matches!(
+        (segments.next(), segments.next(), segments.next(), segments.next(), segments.next()),
+        (Some("api"), Some("webhooks"), Some(id), Some(token), None)
+            if id.parse::<u64>().is_ok()
+                && !token.is_empty()
+                && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    )
+}
+
+/// Webhook as returned by Discord when requesting it through its url
+#[derive(Deserialize)]
+pub struct WebhookInfo {
+    pub name: Option<String>,
+    pub channel_id: Id<ChannelMarker>,
+    pub guild_id: Option<Id<GuildMarker>>,
+}
+
+/// The `payload_json` field of a webhook execution
+#[derive(Serialize)]
+pub(super) struct WebhookPayload<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<&'a str>,
+    pub embeds: &'a [Embed],
+    pub attachments: Vec<PayloadAttachment<'a>>,
+}
+
+#[derive(Serialize)]
+pub(super) struct PayloadAttachment<'a> {
+    pub id: u64,
+    pub filename: &'a str,
+}
+
+impl<'a> From<&'a Attachment> for PayloadAttachment<'a> {
+    #[inline]
+    fn from(attachment: &'a Attachment) -> Self {
+        Self {
+            id: attachment.id,
+            filename: &attachment.filename,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_webhook_url;
+
+    #[test]
+    fn accepts_webhook_urls() {
+        assert!(is_webhook_url(
+            "https://discord.com/api/webhooks/1018154809515323412/aBc-dEf_123"
+        ));
+        assert!(is_webhook_url(
+            "https://canary.discord.com/api/webhooks/1018154809515323412/token/"
+        ));
+    }
+
+    #[test]
+    fn rejects_other_urls() {
+        let urls = [
+            "http://discord.com/api/webhooks/1018154809515323412/token",
+            "https://discord.com.evil.com/api/webhooks/1018154809515323412/token",
+            "https://discord.com:8080/api/webhooks/1018154809515323412/token",
+            "https://discord.com/api/webhooks/1018154809515323412",
+            "https://discord.com/api/webhooks/abc/token",
+            "https://discord.com/api/webhooks/1018154809515323412/token/slack",
+            "https://discord.com/api/webhooks/1018154809515323412/token?wait=true",
+            "not a url",
+        ];
+
+        for url in urls {
+            assert!(!is_webhook_url(url), "{url}");
+        }
+    }
+}
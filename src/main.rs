@@ -19,7 +19,7 @@ use tokio::{runtime::Builder as RuntimeBuilder, signal};
 
 use crate::core::{
     commands::slash::{Command, Commands},
-    event_loop, logging, BotConfig, Context, ReplayQueue,
+    digest, event_loop, logging, metrics, BotConfig, Context, ReplayQueue,
 };
 
 fn main() {
@@ -80,6 +80,20 @@ async fn async_main() -> Result<()> {
 
     // Process the replay queue in the background
     ReplayQueue::process(Arc::clone(&ctx));
+    ReplayQueue::sweep(Arc::clone(&ctx));
+
+    let config = BotConfig::get();
+
+    if let Some(port) = config.metrics_port {
+        metrics::serve(Arc::clone(&ctx), port);
+    }
+
+    if let Some(schedule) = config.digest {
+        match config.digest_channel.or(config.error_channel) {
+            Some(channel) => digest::schedule(Arc::clone(&ctx), schedule, channel),
+            None => warn!("DIGEST is set without a channel to post it in"),
+        }
+    }
 
     tokio::select! {
         _ = event_loop(event_ctx, events) => error!("Event loop ended"),
@@ -11,7 +11,7 @@ use crate::{
     },
 };
 
-use super::Pages;
+use super::{Pages, Pagination};
 
 pub(super) async fn remove_components(
     ctx: &Context,
@@ -45,8 +45,7 @@ pub async fn handle_pagination_component(
                 component.defer(&ctx).await?;
             }
 
-            pagination.reset_timeout();
-            page_fn(&mut pagination.pages);
+            turn_page(pagination, page_fn);
 
             (pagination.build(&ctx).await, defer_components)
         } else {
@@ -63,6 +62,13 @@ pub async fn handle_pagination_component(
     Ok(())
 }
 
+/// Paginations are stored by their message id so the component's message
+/// id is the only thing determining which pagination is modified.
+fn turn_page(pagination: &mut Pagination, page_fn: fn(&mut Pages)) {
+    pagination.reset_timeout();
+    page_fn(&mut pagination.pages);
+}
+
 pub async fn handle_pagination_start(
     ctx: Arc<Context>,
     component: InteractionComponent,
@@ -219,3 +225,98 @@ pub async fn handle_profile_full(ctx: Arc<Context>, component: InteractionCompon
 
     handle_pagination_component(ctx, component, f).await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use flexmap::tokio::TokioMutexMap;
+    use tokio::sync::watch;
+    use twilight_model::{
+        application::component::Component,
+        id::{marker::MessageMarker, Id},
+    };
+
+    use crate::{
+        pagination::{ComponentKind, Pagination, PaginationKind, SkinListPagination},
+        util::hasher::IntBuildHasher,
+    };
+
+    use super::turn_page;
+
+    fn skin_list_pagination() -> Pagination {
+        let skins = (1..=50).map(|i| format!("skin {i}")).collect();
+        let builder = SkinListPagination::builder(skins);
+        let (tx, _) = watch::channel(());
+
+        Pagination {
+            author: Id::new(1),
+            component_kind: ComponentKind::Default,
+            defer_components: false,
+            disable_on_timeout: false,
+            grace_period: Duration::ZERO,
+            kind: builder.kind,
+            pages: builder.pages,
+            tx,
+        }
+    }
+
+    /// The description of the current page and the disabled state of each button
+    fn render(pagination: &mut Pagination) -> (String, Vec<bool>) {
+        let embed = match pagination.kind {
+            PaginationKind::SkinList(ref mut kind) => kind.build_page(&pagination.pages),
+            _ => unreachable!(),
+        };
+
+        let disabled = pagination
+            .pages
+            .components(pagination.component_kind)
+            .into_iter()
+            .flat_map(|component| match component {
+                Component::ActionRow(row) => row.components,
+                _ => Vec::new(),
+            })
+            .filter_map(|component| match component {
+                Component::Button(button) => Some(button.disabled),
+                _ => None,
+            })
+            .collect();
+
+        (embed.description.unwrap_or_default(), disabled)
+    }
+
+    #[tokio::test]
+    async fn concurrent_paginations() {
+        let paginations: TokioMutexMap<Id<MessageMarker>, Pagination, IntBuildHasher> =
+            TokioMutexMap::with_shard_amount_and_hasher(16, IntBuildHasher);
+
+        let msg_a = Id::new(10);
+        let msg_b = Id::new(20);
+
+        paginations.own(msg_a).await.insert(skin_list_pagination());
+        paginations.own(msg_b).await.insert(skin_list_pagination());
+
+        {
+            let mut guard = paginations.lock(&msg_b).await;
+            let pagination = guard.get_mut().unwrap();
+            turn_page(pagination, |pages| pages.index = pages.last_index);
+        }
+
+        {
+            let mut guard = paginations.lock(&msg_a).await;
+            let pagination = guard.get_mut().unwrap();
+            turn_page(pagination, |pages| pages.index += pages.per_page);
+        }
+
+        let (description_a, disabled_a) = render(paginations.lock(&msg_a).await.get_mut().unwrap());
+        let (description_b, disabled_b) = render(paginations.lock(&msg_b).await.get_mut().unwrap());
+
+        assert!(description_a.starts_with("16) skin 16\n"));
+        assert!(description_a.ends_with("30) skin 30\n"));
+        assert_eq!(disabled_a, [false, false, false, false, false]);
+
+        assert!(description_b.starts_with("46) skin 46\n"));
+        assert!(description_b.ends_with("50) skin 50\n"));
+        assert_eq!(disabled_b, [false, false, false, true, true]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt::Write;
+
+use command_macros::pagination;
+use twilight_model::{
+    channel::embed::Embed,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::util::{
+    builder::{EmbedBuilder, FooterBuilder},
+    numbers::with_comma_int,
+};
+
+use super::Pages;
+
+pub struct GuildListEntry {
+    pub id: Id<GuildMarker>,
+    pub name: String,
+    pub members: Option<u64>,
+    pub has_output_channel: bool,
+}
+
+#[pagination(per_page = 10, entries = "guilds")]
+pub struct GuildListPagination {
+    guilds: Vec<GuildListEntry>,
+}
+
+impl GuildListPagination {
+    pub fn build_page(&mut self, pages: &Pages) -> Embed {
+        let mut description = String::with_capacity(512);
+
+        let guilds = self
+            .guilds
+            .iter()
+            .skip(pages.index)
+            .take(pages.per_page)
+            .zip(pages.index + 1..);
+
+        for (guild, idx) in guilds {
+            let members = guild
+                .members
+                .map_or_else(|| "?".to_owned(), |count| with_comma_int(count).to_string());
+
+            let output = if guild.has_output_channel {
+                "output channel set"
+            } else {
+                "no output channel"
+            };
+
+            let _ = writeln!(
+                description,
+                "**{idx}.** {name} (`{id}`)\n{members} members • {output}",
+                name = guild.name,
+                id = guild.id,
+            );
+        }
+
+        let page = pages.curr_page();
+        let last_page = pages.last_page();
+
+        let footer_text = format!("Page {page}/{last_page} • {} servers", self.guilds.len());
+
+        EmbedBuilder::new()
+            .title("Servers the bot is in")
+            .description(description)
+            .footer(FooterBuilder::new(footer_text))
+            .build()
+    }
+}
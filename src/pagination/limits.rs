@@ -0,0 +1,163 @@
+use twilight_model::channel::embed::Embed;
+
+// https://discord.com/developers/docs/resources/channel#embed-object-embed-limits
+const TITLE: usize = 256;
+const DESCRIPTION: usize = 4096;
+const FIELDS: usize = 25;
+const FIELD_NAME: usize = 256;
+const FIELD_VALUE: usize = 1024;
+const FOOTER_TEXT: usize = 2048;
+const AUTHOR_NAME: usize = 256;
+const TOTAL: usize = 6000;
+
+const ELLIPSIS: char = '…';
+
+/// Truncate the embed's texts so that it's guaranteed to be within Discord's limits.
+pub(super) fn fit_embed(embed: &mut Embed) {
+    if let Some(ref mut title) = embed.title {
+        truncate(title, TITLE);
+    }
+
+    if let Some(ref mut description) = embed.description {
+        truncate(description, DESCRIPTION);
+    }
+
+    embed.fields.truncate(FIELDS);
+
+    for field in embed.fields.iter_mut() {
+        truncate(&mut field.name, FIELD_NAME);
+        truncate(&mut field.value, FIELD_VALUE);
+    }
+
+    if let Some(ref mut footer) = embed.footer {
+        truncate(&mut footer.text, FOOTER_TEXT);
+    }
+
+    if let Some(ref mut author) = embed.author {
+        truncate(&mut author.name, AUTHOR_NAME);
+    }
+
+    let mut excess = total_len(embed).saturating_sub(TOTAL);
+
+    if excess == 0 {
+        return;
+    }
+
+    // The description is the most likely culprit so it's shortened first
+    if let Some(ref mut description) = embed.description {
+        let len = description.chars().count();
+        truncate(description, len.saturating_sub(excess).max(1));
+        excess = total_len(embed).saturating_sub(TOTAL);
+    }
+
+    while excess > 0 {
+        match embed.fields.pop() {
+            Some(_) => excess = total_len(embed).saturating_sub(TOTAL),
+            None => break,
+        }
+    }
+}
+
+/// Shorten the string to at most `max` chars, ending it with an ellipsis if necessary
+fn truncate(s: &mut String, max: usize) {
+    if let Some((idx, _)) = s.char_indices().nth(max) {
+        let end = s[..idx]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(idx, _)| idx);
+
+        s.truncate(end);
+        s.push(ELLIPSIS);
+    }
+}
+
+fn total_len(embed: &Embed) -> usize {
+    let len = |s: &str| s.chars().count();
+
+    let fields: usize = embed
+        .fields
+        .iter()
+        .map(|field| len(&field.name) + len(&field.value))
+        .sum();
+
+    embed.title.as_deref().map_or(0, len)
+        + embed.description.as_deref().map_or(0, len)
+        + fields
+        + embed.footer.as_ref().map_or(0, |footer| len(&footer.text))
+        + embed.author.as_ref().map_or(0, |author| len(&author.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::channel::embed::EmbedField;
+
+    use crate::{
+        pagination::{Pages, PaginationKind, SkinListPagination},
+        util::builder::EmbedBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn truncate_keeps_char_boundaries() {
+        let mut s = "äöü".repeat(10);
+        truncate(&mut s, 5);
+
+        assert_eq!(s, "äöüä…");
+    }
+
+    #[test]
+    fn truncate_short_untouched() {
+        let mut s = "skin".to_owned();
+        truncate(&mut s, 4);
+
+        assert_eq!(s, "skin");
+    }
+
+    #[test]
+    fn long_skin_names() {
+        let skins = (0..15)
+            .map(|i| format!("{i}{}", "ö".repeat(2000)))
+            .collect();
+        let builder = SkinListPagination::builder(skins);
+        let pages = Pages::new(15, 15);
+
+        let mut embed = match builder.kind {
+            PaginationKind::SkinList(mut kind) => kind.build_page(&pages),
+            _ => unreachable!(),
+        };
+
+        fit_embed(&mut embed);
+
+        let description = embed.description.as_deref().unwrap();
+
+        assert!(description.chars().count() <= DESCRIPTION);
+        assert!(description.ends_with(ELLIPSIS));
+        assert!(total_len(&embed) <= TOTAL);
+    }
+
+    #[test]
+    fn too_many_long_fields() {
+        let field = EmbedField {
+            inline: false,
+            name: "n".repeat(1000),
+            value: "v".repeat(5000),
+        };
+
+        let mut embed = EmbedBuilder::new()
+            .title("t".repeat(1000))
+            .description("d".repeat(5000))
+            .fields(vec![field; 30])
+            .build();
+
+        fit_embed(&mut embed);
+
+        assert_eq!(embed.title.map(|title| title.chars().count()), Some(TITLE));
+        assert!(embed.fields.len() <= FIELDS);
+        assert!(embed
+            .fields
+            .iter()
+            .all(|field| field.value.chars().count() <= FIELD_VALUE));
+        assert!(total_len(&embed) <= TOTAL);
+    }
+}
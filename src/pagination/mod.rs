@@ -22,21 +22,36 @@ use crate::{
     },
 };
 
-pub use self::skin_list::*;
+pub use self::{guild_list::*, recent_renders::*, skin_list::*};
 
+mod guild_list;
+mod limits;
+mod recent_renders;
 mod skin_list;
 
 pub mod components;
 
 pub enum PaginationKind {
+    GuildList(Box<GuildListPagination>),
+    RecentRenders(Box<RecentRendersPagination>),
     SkinList(Box<SkinListPagination>),
+    /// Pre-built embeds, one per page
+    Static(Vec<Embed>),
 }
 
 impl PaginationKind {
     async fn build_page(&mut self, _ctx: &Context, pages: &Pages) -> Result<Embed> {
-        match self {
-            Self::SkinList(kind) => Ok(kind.build_page(pages)),
-        }
+        let mut embed = match self {
+            Self::GuildList(kind) => kind.build_page(pages),
+            Self::RecentRenders(kind) => kind.build_page(pages),
+            Self::SkinList(kind) => kind.build_page(pages),
+            Self::Static(embeds) => embeds[pages.index / pages.per_page].clone(),
+        };
+
+        // Long names must not prevent the page from being sent
+        limits::fit_embed(&mut embed);
+
+        Ok(embed)
     }
 }
 
@@ -46,6 +61,8 @@ pub struct Pagination {
     author: Id<UserMarker>,
     kind: PaginationKind,
     component_kind: ComponentKind,
+    disable_on_timeout: bool,
+    grace_period: Duration,
     tx: Sender<()>,
 }
 
@@ -63,6 +80,9 @@ impl Pagination {
             start_by_callback,
             defer_components,
             component_kind,
+            disable_on_timeout,
+            timeout,
+            grace_period,
         } = builder;
 
         let embed = kind.build_page(&ctx, &pages).await?;
@@ -95,12 +115,14 @@ impl Pagination {
         let msg = response.id;
 
         let (tx, rx) = watch::channel(());
-        Self::spawn_timeout(Arc::clone(&ctx), rx, msg, channel);
+        Self::spawn_timeout(Arc::clone(&ctx), rx, msg, channel, timeout);
 
         let pagination = Pagination {
             author: command.user_id()?,
             component_kind,
             defer_components,
+            disable_on_timeout,
+            grace_period,
             kind,
             pages,
             tx,
@@ -135,22 +157,17 @@ impl Pagination {
         mut rx: Receiver<()>,
         msg: Id<MessageMarker>,
         channel: Id<ChannelMarker>,
+        timeout: Duration,
     ) {
-        static MINUTE: Duration = Duration::from_secs(60);
-
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     res = rx.changed() => if res.is_ok() { continue } else { return },
-                    _ = sleep(MINUTE) => {
-                        let pagination_active = ctx.paginations.lock(&msg).await.remove().is_some();
-
-                        if pagination_active  {
-                            let builder = MessageBuilder::new().components(Vec::new());
+                    _ = sleep(timeout) => {
+                        let pagination = ctx.paginations.lock(&msg).await.remove();
 
-                            if let Err(err) = (msg, channel).update(&ctx, &builder).await {
-                                warn!("failed to remove components: {err:?}");
-                            }
+                        if let Some(pagination) = pagination {
+                            pagination.expire(&ctx, msg, channel).await;
                         }
 
                         return;
@@ -159,6 +176,39 @@ impl Pagination {
             }
         });
     }
+
+    /// Disable or remove the components once the pagination timed out.
+    ///
+    /// With a grace period, the components are disabled first
+    /// and only removed after the grace period passed.
+    async fn expire(self, ctx: &Context, msg: Id<MessageMarker>, channel: Id<ChannelMarker>) {
+        let grace_period = Some(self.grace_period)
+            .filter(|grace_period| !grace_period.is_zero() && !self.disable_on_timeout);
+
+        let components = if self.disable_on_timeout || grace_period.is_some() {
+            self.pages.disabled_components(self.component_kind)
+        } else {
+            Vec::new()
+        };
+
+        let builder = MessageBuilder::new().components(components);
+
+        if let Err(err) = (msg, channel).update(ctx, &builder).await {
+            warn!("failed to update components: {err:?}");
+
+            return;
+        }
+
+        if let Some(grace_period) = grace_period {
+            sleep(grace_period).await;
+
+            let builder = MessageBuilder::new().components(Vec::new());
+
+            if let Err(err) = (msg, channel).update(ctx, &builder).await {
+                warn!("failed to remove components: {err:?}");
+            }
+        }
+    }
 }
 
 pub struct PaginationBuilder {
@@ -169,10 +219,19 @@ pub struct PaginationBuilder {
     start_by_callback: bool,
     defer_components: bool,
     component_kind: ComponentKind,
+    disable_on_timeout: bool,
+    timeout: Duration,
+    grace_period: Duration,
 }
 
 impl PaginationBuilder {
-    fn new(kind: PaginationKind, pages: Pages) -> Self {
+    /// Time of inactivity after which the components stop working
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Time for which timed out components are shown disabled before they're removed
+    const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+    pub fn new(kind: PaginationKind, pages: Pages) -> Self {
         Self {
             kind,
             pages,
@@ -181,9 +240,27 @@ impl PaginationBuilder {
             start_by_callback: true,
             defer_components: false,
             component_kind: ComponentKind::Default,
+            disable_on_timeout: false,
+            timeout: Self::DEFAULT_TIMEOUT,
+            grace_period: Self::DEFAULT_GRACE_PERIOD,
         }
     }
 
+    #[allow(unused)]
+    /// Paginate embeds that were built upfront, showing one per page.
+    ///
+    /// Panics if `embeds` is empty.
+    pub fn static_embeds(embeds: Vec<Embed>) -> Self {
+        assert!(
+            !embeds.is_empty(),
+            "static pagination requires at least one embed"
+        );
+
+        let pages = Pages::new(1, embeds.len());
+
+        Self::new(PaginationKind::Static(embeds), pages)
+    }
+
     /// Start the pagination
     pub async fn start(self, ctx: Arc<Context>, command: InteractionCommand) -> Result<()> {
         Pagination::start(ctx, command, self).await
@@ -232,6 +309,57 @@ impl PaginationBuilder {
 
         self
     }
+
+    #[allow(unused)]
+    /// By default, components will be removed after the grace period.
+    ///
+    /// If this method is called, the components will stay but be disabled instead.
+    pub fn disable_on_timeout(mut self) -> Self {
+        self.disable_on_timeout = true;
+
+        self
+    }
+
+    #[allow(unused)]
+    /// By default, the pagination times out after a minute without interaction.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    #[allow(unused)]
+    /// By default, components will be disabled when the pagination times out
+    /// and removed five minutes later.
+    ///
+    /// A grace period of zero removes them right away.
+    /// Has no effect alongside [`PaginationBuilder::disable_on_timeout`].
+    pub fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+
+        self
+    }
+}
+
+/// Options shared by all paginations, see `Context::paginate`
+#[derive(Default)]
+pub struct PaginationOptions {
+    /// Attachment of the initial message that sticks throughout all pages
+    pub attachment: Option<(String, Vec<u8>)>,
+    /// Content of the initial message that sticks throughout all pages
+    pub content: Option<String>,
+    /// Whether the command was deferred already so
+    /// the initial message must be sent by updating
+    pub deferred: bool,
+    /// Whether page updates should be deferred because they're slow to build
+    pub defer_components: bool,
+    /// Whether components are disabled instead of removed on timeout
+    pub disable_on_timeout: bool,
+    /// Inactivity after which the pagination times out, defaults to a minute
+    pub timeout: Option<Duration>,
+    /// Time for which components stay disabled before they're removed on timeout,
+    /// defaults to five minutes
+    pub grace_period: Option<Duration>,
 }
 
 #[derive(Clone, Debug)]
@@ -267,6 +395,23 @@ impl Pages {
         }
     }
 
+    /// Same as the regular components but with all buttons disabled
+    fn disabled_components(&self, kind: ComponentKind) -> Vec<Component> {
+        let mut components = self.components(kind);
+
+        for component in components.iter_mut() {
+            if let Component::ActionRow(row) = component {
+                for component in row.components.iter_mut() {
+                    if let Component::Button(button) = component {
+                        button.disabled = true;
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
     fn default_components(&self) -> Vec<Component> {
         if self.last_index == 0 {
             return Vec::new();
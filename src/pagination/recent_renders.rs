@@ -0,0 +1,63 @@
+use std::fmt::Write;
+
+use command_macros::pagination;
+use twilight_model::channel::embed::Embed;
+
+use crate::{
+    core::RenderRecord,
+    util::builder::{EmbedBuilder, FooterBuilder},
+};
+
+use super::Pages;
+
+#[pagination(per_page = 5, entries = "renders")]
+pub struct RecentRendersPagination {
+    renders: Vec<RenderRecord>,
+}
+
+impl RecentRendersPagination {
+    pub fn build_page(&mut self, pages: &Pages) -> Embed {
+        let mut description = String::with_capacity(512);
+
+        let renders = self
+            .renders
+            .iter()
+            .skip(pages.index)
+            .take(pages.per_page)
+            .zip(pages.index + 1..);
+
+        for (render, idx) in renders {
+            let _ = writeln!(
+                description,
+                "**{idx}.** [{map}]({link})\n<@{user}> • <t:{timestamp}:R>",
+                map = render.map,
+                link = render.link,
+                user = render.user,
+                timestamp = render.completed_at,
+            );
+        }
+
+        // Show the cover of the page's first render
+        let mapset_id = self
+            .renders
+            .get(pages.index)
+            .and_then(|render| render.mapset_id);
+
+        let page = pages.curr_page();
+        let pages = pages.last_page();
+
+        let footer_text = format!("Page {page}/{pages}");
+
+        let mut embed = EmbedBuilder::new()
+            .title("Recent renders in this server")
+            .description(description)
+            .footer(FooterBuilder::new(footer_text));
+
+        if let Some(mapset_id) = mapset_id {
+            let url = format!("https://assets.ppy.sh/beatmaps/{mapset_id}/covers/list.jpg");
+            embed = embed.thumbnail(url);
+        }
+
+        embed.build()
+    }
+}
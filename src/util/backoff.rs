@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+/// Iterator over exponentially growing delays, starting at `factor` milliseconds
+/// and multiplying by `base` on each step until `max_delay` is reached.
+#[derive(Clone, Debug)]
+pub struct ExponentialBackoff {
+    current: u64,
+    base: u64,
+    factor: u64,
+    max_delay: Option<u64>,
+}
+
+impl ExponentialBackoff {
+    pub fn new(base: u64) -> Self {
+        Self {
+            current: 1,
+            base,
+            factor: 1,
+            max_delay: None,
+        }
+    }
+
+    /// Milliseconds by which each delay is multiplied
+    pub fn factor(mut self, factor: u64) -> Self {
+        self.factor = factor;
+
+        self
+    }
+
+    /// Upper bound for delays in milliseconds
+    pub fn max_delay(mut self, max_delay: u64) -> Self {
+        self.max_delay = Some(max_delay);
+
+        self
+    }
+}
+
+impl Iterator for ExponentialBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut delay = self.current.saturating_mul(self.factor);
+
+        if let Some(max_delay) = self.max_delay {
+            delay = delay.min(max_delay);
+        }
+
+        self.current = self.current.saturating_mul(self.base);
+
+        Some(Duration::from_millis(delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_exponentially() {
+        let delays: Vec<_> = ExponentialBackoff::new(2).factor(500).take(4).collect();
+
+        let expected = [500, 1000, 2000, 4000].map(Duration::from_millis);
+        assert_eq!(delays, expected);
+    }
+
+    #[test]
+    fn capped_by_max_delay() {
+        let mut backoff = ExponentialBackoff::new(10).factor(100).max_delay(5000);
+
+        assert_eq!(backoff.nth(3), Some(Duration::from_millis(5000)));
+        assert_eq!(backoff.next(), Some(Duration::from_millis(5000)));
+    }
+
+    #[test]
+    fn no_overflow() {
+        let mut backoff = ExponentialBackoff::new(u64::MAX).factor(u64::MAX);
+
+        assert!(backoff.nth(5).is_some());
+    }
+}
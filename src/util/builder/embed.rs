@@ -9,11 +9,15 @@ use crate::util::constants::BEIGE;
 use super::footer::IntoEmbedFooter;
 
 #[derive(Clone)]
-pub struct EmbedBuilder(Embed);
+pub struct EmbedBuilder {
+    embed: Embed,
+    /// Whether the embed should show fewer details, see [`EmbedBuilder::detail`]
+    compact: bool,
+}
 
 impl Default for EmbedBuilder {
     fn default() -> Self {
-        Self(Embed {
+        let embed = Embed {
             author: None,
             color: Some(BEIGE),
             description: None,
@@ -27,7 +31,12 @@ impl Default for EmbedBuilder {
             title: None,
             url: None,
             video: None,
-        })
+        };
+
+        Self {
+            embed,
+            compact: false,
+        }
     }
 }
 
@@ -37,39 +46,59 @@ impl EmbedBuilder {
     }
 
     pub fn build(mut self) -> Embed {
-        self.0.kind.push_str("rich");
+        self.embed.kind.push_str("rich");
 
-        self.0
+        self.embed
     }
 
     #[allow(unused)]
     pub fn author(mut self, author: impl Into<EmbedAuthor>) -> Self {
-        self.0.author = Some(author.into());
+        self.embed.author = Some(author.into());
+
+        self
+    }
+
+    /// Keep the embed short for better readability on mobile
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
 
         self
     }
 
+    pub fn is_compact(&self) -> bool {
+        self.compact
+    }
+
+    /// Content for the embed, `compact` only if the embed should be kept short
+    pub fn detail<T>(&self, full: impl FnOnce() -> T, compact: impl FnOnce() -> T) -> T {
+        if self.compact {
+            compact()
+        } else {
+            full()
+        }
+    }
+
     pub fn color(mut self, color: u32) -> Self {
-        self.0.color = Some(color);
+        self.embed.color = Some(color);
 
         self
     }
 
     pub fn description(mut self, description: impl Into<String>) -> Self {
         let description = description.into();
-        self.0.description = Some(description);
+        self.embed.description = Some(description);
 
         self
     }
 
     pub fn fields(mut self, fields: Vec<EmbedField>) -> Self {
-        self.0.fields = fields;
+        self.embed.fields = fields;
 
         self
     }
 
     pub fn footer(mut self, footer: impl IntoEmbedFooter) -> Self {
-        self.0.footer = Some(footer.into());
+        self.embed.footer = Some(footer.into());
 
         self
     }
@@ -86,20 +115,20 @@ impl EmbedBuilder {
                 url,
             };
 
-            self.0.image = Some(image);
+            self.embed.image = Some(image);
         }
 
         self
     }
 
     pub fn timestamp(mut self, timestamp: OffsetDateTime) -> Self {
-        self.0.timestamp = Timestamp::from_secs(timestamp.unix_timestamp() as i64).ok();
+        self.embed.timestamp = Timestamp::from_secs(timestamp.unix_timestamp() as i64).ok();
 
         self
     }
 
     pub fn title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = Some(title.into());
+        self.embed.title = Some(title.into());
 
         self
     }
@@ -116,7 +145,7 @@ impl EmbedBuilder {
                 url,
             };
 
-            self.0.thumbnail = Some(thumbnail);
+            self.embed.thumbnail = Some(thumbnail);
         }
 
         self
@@ -124,7 +153,7 @@ impl EmbedBuilder {
 
     #[allow(unused)]
     pub fn url(mut self, url: impl Into<String>) -> Self {
-        self.0.url = Some(url.into());
+        self.embed.url = Some(url.into());
 
         self
     }
@@ -65,7 +65,6 @@ impl ModalBuilder {
         self
     }
 
-    #[allow(unused)]
     /// Use this as default input. Renders the placeholder useless.
     pub fn value(mut self, value: impl Into<String>) -> Self {
         self.input.value = Some(value.into());
@@ -4,6 +4,8 @@ pub const RED: u32 = 0xE74C3C;
 
 // Error messages
 pub const GENERAL_ISSUE: &str = "Something went wrong, blame mezo";
+pub const GLOBAL_RATELIMIT: &str =
+    "The bot is currently ratelimited by discord, please retry shortly";
 
 // Misc
 pub const INVITE_LINK: &str = "https://discord.com/api/oauth2/authorize?client_id=867681950818893835&permissions=117824&scope=bot";
@@ -0,0 +1,30 @@
+use crate::core::BotConfig;
+
+/// Checks whether the free disk space of the folders path is below the configured threshold.
+///
+/// Returns the remaining megabytes if the space is low. If the space
+/// could not be determined, renders are not blocked and `None` is returned.
+pub fn low_disk_space() -> Option<u64> {
+    let config = BotConfig::get();
+    let path = config.paths.folders();
+
+    let available = match fs2::available_space(path) {
+        Ok(bytes) => bytes / 1024 / 1024,
+        Err(err) => {
+            warn!("failed to check available disk space at {path:?}: {err}");
+
+            return None;
+        }
+    };
+
+    if available < config.min_free_space {
+        warn!(
+            "only {available}MB of disk space left at {path:?}, threshold is {}MB",
+            config.min_free_space
+        );
+
+        Some(available)
+    } else {
+        None
+    }
+}
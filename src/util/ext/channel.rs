@@ -10,7 +10,7 @@ use crate::{
     core::Context,
     util::{
         builder::{EmbedBuilder, MessageBuilder},
-        constants::RED,
+        constants::{GLOBAL_RATELIMIT, RED},
     },
 };
 
@@ -27,6 +27,12 @@ pub trait ChannelExt {
 
     /// Create a message without embed; only content
     fn plain_message(&self, ctx: &Context, content: &str) -> ResponseFuture<Message>;
+
+    /// Let the user know to retry after the bot is no longer ratelimited globally
+    #[inline]
+    fn ratelimited(&self, ctx: &Context) -> ResponseFuture<Message> {
+        self.error(ctx, GLOBAL_RATELIMIT)
+    }
 }
 
 impl ChannelExt for Id<ChannelMarker> {
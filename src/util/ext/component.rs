@@ -2,15 +2,18 @@ use std::borrow::Cow;
 
 use twilight_http::response::{marker::EmptyBody, ResponseFuture};
 use twilight_model::{
-    channel::Message,
+    channel::{message::MessageFlags, Message},
     http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
 };
 
 use crate::{
     core::Context,
     util::{
-        builder::{MessageBuilder, ModalBuilder},
+        builder::{EmbedBuilder, MessageBuilder, ModalBuilder},
+        constants::{GLOBAL_RATELIMIT, RED},
         interaction::InteractionComponent,
+        ratelimit::ratelimit_followup,
+        RatelimitFuture,
     },
 };
 
@@ -30,6 +33,20 @@ pub trait ComponentExt {
 
     /// Acknowledge a component by responding with a modal.
     fn modal(&self, ctx: &Context, modal: ModalBuilder) -> ResponseFuture<EmptyBody>;
+
+    /// Ackowledge the component and respond with a red embed in
+    /// a new message that only the user can see.
+    fn error_callback(
+        &self,
+        ctx: &Context,
+        content: impl Into<String>,
+    ) -> ResponseFuture<EmptyBody>;
+
+    /// Let the user know that the component failed because of a global ratelimit.
+    ///
+    /// Whether the component was acknowledged already is unknown at this point
+    /// so a followup message is sent if no response can be created.
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a>;
 }
 
 impl ComponentExt for InteractionComponent {
@@ -80,4 +97,39 @@ impl ComponentExt for InteractionComponent {
             .create_response(self.id, &self.token, &response)
             .exec()
     }
+
+    #[inline]
+    fn error_callback(
+        &self,
+        ctx: &Context,
+        content: impl Into<String>,
+    ) -> ResponseFuture<EmptyBody> {
+        let embed = EmbedBuilder::new().description(content).color(RED).build();
+
+        let data = InteractionResponseData {
+            embeds: Some(vec![embed]),
+            flags: Some(MessageFlags::EPHEMERAL),
+            ..Default::default()
+        };
+
+        let response = InteractionResponse {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(data),
+        };
+
+        ctx.interaction()
+            .create_response(self.id, &self.token, &response)
+            .exec()
+    }
+
+    #[inline]
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a> {
+        Box::pin(async move {
+            if self.error_callback(ctx, GLOBAL_RATELIMIT).await.is_err() {
+                ratelimit_followup(ctx, &self.token).await?;
+            }
+
+            Ok(())
+        })
+    }
 }
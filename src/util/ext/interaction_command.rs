@@ -12,8 +12,9 @@ use crate::{
     core::Context,
     util::{
         builder::{EmbedBuilder, MessageBuilder},
-        constants::RED,
+        constants::{GLOBAL_RATELIMIT, RED},
         interaction::InteractionCommand,
+        RatelimitFuture,
     },
 };
 
@@ -60,6 +61,12 @@ pub trait InteractionCommandExt {
         ctx: &Context,
         choices: Vec<CommandOptionChoice>,
     ) -> ResponseFuture<EmptyBody>;
+
+    /// Let the user know that the command failed because of a global ratelimit.
+    ///
+    /// Whether the command was acknowledged already is unknown at this point
+    /// so the response is updated if it can't be created.
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a>;
 }
 
 impl InteractionCommandExt for InteractionCommand {
@@ -192,4 +199,19 @@ impl InteractionCommandExt for InteractionCommand {
             .create_response(self.id, &self.token, &response)
             .exec()
     }
+
+    #[inline]
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a> {
+        Box::pin(async move {
+            if self
+                .error_callback(ctx, GLOBAL_RATELIMIT, true)
+                .await
+                .is_err()
+            {
+                self.error(ctx, GLOBAL_RATELIMIT).await?;
+            }
+
+            Ok(())
+        })
+    }
 }
@@ -1,12 +1,18 @@
 use twilight_http::response::{marker::EmptyBody, ResponseFuture};
 use twilight_model::{
-    channel::Message,
+    channel::{message::MessageFlags, Message},
     http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
 };
 
 use crate::{
     core::Context,
-    util::{builder::MessageBuilder, interaction::InteractionModal},
+    util::{
+        builder::{EmbedBuilder, MessageBuilder},
+        constants::{GLOBAL_RATELIMIT, RED},
+        interaction::InteractionModal,
+        ratelimit::ratelimit_followup,
+        RatelimitFuture,
+    },
 };
 
 use super::MessageExt;
@@ -24,6 +30,12 @@ pub trait ModalExt {
     ///
     /// Note: Can only be used if `ModalSubmitInteraction::message` is `Some`.
     fn update(&self, ctx: &Context, builder: &MessageBuilder<'_>) -> ResponseFuture<Message>;
+
+    /// Let the user know that the modal failed because of a global ratelimit.
+    ///
+    /// Whether the modal was acknowledged already is unknown at this point
+    /// so a followup message is sent if no response can be created.
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a>;
 }
 
 impl ModalExt for InteractionModal {
@@ -64,4 +76,37 @@ impl ModalExt for InteractionModal {
             .expect("no message in modal")
             .update(ctx, builder)
     }
+
+    #[inline]
+    fn ratelimited<'a>(&'a self, ctx: &'a Context) -> RatelimitFuture<'a> {
+        Box::pin(async move {
+            let embed = EmbedBuilder::new()
+                .description(GLOBAL_RATELIMIT)
+                .color(RED)
+                .build();
+
+            let data = InteractionResponseData {
+                embeds: Some(vec![embed]),
+                flags: Some(MessageFlags::EPHEMERAL),
+                ..Default::default()
+            };
+
+            let response = InteractionResponse {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(data),
+            };
+
+            let res = ctx
+                .interaction()
+                .create_response(self.id, &self.token, &response)
+                .exec()
+                .await;
+
+            if res.is_err() {
+                ratelimit_followup(ctx, &self.token).await?;
+            }
+
+            Ok(())
+        })
+    }
 }
@@ -15,7 +15,7 @@ use twilight_model::{
 
 use super::Authored;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct InteractionCommand {
     pub permissions: Option<Permissions>,
     pub channel_id: Id<ChannelMarker>,
@@ -27,7 +27,7 @@ pub struct InteractionCommand {
     pub user: Option<User>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct InteractionComponent {
     pub permissions: Option<Permissions>,
     pub channel_id: Id<ChannelMarker>,
@@ -40,7 +40,7 @@ pub struct InteractionComponent {
     pub user: Option<User>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct InteractionModal {
     pub permissions: Option<Permissions>,
     pub channel_id: Id<ChannelMarker>,
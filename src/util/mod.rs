@@ -1,16 +1,26 @@
 use std::mem;
 
-pub use self::{cow::CowUtils, ext::*};
+pub use self::{
+    backoff::ExponentialBackoff,
+    cow::CowUtils,
+    ext::*,
+    mods::{mods_string, parse_mods},
+    ratelimit::{global_ratelimit, RatelimitFuture},
+};
 
 pub mod builder;
 pub mod constants;
 pub mod datetime;
+pub mod disk;
 pub mod hasher;
 pub mod interaction;
 pub mod numbers;
 
+mod backoff;
 mod cow;
 mod ext;
+mod mods;
+mod ratelimit;
 
 macro_rules! get {
     ($slice:ident[$idx:expr]) => {
@@ -24,12 +34,6 @@ macro_rules! set {
     };
 }
 
-pub fn levenshtein_similarity(word_a: &str, word_b: &str) -> f32 {
-    let (dist, len) = levenshtein_distance(word_a, word_b);
-
-    (len - dist) as f32 / len as f32
-}
-
 /// "How many replace/delete/insert operations are necessary to morph one word into the other?"
 ///
 /// Returns (distance, max word length) tuple
@@ -0,0 +1,115 @@
+use rosu_v2::prelude::GameMods;
+
+/// Acronyms in the order in which they are usually displayed
+const ORDER: [(GameMods, &str); 14] = [
+    (GameMods::NoFail, "NF"),
+    (GameMods::Easy, "EZ"),
+    (GameMods::TouchDevice, "TD"),
+    (GameMods::Hidden, "HD"),
+    (GameMods::HardRock, "HR"),
+    (GameMods::Perfect, "PF"),
+    (GameMods::SuddenDeath, "SD"),
+    (GameMods::NightCore, "NC"),
+    (GameMods::DoubleTime, "DT"),
+    (GameMods::HalfTime, "HT"),
+    (GameMods::Flashlight, "FL"),
+    (GameMods::Relax, "RX"),
+    (GameMods::Autopilot, "AP"),
+    (GameMods::SpunOut, "SO"),
+];
+
+/// Turn the mod bits of a replay into a string such as `HDDT`.
+///
+/// NC and PF are displayed instead of the DT and SD they imply.
+pub fn mods_string(mods: u32) -> String {
+    let mut mods = GameMods::from_bits_truncate(mods);
+
+    if mods.contains(GameMods::NightCore) {
+        mods.remove(GameMods::DoubleTime);
+    }
+
+    if mods.contains(GameMods::Perfect) {
+        mods.remove(GameMods::SuddenDeath);
+    }
+
+    let mut s = String::with_capacity(8);
+
+    for (_, acronym) in ORDER.iter().filter(|(m, _)| mods.contains(*m)) {
+        s.push_str(acronym);
+    }
+
+    if s.is_empty() {
+        s.push_str("NM");
+    }
+
+    s
+}
+
+/// Parse a string of acronyms such as `HDDT` into mod bits.
+///
+/// `NM` or an empty string is nomod. Returns the first unknown acronym on failure.
+pub fn parse_mods(s: &str) -> Result<u32, String> {
+    let s = s.trim().to_uppercase();
+
+    if s.is_empty() || s == "NM" {
+        return Ok(0);
+    }
+
+    let mut mods = GameMods::empty();
+
+    for chunk in s.as_bytes().chunks(2) {
+        let acronym = String::from_utf8_lossy(chunk);
+
+        match ORDER.iter().find(|(_, name)| *name == acronym) {
+            Some((m, _)) => mods |= *m,
+            None => return Err(acronym.into_owned()),
+        }
+    }
+
+    Ok(mods.bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nomod() {
+        assert_eq!(mods_string(0), "NM");
+    }
+
+    #[test]
+    fn single() {
+        assert_eq!(mods_string(GameMods::Hidden.bits()), "HD");
+        assert_eq!(mods_string(GameMods::Flashlight.bits()), "FL");
+    }
+
+    #[test]
+    fn combined_in_order() {
+        let mods = GameMods::Flashlight | GameMods::DoubleTime | GameMods::Hidden;
+        assert_eq!(mods_string(mods.bits()), "HDDTFL");
+
+        let mods = GameMods::HardRock | GameMods::NoFail | GameMods::Easy;
+        assert_eq!(mods_string(mods.bits()), "NFEZHR");
+    }
+
+    #[test]
+    fn nightcore_over_doubletime() {
+        let mods = GameMods::NightCore | GameMods::DoubleTime | GameMods::Hidden;
+        assert_eq!(mods_string(mods.bits()), "HDNC");
+    }
+
+    #[test]
+    fn perfect_over_suddendeath() {
+        let mods = GameMods::Perfect | GameMods::SuddenDeath | GameMods::HardRock;
+        assert_eq!(mods_string(mods.bits()), "HRPF");
+    }
+
+    #[test]
+    fn parse_acronyms() {
+        let mods = GameMods::Hidden | GameMods::Flashlight;
+        assert_eq!(parse_mods("flhd"), Ok(mods.bits()));
+        assert_eq!(parse_mods(" NM "), Ok(0));
+        assert_eq!(parse_mods("HDXY"), Err("XY".to_owned()));
+    }
+}
@@ -0,0 +1,49 @@
+use std::{future::Future, pin::Pin, time::Duration};
+
+use eyre::Report;
+use twilight_http::{
+    api_error::ApiError,
+    error::{Error as HttpError, ErrorType},
+    response::ResponseFuture,
+};
+use twilight_model::channel::{message::MessageFlags, Message};
+
+use crate::{
+    core::Context,
+    util::{
+        builder::EmbedBuilder,
+        constants::{GLOBAL_RATELIMIT, RED},
+    },
+};
+
+/// Letting the user know that an interaction failed because of a global ratelimit
+pub type RatelimitFuture<'a> = Pin<Box<dyn Future<Output = Result<(), HttpError>> + Send + 'a>>;
+
+/// How long discord wants the bot to wait if the error stems from a global ratelimit
+pub fn global_ratelimit(err: &Report) -> Option<Duration> {
+    err.chain()
+        .filter_map(|err| err.downcast_ref::<HttpError>())
+        .find_map(|err| match err.kind() {
+            ErrorType::Response {
+                error: ApiError::Ratelimited(ratelimited),
+                ..
+            } if ratelimited.global => Some(Duration::from_secs_f64(ratelimited.retry_after)),
+            _ => None,
+        })
+}
+
+/// Red message about the global ratelimit that only the user can see
+/// for interactions that were acknowledged already.
+pub(super) fn ratelimit_followup(ctx: &Context, token: &str) -> ResponseFuture<Message> {
+    let embed = EmbedBuilder::new()
+        .description(GLOBAL_RATELIMIT)
+        .color(RED)
+        .build();
+
+    ctx.interaction()
+        .create_followup(token)
+        .flags(MessageFlags::EPHEMERAL)
+        .embeds(&[embed])
+        .expect("invalid embed")
+        .exec()
+}